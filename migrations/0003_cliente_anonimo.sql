@@ -0,0 +1,2 @@
+-- Clientes anônimos ainda não informaram CPF
+ALTER TABLE cliente ALTER COLUMN cpf DROP NOT NULL;
@@ -0,0 +1,2 @@
+-- Administrador inicial precisa trocar a senha no primeiro login
+ALTER TABLE usuario ADD COLUMN troca_senha_obrigatoria BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,8 @@
+-- CPF e e-mail únicos; a checagem do caso de uso sozinha não impede duas
+-- requisições simultâneas. Falha se já houver duplicados na base
+CREATE UNIQUE INDEX IF NOT EXISTS usuario_cpf_key ON usuario (cpf);
+CREATE UNIQUE INDEX IF NOT EXISTS usuario_email_key ON usuario (lower(email));
+
+-- Clientes anônimos têm cpf nulo e e-mail vazio
+CREATE UNIQUE INDEX IF NOT EXISTS cliente_cpf_key ON cliente (cpf);
+CREATE UNIQUE INDEX IF NOT EXISTS cliente_email_key ON cliente (lower(email)) WHERE email <> '';
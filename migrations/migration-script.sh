@@ -3,3 +3,6 @@
 # Your migration commands
 psql -U ${POSTGRES_USER} -d ${POSTGRES_DB} -a -f 0001_create_table.sql
 psql -U "$POSTGRES_USER" -d "$POSTGRES_DB" -a -f 0002_insert_basic.sql
+psql -U "$POSTGRES_USER" -d "$POSTGRES_DB" -a -f 0003_cliente_anonimo.sql
+psql -U "$POSTGRES_USER" -d "$POSTGRES_DB" -a -f 0004_troca_senha_obrigatoria.sql
+psql -U "$POSTGRES_USER" -d "$POSTGRES_DB" -a -f 0005_unique_cpf_email.sql
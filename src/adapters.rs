@@ -1 +1,7 @@
+pub mod argon2_password_hasher;
+pub mod in_memory_login_attempt_store;
+pub mod in_memory_token_revocation_store;
+pub mod in_memory_verification_code_store;
 pub mod jwt_authentication_adapter;
+pub mod jwt_keys;
+pub mod log_notifier;
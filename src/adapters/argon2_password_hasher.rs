@@ -0,0 +1,87 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString},
+    Argon2,
+};
+
+use crate::{base::domain_error::DomainError, traits::password_hasher::PasswordHasher};
+
+#[derive(Clone, Default)]
+pub struct Argon2PasswordHasher {
+    argon2: Argon2<'static>,
+}
+
+impl Argon2PasswordHasher {
+    pub fn new() -> Self {
+        Argon2PasswordHasher {
+            argon2: Argon2::default(),
+        }
+    }
+}
+
+impl PasswordHasher for Argon2PasswordHasher {
+    fn hash(&self, senha: &str) -> Result<String, DomainError> {
+        let salt = SaltString::generate(&mut OsRng);
+        match self.argon2.hash_password(senha.as_bytes(), &salt) {
+            Ok(hash) => Ok(hash.to_string()),
+            Err(_) => Err(DomainError::Internal("Erro ao gerar hash da senha".to_string())),
+        }
+    }
+
+    fn verify(&self, senha: &str, hash: &str) -> bool {
+        match PasswordHash::new(hash) {
+            Ok(parsed_hash) => self
+                .argon2
+                .verify_password(senha.as_bytes(), &parsed_hash)
+                .is_ok(),
+            // Senhas legadas armazenadas em texto puro
+            Err(_) => !hash.is_empty() && constant_time_eq(senha.as_bytes(), hash.as_bytes()),
+        }
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        match PasswordHash::new(hash) {
+            Ok(parsed_hash) => parsed_hash.algorithm != argon2::ARGON2ID_IDENT,
+            Err(_) => true,
+        }
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_hash_and_verify_senha() {
+        let hasher = Argon2PasswordHasher::new();
+        let hash = hasher.hash("senha_segura").unwrap();
+        assert_ne!(hash, "senha_segura");
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(hasher.verify("senha_segura", &hash));
+        assert!(!hasher.verify("senha_errada", &hash));
+        assert!(!hasher.needs_rehash(&hash));
+    }
+
+    #[test]
+    fn should_generate_distinct_salts() {
+        let hasher = Argon2PasswordHasher::new();
+        let first = hasher.hash("senha_segura").unwrap();
+        let second = hasher.hash("senha_segura").unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn should_verify_legacy_plaintext_senha() {
+        let hasher = Argon2PasswordHasher::new();
+        assert!(hasher.verify("melhor_projeto", "melhor_projeto"));
+        assert!(!hasher.verify("outra_senha", "melhor_projeto"));
+        assert!(!hasher.verify("", ""));
+        assert!(hasher.needs_rehash("melhor_projeto"));
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::Utc;
+
+use crate::{
+    base::domain_error::DomainError,
+    traits::login_attempt_store::{Lockout, LoginAttemptKey, LoginAttemptStore},
+};
+
+// Cada bloqueio seguido dobra o anterior, até um dia
+const MAX_BLOQUEIO: i64 = 24 * 60 * 60;
+
+#[derive(Clone, Debug)]
+pub struct LoginThrottleSettings {
+    pub max_falhas_conta: u32,
+    pub max_falhas_ip: u32,
+    pub max_anonimos_ip: u32,
+    pub bloqueio_ttl: i64,
+}
+
+#[derive(Default)]
+struct Tentativas {
+    falhas: u32,
+    ultima_falha: i64,
+    bloqueios: u32,
+    bloqueado_ate: i64,
+    pendentes: u32,
+    ultima_reserva: i64,
+}
+
+impl Tentativas {
+    fn vazia(&self) -> bool {
+        self.falhas == 0 && self.bloqueios == 0 && self.pendentes == 0
+    }
+}
+
+pub struct InMemoryLoginAttemptStore {
+    tentativas: Mutex<HashMap<LoginAttemptKey, Tentativas>>,
+    settings: LoginThrottleSettings,
+}
+
+impl InMemoryLoginAttemptStore {
+    pub fn new(settings: LoginThrottleSettings) -> Self {
+        InMemoryLoginAttemptStore {
+            tentativas: Mutex::new(HashMap::new()),
+            settings,
+        }
+    }
+
+    fn max_falhas(&self, key: &LoginAttemptKey) -> u32 {
+        match key {
+            LoginAttemptKey::Conta(_) => self.settings.max_falhas_conta,
+            LoginAttemptKey::Ip(_) => self.settings.max_falhas_ip,
+            LoginAttemptKey::Anonimo(_) => self.settings.max_anonimos_ip,
+        }
+    }
+
+    fn duracao_bloqueio(&self, bloqueios: u32) -> i64 {
+        let fator = 1i64 << bloqueios.saturating_sub(1).min(16);
+        self.settings.bloqueio_ttl.saturating_mul(fator).min(MAX_BLOQUEIO)
+    }
+}
+
+#[async_trait]
+impl LoginAttemptStore for InMemoryLoginAttemptStore {
+    async fn reserve(&self, key: LoginAttemptKey) -> Result<(), DomainError> {
+        let now = Utc::now().timestamp();
+        let max_falhas = self.max_falhas(&key);
+        let bloqueio_ttl = self.settings.bloqueio_ttl;
+        let mut tentativas = self.tentativas.lock().unwrap();
+        let entry = tentativas.entry(key).or_default();
+        if entry.bloqueado_ate > now {
+            return Err(DomainError::TooManyAttempts(entry.bloqueado_ate - now));
+        }
+        // Reservas de requisições interrompidas não bloqueiam a chave para sempre
+        if now - entry.ultima_reserva > bloqueio_ttl {
+            entry.pendentes = 0;
+        }
+        let falhas = if now - entry.ultima_falha > bloqueio_ttl { 0 } else { entry.falhas };
+        // Tentativas simultâneas contam como falhas até terminarem, senão todas
+        // passariam antes de a primeira falha ser registrada
+        if falhas + entry.pendentes >= max_falhas {
+            return Err(DomainError::TooManyAttempts(1));
+        }
+        entry.pendentes += 1;
+        entry.ultima_reserva = now;
+        Ok(())
+    }
+
+    async fn register_failure(&self, key: LoginAttemptKey) -> Result<(), DomainError> {
+        let now = Utc::now().timestamp();
+        let max_falhas = self.max_falhas(&key);
+        let mut tentativas = self.tentativas.lock().unwrap();
+        // Depois de um dia sem falhas a chave volta a começar do zero
+        tentativas.retain(|_, entry| {
+            entry.bloqueado_ate > now
+                || now - entry.ultima_falha <= MAX_BLOQUEIO
+                || now - entry.ultima_reserva <= self.settings.bloqueio_ttl
+        });
+        let entry = tentativas.entry(key).or_default();
+        entry.pendentes = entry.pendentes.saturating_sub(1);
+        // As falhas só se acumulam dentro da janela de um bloqueio
+        if now - entry.ultima_falha > self.settings.bloqueio_ttl {
+            entry.falhas = 0;
+        }
+        entry.falhas += 1;
+        entry.ultima_falha = now;
+        if entry.falhas >= max_falhas {
+            entry.falhas = 0;
+            entry.bloqueios += 1;
+            entry.bloqueado_ate = now + self.duracao_bloqueio(entry.bloqueios);
+        }
+        Ok(())
+    }
+
+    async fn register_success(&self, key: LoginAttemptKey) -> Result<(), DomainError> {
+        self.tentativas.lock().unwrap().remove(&key);
+        Ok(())
+    }
+
+    async fn release(&self, key: LoginAttemptKey) -> Result<(), DomainError> {
+        let mut tentativas = self.tentativas.lock().unwrap();
+        if let Some(entry) = tentativas.get_mut(&key) {
+            entry.pendentes = entry.pendentes.saturating_sub(1);
+            if entry.vazia() {
+                tentativas.remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_lockouts(&self) -> Result<Vec<Lockout>, DomainError> {
+        let now = Utc::now().timestamp();
+        let tentativas = self.tentativas.lock().unwrap();
+        let mut lockouts: Vec<Lockout> = tentativas
+            .iter()
+            .filter(|(_, entry)| entry.bloqueado_ate > now)
+            .map(|(key, entry)| Lockout {
+                chave: key.clone(),
+                bloqueios: entry.bloqueios,
+                bloqueado_ate: entry.bloqueado_ate,
+            })
+            .collect();
+        lockouts.sort_by_key(|lockout| lockout.bloqueado_ate);
+        Ok(lockouts)
+    }
+
+    async fn clear(&self, key: LoginAttemptKey) -> Result<(), DomainError> {
+        match self.tentativas.lock().unwrap().remove(&key) {
+            Some(_) => Ok(()),
+            None => Err(DomainError::NotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio;
+
+    fn store() -> InMemoryLoginAttemptStore {
+        InMemoryLoginAttemptStore::new(LoginThrottleSettings {
+            max_falhas_conta: 3,
+            max_falhas_ip: 5,
+            max_anonimos_ip: 2,
+            bloqueio_ttl: 60,
+        })
+    }
+
+    fn conta() -> LoginAttemptKey {
+        LoginAttemptKey::Conta("123.456.789-09".to_string())
+    }
+
+    // Simula o fim do bloqueio sem esperar o tempo real
+    fn expira_bloqueio(store: &InMemoryLoginAttemptStore, key: &LoginAttemptKey) {
+        let mut tentativas = store.tentativas.lock().unwrap();
+        tentativas.get_mut(key).unwrap().bloqueado_ate = Utc::now().timestamp() - 1;
+    }
+
+    #[tokio::test]
+    async fn should_lock_after_max_failures() {
+        let store = store();
+        for _ in 0..2 {
+            store.register_failure(conta()).await.unwrap();
+            assert!(store.reserve(conta()).await.is_ok());
+        }
+        store.register_failure(conta()).await.unwrap();
+        let result = store.reserve(conta()).await;
+        assert!(
+            matches!(result, Err(DomainError::TooManyAttempts(segundos)) if segundos > 0 && segundos <= 60),
+            "Esperado Err(DomainError::TooManyAttempts), obtido {:?}",
+            result
+        );
+        assert!(store.reserve(LoginAttemptKey::Ip("10.0.0.1".to_string())).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_use_separate_limit_for_ip() {
+        let store = store();
+        let ip = LoginAttemptKey::Ip("10.0.0.1".to_string());
+        for _ in 0..4 {
+            store.register_failure(ip.clone()).await.unwrap();
+        }
+        assert!(store.reserve(ip.clone()).await.is_ok());
+        store.register_failure(ip.clone()).await.unwrap();
+        assert!(store.reserve(ip).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_double_consecutive_lockouts() {
+        let store = store();
+        for _ in 0..3 {
+            store.register_failure(conta()).await.unwrap();
+        }
+        expira_bloqueio(&store, &conta());
+        for _ in 0..3 {
+            store.register_failure(conta()).await.unwrap();
+        }
+        let result = store.reserve(conta()).await;
+        assert!(
+            matches!(result, Err(DomainError::TooManyAttempts(segundos)) if segundos > 60 && segundos <= 120),
+            "Esperado bloqueio de 120 segundos, obtido {:?}",
+            result
+        );
+        assert_eq!(store.list_lockouts().await.unwrap()[0].bloqueios, 2);
+    }
+
+    #[tokio::test]
+    async fn should_reset_on_success() {
+        let store = store();
+        for _ in 0..2 {
+            store.register_failure(conta()).await.unwrap();
+        }
+        store.register_success(conta()).await.unwrap();
+        store.register_failure(conta()).await.unwrap();
+        assert!(store.reserve(conta()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_list_and_clear_lockouts() {
+        let store = store();
+        for _ in 0..3 {
+            store.register_failure(conta()).await.unwrap();
+        }
+        store.register_failure(LoginAttemptKey::Ip("10.0.0.1".to_string())).await.unwrap();
+
+        let lockouts = store.list_lockouts().await.unwrap();
+        assert_eq!(lockouts.len(), 1);
+        assert_eq!(lockouts[0].chave, conta());
+
+        store.clear(conta()).await.unwrap();
+        assert!(store.reserve(conta()).await.is_ok());
+        store.release(conta()).await.unwrap();
+        assert!(store.list_lockouts().await.unwrap().is_empty());
+        assert!(matches!(store.clear(conta()).await, Err(DomainError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn should_count_pending_attempts() {
+        let store = store();
+        for _ in 0..3 {
+            store.reserve(conta()).await.unwrap();
+        }
+        let result = store.reserve(conta()).await;
+        assert!(
+            matches!(result, Err(DomainError::TooManyAttempts(_))),
+            "Esperado Err(DomainError::TooManyAttempts), obtido {:?}",
+            result
+        );
+        store.release(conta()).await.unwrap();
+        assert!(store.reserve(conta()).await.is_ok());
+        for _ in 0..3 {
+            store.register_failure(conta()).await.unwrap();
+        }
+        assert!(!store.list_lockouts().await.unwrap().is_empty());
+    }
+}
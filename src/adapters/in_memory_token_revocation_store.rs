@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::Utc;
+
+use crate::{base::domain_error::DomainError, traits::token_revocation_store::TokenRevocationStore};
+
+#[derive(Default)]
+pub struct InMemoryTokenRevocationStore {
+    revoked: Mutex<HashMap<String, usize>>,
+}
+
+impl InMemoryTokenRevocationStore {
+    pub fn new() -> Self {
+        InMemoryTokenRevocationStore {
+            revoked: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenRevocationStore for InMemoryTokenRevocationStore {
+    async fn revoke(&self, jti: String, exp: usize) -> Result<(), DomainError> {
+        let now = Utc::now().timestamp() as usize;
+        let mut revoked = self.revoked.lock().unwrap();
+        // Tokens expirados já são rejeitados pela validação do JWT
+        revoked.retain(|_, revoked_exp| *revoked_exp > now);
+        revoked.insert(jti, exp);
+        Ok(())
+    }
+
+    async fn is_revoked(&self, jti: String) -> Result<bool, DomainError> {
+        let revoked = self.revoked.lock().unwrap();
+        Ok(revoked.contains_key(&jti))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio;
+
+    #[tokio::test]
+    async fn should_revoke_token() {
+        let store = InMemoryTokenRevocationStore::new();
+        let exp = Utc::now().timestamp() as usize + 60;
+        assert!(!store.is_revoked("jti".to_string()).await.unwrap());
+        store.revoke("jti".to_string(), exp).await.unwrap();
+        assert!(store.is_revoked("jti".to_string()).await.unwrap());
+        assert!(!store.is_revoked("outro".to_string()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_prune_expired_entries() {
+        let store = InMemoryTokenRevocationStore::new();
+        let now = Utc::now().timestamp() as usize;
+        store.revoke("expirado".to_string(), now - 1).await.unwrap();
+        store.revoke("valido".to_string(), now + 60).await.unwrap();
+        assert!(!store.is_revoked("expirado".to_string()).await.unwrap());
+        assert!(store.is_revoked("valido".to_string()).await.unwrap());
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chrono::Utc;
+
+use crate::{base::domain_error::DomainError, traits::verification_code_store::VerificationCodeStore};
+
+struct VerificationCode {
+    code: String,
+    exp: i64,
+    attempts: u32,
+}
+
+pub struct InMemoryVerificationCodeStore {
+    codes: Mutex<HashMap<String, VerificationCode>>,
+    ttl: i64,
+    max_attempts: u32,
+}
+
+impl InMemoryVerificationCodeStore {
+    pub fn new(ttl: i64, max_attempts: u32) -> Self {
+        InMemoryVerificationCodeStore {
+            codes: Mutex::new(HashMap::new()),
+            ttl,
+            max_attempts,
+        }
+    }
+}
+
+#[async_trait]
+impl VerificationCodeStore for InMemoryVerificationCodeStore {
+    async fn issue(&self, key: String) -> Result<String, DomainError> {
+        let now = Utc::now().timestamp();
+        let mut codes = self.codes.lock().unwrap();
+        codes.retain(|_, code| code.exp > now);
+        // Enquanto o código atual vale, inclusive depois de esgotar as
+        // tentativas, não sai outro; assim pedir códigos novos não renova as
+        // tentativas de adivinhar
+        if let Some(entry) = codes.get(&key) {
+            return Err(DomainError::TooManyAttempts(entry.exp - now));
+        }
+        let code = format!("{:06}", OsRng.next_u32() % 1_000_000);
+        codes.insert(
+            key,
+            VerificationCode {
+                code: code.clone(),
+                exp: now + self.ttl,
+                attempts: 0,
+            },
+        );
+        Ok(code)
+    }
+
+    async fn verify(&self, key: String, code: String) -> Result<(), DomainError> {
+        let now = Utc::now().timestamp();
+        let mut codes = self.codes.lock().unwrap();
+        let entry = match codes.get_mut(&key) {
+            Some(entry) if entry.exp > now => entry,
+            Some(_) => {
+                codes.remove(&key);
+                return Err(DomainError::Unauthorized);
+            }
+            None => return Err(DomainError::Unauthorized),
+        };
+        // Esgotadas as tentativas, o código fica bloqueado até expirar
+        if entry.attempts >= self.max_attempts {
+            return Err(DomainError::Unauthorized);
+        }
+        if entry.code == code {
+            codes.remove(&key);
+            return Ok(());
+        }
+        entry.attempts += 1;
+        Err(DomainError::Unauthorized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio;
+
+    #[tokio::test]
+    async fn should_accept_code_once() {
+        let store = InMemoryVerificationCodeStore::new(60, 3);
+        let code = store.issue("chave".to_string()).await.unwrap();
+        assert_eq!(code.len(), 6);
+        assert!(store.verify("chave".to_string(), code.clone()).await.is_ok());
+        let result = store.verify("chave".to_string(), code).await;
+        assert!(
+            matches!(result, Err(DomainError::Unauthorized)),
+            "Esperado Err(DomainError::Unauthorized), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_expired_code() {
+        let store = InMemoryVerificationCodeStore::new(-1, 3);
+        let code = store.issue("chave".to_string()).await.unwrap();
+        assert!(store.verify("chave".to_string(), code).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_discard_code_after_max_attempts() {
+        let store = InMemoryVerificationCodeStore::new(60, 2);
+        let code = store.issue("chave".to_string()).await.unwrap();
+        let wrong = if code == "000000" { "111111" } else { "000000" };
+        assert!(store.verify("chave".to_string(), wrong.to_string()).await.is_err());
+        assert!(store.verify("chave".to_string(), wrong.to_string()).await.is_err());
+        assert!(store.verify("chave".to_string(), code).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_not_reissue_while_code_is_valid() {
+        let store = InMemoryVerificationCodeStore::new(60, 2);
+        let code = store.issue("chave".to_string()).await.unwrap();
+        let wrong = if code == "000000" { "111111" } else { "000000" };
+        assert!(store.verify("chave".to_string(), wrong.to_string()).await.is_err());
+        assert!(store.verify("chave".to_string(), wrong.to_string()).await.is_err());
+
+        let result = store.issue("chave".to_string()).await;
+        assert!(
+            matches!(result, Err(DomainError::TooManyAttempts(segundos)) if segundos > 0 && segundos <= 60),
+            "Esperado Err(DomainError::TooManyAttempts), obtido {:?}",
+            result
+        );
+        assert!(store.issue("outra".to_string()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_issue_again_after_use() {
+        let store = InMemoryVerificationCodeStore::new(60, 3);
+        let code = store.issue("chave".to_string()).await.unwrap();
+        store.verify("chave".to_string(), code).await.unwrap();
+        assert!(store.issue("chave".to_string()).await.is_ok());
+    }
+}
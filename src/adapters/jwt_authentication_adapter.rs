@@ -1,104 +1,373 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
 use crate::{
+    adapters::jwt_keys::JwtKeys,
     base::domain_error::DomainError,
-    entities::usuario::{Tipo, Usuario},
-    traits::authentication_adapter::AuthenticationAdapter,
+    entities::cliente::Cliente,
+    entities::usuario::{Status, Tipo, Usuario},
+    traits::authentication_adapter::{
+        AuthenticationAdapter, AuthenticationTokens, ClienteAuthenticationToken, JwkSet,
+    },
+    traits::cliente_gateway::ClienteGateway,
+    traits::token_revocation_store::TokenRevocationStore,
+    traits::usuario_gateway::UsuarioGateway,
 };
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use chrono::Utc;
+use jsonwebtoken::{decode, decode_header, encode, Validation};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum TokenType {
+    Access,
+    Refresh,
+}
+
+// Papel carregado no token: os tipos de Usuario (staff) mais o Cliente,
+// que se identifica pelo totem e nunca é confundido com um Usuario.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+enum Role {
+    Admin,
+    Cozinha,
+    Cliente,
+}
+
+impl From<&Tipo> for Role {
+    fn from(tipo: &Tipo) -> Self {
+        match tipo {
+            Tipo::Admin => Role::Admin,
+            Tipo::Cozinha => Role::Cozinha,
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
     sub: String,
-    role: Tipo,
+    role: Role,
     company: String,
     exp: usize,
+    iat: usize,
+    jti: String,
+    token_type: TokenType,
+    family: String,
+}
+
+struct RefreshSession {
+    current_jti: String,
+    exp: usize,
 }
 
 #[derive(Clone)]
 pub struct JWTAuthenticationAdapter {
-    secret: String,
+    keys: Arc<JwtKeys>,
+    access_token_ttl: i64,
+    refresh_token_ttl: i64,
+    refresh_sessions: Arc<Mutex<HashMap<String, RefreshSession>>>,
+    usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+    cliente_repository: Arc<dyn ClienteGateway + Sync + Send>,
+    revocation_store: Arc<dyn TokenRevocationStore + Sync + Send>,
 }
 
 impl JWTAuthenticationAdapter {
-    pub fn new(secret: String) -> Self {
-        JWTAuthenticationAdapter { secret: secret }
+    pub fn new(
+        keys: JwtKeys,
+        access_token_ttl: i64,
+        refresh_token_ttl: i64,
+        usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+        cliente_repository: Arc<dyn ClienteGateway + Sync + Send>,
+        revocation_store: Arc<dyn TokenRevocationStore + Sync + Send>,
+    ) -> Self {
+        JWTAuthenticationAdapter {
+            keys: Arc::new(keys),
+            access_token_ttl,
+            refresh_token_ttl,
+            refresh_sessions: Arc::new(Mutex::new(HashMap::new())),
+            usuario_repository,
+            cliente_repository,
+            revocation_store,
+        }
     }
-}
 
-#[async_trait]
-impl AuthenticationAdapter for JWTAuthenticationAdapter {
-    async fn get_token(&self, user: Usuario) -> Result<String, DomainError> {
-        let my_claims = Claims {
-            sub: user.id().to_string(),
-            role: user.tipo().clone(),
-            company: "wdrops".to_string(),
-            exp: 10000000000,
-        };
+    // Tokens de usuários removidos ou inativados deixam de valer imediatamente
+    async fn check_usuario_ativo(&self, sub: &str) -> Result<(), DomainError> {
+        let id = sub.parse::<usize>().map_err(|_| DomainError::Unauthorized)?;
+        match self.usuario_repository.get_usuario_by_id(id).await {
+            Ok(usuario) if *usuario.status() == Status::Ativo => Ok(()),
+            Ok(_) => Err(DomainError::InactiveUser),
+            Err(DomainError::NotFound) => Err(DomainError::Unauthorized),
+            Err(err) => Err(err),
+        }
+    }
 
-        let header = Header::new(Algorithm::HS512);
-        let token = encode(
-            &header,
-            &my_claims,
-            &EncodingKey::from_secret(self.secret.as_ref()),
-        );
-        match token {
+    async fn check_cliente_existe(&self, sub: &str) -> Result<(), DomainError> {
+        let id = sub.parse::<usize>().map_err(|_| DomainError::Unauthorized)?;
+        match self.cliente_repository.get_cliente_by_id(id).await {
+            Ok(_) => Ok(()),
+            Err(DomainError::NotFound) => Err(DomainError::Unauthorized),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn encode_claims(&self, claims: &Claims) -> Result<String, DomainError> {
+        match encode(&self.keys.header(), claims, self.keys.encoding_key()) {
             Ok(t) => Ok(t),
-            Err(_) => Err(DomainError::Invalid("Erro ao gerar token".to_string())),
+            Err(_) => Err(DomainError::Internal("Erro ao gerar token".to_string())),
         }
     }
 
-    async fn validate_token(&self, token: String, role: Option<Tipo>) -> Result<String, DomainError> {
-        let token_data = decode::<Claims>(
-            &token,
-            &DecodingKey::from_secret(self.secret.as_ref()),
-            &Validation::new(Algorithm::HS512),
-        );
+    fn decode_claims(&self, token: &str, token_type: TokenType) -> Result<Claims, DomainError> {
+        let header = decode_header(token).map_err(|_| DomainError::Unauthorized)?;
+        let decoding_key = match self.keys.decoding_key(header.kid.as_deref()) {
+            Some(decoding_key) => decoding_key,
+            None => {
+                eprintln!("Invalid Token: unknown kid {:?}", header.kid);
+                return Err(DomainError::Unauthorized);
+            }
+        };
+        let token_data = decode::<Claims>(token, decoding_key, &Validation::new(self.keys.algorithm()));
         match token_data {
-            Ok(t) => match role {
-                Some(r) => {
-                    if t.claims.role != r {
-                        return Err(DomainError::Unauthorized);
-                    }
-                    Ok(t.claims.sub)
-                }
-                None => Ok(t.claims.sub),
-            },
+            Ok(t) if t.claims.token_type == token_type => Ok(t.claims),
+            Ok(_) => Err(DomainError::Unauthorized),
             Err(err) => {
                 eprintln!("Invalid Token: {}", err);
                 Err(DomainError::Unauthorized)
             }
         }
     }
+
+    // Um refresh token já rotacionado sendo reapresentado indica vazamento,
+    // então a família inteira é revogada.
+    fn check_refresh_session(
+        sessions: &mut HashMap<String, RefreshSession>,
+        claims: &Claims,
+    ) -> Result<(), DomainError> {
+        match sessions.get(&claims.family) {
+            Some(session) if session.current_jti == claims.jti => Ok(()),
+            Some(_) => {
+                eprintln!("Refresh token reuse detected for user {}", claims.sub);
+                sessions.remove(&claims.family);
+                Err(DomainError::Unauthorized)
+            }
+            None => Err(DomainError::Unauthorized),
+        }
+    }
+
+    fn issue_tokens(
+        &self,
+        user: &Usuario,
+        family: String,
+        sessions: &mut HashMap<String, RefreshSession>,
+    ) -> Result<AuthenticationTokens, DomainError> {
+        let now = Utc::now().timestamp();
+        let access_claims = Claims {
+            sub: user.id().to_string(),
+            role: Role::from(user.tipo()),
+            company: "wdrops".to_string(),
+            exp: (now + self.access_token_ttl) as usize,
+            iat: now as usize,
+            jti: Uuid::new_v4().to_string(),
+            token_type: TokenType::Access,
+            family: family.clone(),
+        };
+        let refresh_claims = Claims {
+            sub: user.id().to_string(),
+            role: Role::from(user.tipo()),
+            company: "wdrops".to_string(),
+            exp: (now + self.refresh_token_ttl) as usize,
+            iat: now as usize,
+            jti: Uuid::new_v4().to_string(),
+            token_type: TokenType::Refresh,
+            family: family.clone(),
+        };
+
+        let token = self.encode_claims(&access_claims)?;
+        let refresh_token = self.encode_claims(&refresh_claims)?;
+
+        sessions.retain(|_, session| session.exp > now as usize);
+        sessions.insert(
+            family,
+            RefreshSession {
+                current_jti: refresh_claims.jti.clone(),
+                exp: refresh_claims.exp,
+            },
+        );
+
+        Ok(AuthenticationTokens {
+            token,
+            token_expiration: access_claims.exp,
+            refresh_token,
+            refresh_token_expiration: refresh_claims.exp,
+        })
+    }
 }
 
-unsafe impl Sync for JWTAuthenticationAdapter {}
-unsafe impl Send for JWTAuthenticationAdapter {}
+#[async_trait]
+impl AuthenticationAdapter for JWTAuthenticationAdapter {
+    async fn get_token(&self, user: Usuario) -> Result<AuthenticationTokens, DomainError> {
+        let mut sessions = self.refresh_sessions.lock().unwrap();
+        self.issue_tokens(&user, Uuid::new_v4().to_string(), &mut sessions)
+    }
+
+    async fn validate_token(&self, token: String, role: Option<Tipo>) -> Result<String, DomainError> {
+        let claims = self.decode_claims(&token, TokenType::Access)?;
+        // O sub de um token de Cliente é o id do cliente, não de um Usuario
+        if claims.role == Role::Cliente {
+            return Err(DomainError::Unauthorized);
+        }
+        if self.revocation_store.is_revoked(claims.jti.clone()).await? {
+            return Err(DomainError::Unauthorized);
+        }
+        self.check_usuario_ativo(&claims.sub).await?;
+        match role {
+            Some(r) => {
+                if claims.role != Role::from(&r) {
+                    return Err(DomainError::Unauthorized);
+                }
+                Ok(claims.sub)
+            }
+            None => Ok(claims.sub),
+        }
+    }
+
+    async fn validate_refresh_token(&self, refresh_token: String) -> Result<String, DomainError> {
+        let claims = self.decode_claims(&refresh_token, TokenType::Refresh)?;
+        {
+            let mut sessions = self.refresh_sessions.lock().unwrap();
+            Self::check_refresh_session(&mut sessions, &claims)?;
+        }
+        self.check_usuario_ativo(&claims.sub).await?;
+        Ok(claims.sub)
+    }
+
+    async fn rotate_refresh_token(&self, refresh_token: String, user: Usuario) -> Result<AuthenticationTokens, DomainError> {
+        let claims = self.decode_claims(&refresh_token, TokenType::Refresh)?;
+        if claims.sub != user.id().to_string() {
+            return Err(DomainError::Unauthorized);
+        }
+        let mut sessions = self.refresh_sessions.lock().unwrap();
+        Self::check_refresh_session(&mut sessions, &claims)?;
+        self.issue_tokens(&user, claims.family, &mut sessions)
+    }
+
+    async fn revoke_token(&self, token: String) -> Result<(), DomainError> {
+        let claims = self.decode_claims(&token, TokenType::Access)?;
+        self.refresh_sessions.lock().unwrap().remove(&claims.family);
+        self.revocation_store.revoke(claims.jti, claims.exp).await
+    }
+
+    async fn get_cliente_token(&self, cliente: Cliente) -> Result<ClienteAuthenticationToken, DomainError> {
+        let now = Utc::now().timestamp();
+        let claims = Claims {
+            sub: cliente.id().to_string(),
+            role: Role::Cliente,
+            company: "wdrops".to_string(),
+            exp: (now + self.access_token_ttl) as usize,
+            iat: now as usize,
+            jti: Uuid::new_v4().to_string(),
+            token_type: TokenType::Access,
+            family: Uuid::new_v4().to_string(),
+        };
+        let token = self.encode_claims(&claims)?;
+        Ok(ClienteAuthenticationToken {
+            token,
+            token_expiration: claims.exp,
+        })
+    }
+
+    async fn validate_cliente_token(&self, token: String) -> Result<String, DomainError> {
+        let claims = self.decode_claims(&token, TokenType::Access)?;
+        if claims.role != Role::Cliente {
+            return Err(DomainError::Unauthorized);
+        }
+        if self.revocation_store.is_revoked(claims.jti.clone()).await? {
+            return Err(DomainError::Unauthorized);
+        }
+        self.check_cliente_existe(&claims.sub).await?;
+        Ok(claims.sub)
+    }
+
+    async fn get_jwks(&self) -> Result<JwkSet, DomainError> {
+        Ok(self.keys.jwks().clone())
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::entities::email::Email;
     use chrono::Utc;
-    use crate::adapters::jwt_authentication_adapter;
     use crate::entities::cpf::Cpf;
+    use crate::adapters::in_memory_token_revocation_store::InMemoryTokenRevocationStore;
     use crate::entities::usuario::{Status, Tipo};
+    use crate::traits::cliente_gateway::MockClienteGateway;
+    use crate::traits::usuario_gateway::MockUsuarioGateway;
+    use jsonwebtoken::Algorithm;
     use tokio;
 
+    const RS256_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/certs/test/rs256");
+    const ES256_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/certs/test/es256");
+
+    fn adapter(access_token_ttl: i64, refresh_token_ttl: i64, status: Status) -> JWTAuthenticationAdapter {
+        adapter_with_keys(JwtKeys::from_secret("secret"), access_token_ttl, refresh_token_ttl, status)
+    }
+
+    fn adapter_with_keys(
+        keys: JwtKeys,
+        access_token_ttl: i64,
+        refresh_token_ttl: i64,
+        status: Status,
+    ) -> JWTAuthenticationAdapter {
+        let mut usuario_repository = MockUsuarioGateway::new();
+        usuario_repository.expect_get_usuario_by_id().returning(move |id| {
+            if id == 2 {
+                return Err(DomainError::Internal("Usuario".to_string()));
+            }
+            if id != 1 {
+                return Err(DomainError::NotFound);
+            }
+            let mut usuario = create_usuario();
+            usuario.set_status(status.clone());
+            Ok(usuario)
+        });
+        let mut cliente_repository = MockClienteGateway::new();
+        cliente_repository.expect_get_cliente_by_id().returning(|id| {
+            if id == 9 {
+                return Err(DomainError::Internal("Cliente".to_string()));
+            }
+            if id != 7 {
+                return Err(DomainError::NotFound);
+            }
+            Ok(create_cliente(id))
+        });
+        JWTAuthenticationAdapter::new(
+            keys,
+            access_token_ttl,
+            refresh_token_ttl,
+            Arc::new(usuario_repository),
+            Arc::new(cliente_repository),
+            Arc::new(InMemoryTokenRevocationStore::new()),
+        )
+    }
+
     #[tokio::test]
     async fn should_generate_token() {
         let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
-        let now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let now = Utc::now();
         let user = Usuario::new(
             1,
             "Teste".to_string(),
-            "teste@email.com".to_string(),
+            Email::new("teste@email.com".to_string()).unwrap(),
             cpf.clone(),
             "senha_segura".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            now.clone(),
+            now,
             now,
         );
-        let jwt_authentication_adapter = jwt_authentication_adapter::JWTAuthenticationAdapter::new("secret".to_string());
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
         let token = jwt_authentication_adapter.get_token(user).await;
         assert!(token.is_ok());
     }
@@ -106,22 +375,22 @@ mod tests {
     #[tokio::test]
     async fn should_validate_token_for_any_user() {
         let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
-        let now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let now = Utc::now();
         let user = Usuario::new(
             1,
             "Teste".to_string(),
-            "teste@email.com".to_string(),
+            Email::new("teste@email.com".to_string()).unwrap(),
             cpf.clone(),
             "senha_segura".to_string(),
             Tipo::Cozinha,
             Status::Ativo,
-            now.clone(),
+            now,
             now,
         );
-        let jwt_authentication_adapter = jwt_authentication_adapter::JWTAuthenticationAdapter::new("secret".to_string());
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
         let token = jwt_authentication_adapter.get_token(user.clone()).await;
         assert!(token.is_ok());
-        let token = token.unwrap();
+        let token = token.unwrap().token;
         let user_id = jwt_authentication_adapter.validate_token(token, None).await;
         assert!(user_id.is_ok());
         assert_eq!(user_id.unwrap(), user.id().to_string());
@@ -130,22 +399,22 @@ mod tests {
     #[tokio::test]
     async fn should_validate_token_for_admin_user() {
         let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
-        let now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let now = Utc::now();
         let user = Usuario::new(
             1,
             "Teste".to_string(),
-            "teste@email.com".to_string(),
+            Email::new("teste@email.com".to_string()).unwrap(),
             cpf.clone(),
             "senha_segura".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            now.clone(),
+            now,
             now,
         );
-        let jwt_authentication_adapter = jwt_authentication_adapter::JWTAuthenticationAdapter::new("secret".to_string());
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
         let token = jwt_authentication_adapter.get_token(user.clone()).await;
         assert!(token.is_ok());
-        let token = token.unwrap();
+        let token = token.unwrap().token;
         let user_id = jwt_authentication_adapter.validate_token(token, Some(Tipo::Admin)).await;
         assert!(user_id.is_ok());
         assert_eq!(user_id.unwrap(), user.id().to_string());
@@ -154,23 +423,255 @@ mod tests {
     #[tokio::test]
     async fn should_block_token_for_non_admin_user() {
         let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
-        let now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let now = Utc::now();
         let user = Usuario::new(
             1,
             "Teste".to_string(),
-            "teste@email.com".to_string(),
+            Email::new("teste@email.com".to_string()).unwrap(),
             cpf.clone(),
             "senha_segura".to_string(),
             Tipo::Cozinha,
             Status::Ativo,
-            now.clone(),
+            now,
             now,
         );
-        let jwt_authentication_adapter = jwt_authentication_adapter::JWTAuthenticationAdapter::new("secret".to_string());
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
         let token = jwt_authentication_adapter.get_token(user.clone()).await;
         assert!(token.is_ok());
-        let token = token.unwrap();
+        let token = token.unwrap().token;
         let user_id = jwt_authentication_adapter.validate_token(token, Some(Tipo::Admin)).await;
         assert!(user_id.is_err());
     }
-}
\ No newline at end of file
+
+    fn create_usuario() -> Usuario {
+        let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
+        let now = Utc::now();
+        Usuario::new(
+            1,
+            "Teste".to_string(),
+            Email::new("teste@email.com".to_string()).unwrap(),
+            cpf,
+            "senha_segura".to_string(),
+            Tipo::Cozinha,
+            Status::Ativo,
+            now,
+            now,
+        )
+    }
+
+    fn create_cliente(id: usize) -> Cliente {
+        let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
+        let now = Utc::now();
+        Cliente::new(
+            id,
+            "Cliente".to_string(),
+            Email::new("cliente@email.com".to_string()).unwrap(),
+            cpf,
+            now,
+            now,
+        )
+    }
+
+    #[tokio::test]
+    async fn should_set_token_expiration_from_ttl() {
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
+        let now = Utc::now().timestamp() as usize;
+        let tokens = jwt_authentication_adapter.get_token(create_usuario()).await.unwrap();
+        assert!(tokens.token_expiration >= now + 900 && tokens.token_expiration <= now + 901);
+        assert!(tokens.refresh_token_expiration >= now + 3600 && tokens.refresh_token_expiration <= now + 3601);
+    }
+
+    #[tokio::test]
+    async fn should_reject_expired_token() {
+        let jwt_authentication_adapter = adapter(-120, 3600, Status::Ativo);
+        let tokens = jwt_authentication_adapter.get_token(create_usuario()).await.unwrap();
+        let user_id = jwt_authentication_adapter.validate_token(tokens.token, None).await;
+        assert!(user_id.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_not_accept_refresh_token_as_access_token() {
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
+        let tokens = jwt_authentication_adapter.get_token(create_usuario()).await.unwrap();
+        assert!(jwt_authentication_adapter.validate_token(tokens.refresh_token, None).await.is_err());
+        assert!(jwt_authentication_adapter.validate_refresh_token(tokens.token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_rotate_refresh_token() {
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
+        let user = create_usuario();
+        let tokens = jwt_authentication_adapter.get_token(user.clone()).await.unwrap();
+        let user_id = jwt_authentication_adapter.validate_refresh_token(tokens.refresh_token.clone()).await;
+        assert_eq!(user_id.unwrap(), user.id().to_string());
+
+        let rotated = jwt_authentication_adapter
+            .rotate_refresh_token(tokens.refresh_token.clone(), user.clone())
+            .await
+            .unwrap();
+        assert_ne!(rotated.refresh_token, tokens.refresh_token);
+        assert!(jwt_authentication_adapter.validate_token(rotated.token, None).await.is_ok());
+        assert!(jwt_authentication_adapter.validate_refresh_token(rotated.refresh_token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_revoke_family_on_refresh_token_reuse() {
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
+        let user = create_usuario();
+        let tokens = jwt_authentication_adapter.get_token(user.clone()).await.unwrap();
+        let rotated = jwt_authentication_adapter
+            .rotate_refresh_token(tokens.refresh_token.clone(), user.clone())
+            .await
+            .unwrap();
+
+        let reused = jwt_authentication_adapter
+            .rotate_refresh_token(tokens.refresh_token, user.clone())
+            .await;
+        assert!(reused.is_err());
+
+        let after_reuse = jwt_authentication_adapter
+            .rotate_refresh_token(rotated.refresh_token, user)
+            .await;
+        assert!(after_reuse.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_reject_revoked_token() {
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
+        let user = create_usuario();
+        let tokens = jwt_authentication_adapter.get_token(user.clone()).await.unwrap();
+        assert!(jwt_authentication_adapter.validate_token(tokens.token.clone(), None).await.is_ok());
+
+        jwt_authentication_adapter.revoke_token(tokens.token.clone()).await.unwrap();
+        assert!(jwt_authentication_adapter.validate_token(tokens.token, None).await.is_err());
+        assert!(jwt_authentication_adapter.validate_refresh_token(tokens.refresh_token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_reject_token_for_inactive_user() {
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Inativo);
+        let tokens = jwt_authentication_adapter.get_token(create_usuario()).await.unwrap();
+        let result = jwt_authentication_adapter.validate_token(tokens.token, None).await;
+        assert!(
+            matches!(result, Err(DomainError::InactiveUser)),
+            "Esperado Err(DomainError::InactiveUser), obtido {:?}",
+            result
+        );
+        let result = jwt_authentication_adapter.validate_refresh_token(tokens.refresh_token).await;
+        assert!(matches!(result, Err(DomainError::InactiveUser)));
+    }
+
+    #[tokio::test]
+    async fn should_sign_and_validate_with_rsa_key() {
+        let keys = JwtKeys::from_pem_dir(Algorithm::RS256, RS256_DIR, "2024-01").unwrap();
+        let jwt_authentication_adapter = adapter_with_keys(keys, 900, 3600, Status::Ativo);
+        let tokens = jwt_authentication_adapter.get_token(create_usuario()).await.unwrap();
+
+        let header = decode_header(&tokens.token).unwrap();
+        assert_eq!(header.alg, Algorithm::RS256);
+        assert_eq!(header.kid, Some("2024-01".to_string()));
+        assert!(jwt_authentication_adapter.validate_token(tokens.token, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_sign_and_validate_with_ec_key() {
+        let keys = JwtKeys::from_pem_dir(Algorithm::ES256, ES256_DIR, "2024-01").unwrap();
+        let jwt_authentication_adapter = adapter_with_keys(keys, 900, 3600, Status::Ativo);
+        let tokens = jwt_authentication_adapter.get_token(create_usuario()).await.unwrap();
+        assert_eq!(decode_header(&tokens.token).unwrap().alg, Algorithm::ES256);
+        assert!(jwt_authentication_adapter.validate_token(tokens.token, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_accept_tokens_signed_with_rotated_key() {
+        let old_keys = JwtKeys::from_pem_dir(Algorithm::RS256, RS256_DIR, "2024-01").unwrap();
+        let old_adapter = adapter_with_keys(old_keys, 900, 3600, Status::Ativo);
+        let tokens = old_adapter.get_token(create_usuario()).await.unwrap();
+
+        let new_keys = JwtKeys::from_pem_dir(Algorithm::RS256, RS256_DIR, "2024-02").unwrap();
+        let new_adapter = adapter_with_keys(new_keys, 900, 3600, Status::Ativo);
+        assert!(new_adapter.validate_token(tokens.token, None).await.is_ok());
+        let new_tokens = new_adapter.get_token(create_usuario()).await.unwrap();
+        assert_eq!(decode_header(&new_tokens.token).unwrap().kid, Some("2024-02".to_string()));
+    }
+
+    #[tokio::test]
+    async fn should_reject_token_signed_with_other_algorithm() {
+        let hs_adapter = adapter(900, 3600, Status::Ativo);
+        let tokens = hs_adapter.get_token(create_usuario()).await.unwrap();
+
+        let keys = JwtKeys::from_pem_dir(Algorithm::RS256, RS256_DIR, "2024-01").unwrap();
+        let rs_adapter = adapter_with_keys(keys, 900, 3600, Status::Ativo);
+        assert!(rs_adapter.validate_token(tokens.token, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_publish_public_keys() {
+        let keys = JwtKeys::from_pem_dir(Algorithm::RS256, RS256_DIR, "2024-01").unwrap();
+        let jwt_authentication_adapter = adapter_with_keys(keys, 900, 3600, Status::Ativo);
+        let jwks = jwt_authentication_adapter.get_jwks().await.unwrap();
+        let kids: Vec<&str> = jwks.keys.iter().map(|key| key.kid.as_str()).collect();
+        assert_eq!(kids, vec!["2024-01", "2024-02"]);
+    }
+
+    #[tokio::test]
+    async fn should_validate_cliente_token() {
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
+        let token = jwt_authentication_adapter.get_cliente_token(create_cliente(7)).await.unwrap();
+        let cliente_id = jwt_authentication_adapter.validate_cliente_token(token.token).await;
+        assert_eq!(cliente_id.unwrap(), "7");
+    }
+
+    #[tokio::test]
+    async fn should_not_accept_cliente_token_as_usuario_token() {
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
+        let token = jwt_authentication_adapter.get_cliente_token(create_cliente(1)).await.unwrap();
+        let result = jwt_authentication_adapter.validate_token(token.token.clone(), None).await;
+        assert!(
+            matches!(result, Err(DomainError::Unauthorized)),
+            "Esperado Err(DomainError::Unauthorized), obtido {:?}",
+            result
+        );
+        assert!(jwt_authentication_adapter.validate_token(token.token, Some(Tipo::Admin)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_not_accept_usuario_token_as_cliente_token() {
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
+        let tokens = jwt_authentication_adapter.get_token(create_usuario()).await.unwrap();
+        let result = jwt_authentication_adapter.validate_cliente_token(tokens.token).await;
+        assert!(matches!(result, Err(DomainError::Unauthorized)));
+    }
+
+    #[tokio::test]
+    async fn should_reject_token_for_removed_cliente() {
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
+        let token = jwt_authentication_adapter.get_cliente_token(create_cliente(8)).await.unwrap();
+        let result = jwt_authentication_adapter.validate_cliente_token(token.token).await;
+        assert!(matches!(result, Err(DomainError::Unauthorized)));
+    }
+
+    #[tokio::test]
+    async fn should_not_hide_repository_errors_as_unauthorized() {
+        let jwt_authentication_adapter = adapter(900, 3600, Status::Ativo);
+        let now = Utc::now();
+        let usuario = Usuario::new(
+            2,
+            "Teste".to_string(),
+            Email::new("teste@email.com".to_string()).unwrap(),
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
+            "senha_segura".to_string(),
+            Tipo::Cozinha,
+            Status::Ativo,
+            now,
+            now,
+        );
+        let tokens = jwt_authentication_adapter.get_token(usuario).await.unwrap();
+        let result = jwt_authentication_adapter.validate_token(tokens.token, None).await;
+        assert!(matches!(result, Err(DomainError::Internal(_))), "Esperado Err(DomainError::Internal), obtido {:?}", result);
+
+        let token = jwt_authentication_adapter.get_cliente_token(create_cliente(9)).await.unwrap();
+        let result = jwt_authentication_adapter.validate_cliente_token(token.token).await;
+        assert!(matches!(result, Err(DomainError::Internal(_))), "Esperado Err(DomainError::Internal), obtido {:?}", result);
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header};
+use simple_asn1::ASN1Block;
+
+use crate::traits::authentication_adapter::{Jwk, JwkSet};
+
+const PUBLIC_KEY_SUFFIX: &str = ".pub.pem";
+
+// Chaves de assinatura dos tokens. Com HS512 o segredo compartilhado é usado
+// para assinar e validar; com RS256/ES256 o diretório de chaves contém um par
+// `<kid>.pem`/`<kid>.pub.pem` por chave, e todas as chaves públicas continuam
+// aceitas (e publicadas no JWKS) para permitir a rotação.
+pub struct JwtKeys {
+    algorithm: Algorithm,
+    signing_kid: Option<String>,
+    encoding_key: EncodingKey,
+    decoding_keys: HashMap<String, DecodingKey>,
+    default_decoding_key: Option<DecodingKey>,
+    jwks: JwkSet,
+}
+
+impl JwtKeys {
+    pub fn from_secret(secret: &str) -> Self {
+        JwtKeys {
+            algorithm: Algorithm::HS512,
+            signing_kid: None,
+            encoding_key: EncodingKey::from_secret(secret.as_ref()),
+            decoding_keys: HashMap::new(),
+            default_decoding_key: Some(DecodingKey::from_secret(secret.as_ref())),
+            jwks: JwkSet::default(),
+        }
+    }
+
+    pub fn from_pem_dir(algorithm: Algorithm, dir: &str, signing_kid: &str) -> Result<Self, String> {
+        if !matches!(algorithm, Algorithm::RS256 | Algorithm::ES256) {
+            return Err(format!("Algoritmo não suportado: {:?}", algorithm));
+        }
+
+        let private_key_path = Path::new(dir).join(format!("{}.pem", signing_kid));
+        let private_pem = fs::read(&private_key_path)
+            .map_err(|err| format!("Erro ao ler {}: {}", private_key_path.display(), err))?;
+        let encoding_key = match algorithm {
+            Algorithm::RS256 => EncodingKey::from_rsa_pem(&private_pem),
+            _ => EncodingKey::from_ec_pem(&private_pem),
+        }
+        .map_err(|err| format!("Chave privada inválida {}: {}", private_key_path.display(), err))?;
+
+        let entries = fs::read_dir(dir).map_err(|err| format!("Erro ao ler {}: {}", dir, err))?;
+        let mut decoding_keys = HashMap::new();
+        let mut jwks = JwkSet::default();
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let kid = match file_name.strip_suffix(PUBLIC_KEY_SUFFIX) {
+                Some(kid) => kid.to_string(),
+                None => continue,
+            };
+            let public_pem = fs::read(entry.path())
+                .map_err(|err| format!("Erro ao ler {}: {}", file_name, err))?;
+            let decoding_key = match algorithm {
+                Algorithm::RS256 => DecodingKey::from_rsa_pem(&public_pem),
+                _ => DecodingKey::from_ec_pem(&public_pem),
+            }
+            .map_err(|err| format!("Chave pública inválida {}: {}", file_name, err))?;
+
+            jwks.keys.push(public_key_to_jwk(algorithm, &kid, &public_pem)?);
+            decoding_keys.insert(kid, decoding_key);
+        }
+
+        if !decoding_keys.contains_key(signing_kid) {
+            return Err(format!("Chave pública {}{} não encontrada", signing_kid, PUBLIC_KEY_SUFFIX));
+        }
+        jwks.keys.sort_by(|a, b| a.kid.cmp(&b.kid));
+
+        Ok(JwtKeys {
+            algorithm,
+            signing_kid: Some(signing_kid.to_string()),
+            encoding_key,
+            decoding_keys,
+            default_decoding_key: None,
+            jwks,
+        })
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn header(&self) -> Header {
+        let mut header = Header::new(self.algorithm);
+        header.kid = self.signing_kid.clone();
+        header
+    }
+
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.encoding_key
+    }
+
+    pub fn decoding_key(&self, kid: Option<&str>) -> Option<&DecodingKey> {
+        match kid {
+            Some(kid) => self.decoding_keys.get(kid),
+            None => self.default_decoding_key.as_ref(),
+        }
+    }
+
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+}
+
+fn public_key_to_jwk(algorithm: Algorithm, kid: &str, public_pem: &[u8]) -> Result<Jwk, String> {
+    let invalid = || format!("Chave pública inválida: {}", kid);
+
+    // SubjectPublicKeyInfo ::= SEQUENCE { algorithm AlgorithmIdentifier, subjectPublicKey BIT STRING }
+    let public_pem = pem::parse(public_pem).map_err(|_| invalid())?;
+    let blocks = simple_asn1::from_der(public_pem.contents()).map_err(|_| invalid())?;
+    let public_key = match blocks.first() {
+        Some(ASN1Block::Sequence(_, items)) => match items.get(1) {
+            Some(ASN1Block::BitString(_, _, bits)) => bits.clone(),
+            _ => return Err(invalid()),
+        },
+        _ => return Err(invalid()),
+    };
+
+    let mut jwk = Jwk {
+        kty: String::new(),
+        kid: kid.to_string(),
+        key_use: "sig".to_string(),
+        alg: format!("{:?}", algorithm),
+        n: None,
+        e: None,
+        crv: None,
+        x: None,
+        y: None,
+    };
+
+    match algorithm {
+        Algorithm::RS256 => {
+            // RSAPublicKey ::= SEQUENCE { modulus INTEGER, publicExponent INTEGER }
+            let blocks = simple_asn1::from_der(&public_key).map_err(|_| invalid())?;
+            let (n, e) = match blocks.first() {
+                Some(ASN1Block::Sequence(_, items)) => match (items.first(), items.get(1)) {
+                    (Some(ASN1Block::Integer(_, n)), Some(ASN1Block::Integer(_, e))) => {
+                        (n.to_bytes_be().1, e.to_bytes_be().1)
+                    }
+                    _ => return Err(invalid()),
+                },
+                _ => return Err(invalid()),
+            };
+            jwk.kty = "RSA".to_string();
+            jwk.n = Some(URL_SAFE_NO_PAD.encode(n));
+            jwk.e = Some(URL_SAFE_NO_PAD.encode(e));
+        }
+        _ => {
+            // Ponto não comprimido: 0x04 || x || y
+            if public_key.len() != 65 || public_key[0] != 0x04 {
+                return Err(invalid());
+            }
+            jwk.kty = "EC".to_string();
+            jwk.crv = Some("P-256".to_string());
+            jwk.x = Some(URL_SAFE_NO_PAD.encode(&public_key[1..33]));
+            jwk.y = Some(URL_SAFE_NO_PAD.encode(&public_key[33..]));
+        }
+    }
+
+    Ok(jwk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RS256_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/certs/test/rs256");
+    const ES256_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/certs/test/es256");
+
+    #[test]
+    fn should_load_rsa_keys() {
+        let keys = JwtKeys::from_pem_dir(Algorithm::RS256, RS256_DIR, "2024-02").unwrap();
+        assert_eq!(keys.header().kid, Some("2024-02".to_string()));
+        assert!(keys.decoding_key(Some("2024-01")).is_some());
+        assert!(keys.decoding_key(Some("2024-02")).is_some());
+        assert!(keys.decoding_key(None).is_none());
+
+        let jwks = keys.jwks();
+        assert_eq!(jwks.keys.len(), 2);
+        assert_eq!(jwks.keys[0].kid, "2024-01");
+        assert_eq!(jwks.keys[0].kty, "RSA");
+        assert_eq!(jwks.keys[0].alg, "RS256");
+        assert_eq!(jwks.keys[0].e, Some("AQAB".to_string()));
+        assert_eq!(jwks.keys[0].n.as_ref().unwrap().len(), 342);
+    }
+
+    #[test]
+    fn should_load_ec_keys() {
+        let keys = JwtKeys::from_pem_dir(Algorithm::ES256, ES256_DIR, "2024-01").unwrap();
+        let jwks = keys.jwks();
+        assert_eq!(jwks.keys.len(), 1);
+        assert_eq!(jwks.keys[0].kty, "EC");
+        assert_eq!(jwks.keys[0].crv, Some("P-256".to_string()));
+        assert_eq!(jwks.keys[0].x.as_ref().unwrap().len(), 43);
+        assert_eq!(jwks.keys[0].y.as_ref().unwrap().len(), 43);
+    }
+
+    #[test]
+    fn should_fail_for_missing_signing_key() {
+        assert!(JwtKeys::from_pem_dir(Algorithm::RS256, RS256_DIR, "2023-12").is_err());
+    }
+
+    #[test]
+    fn should_fail_for_unsupported_algorithm() {
+        assert!(JwtKeys::from_pem_dir(Algorithm::HS256, RS256_DIR, "2024-01").is_err());
+    }
+
+    #[test]
+    fn should_fail_for_mismatched_algorithm() {
+        assert!(JwtKeys::from_pem_dir(Algorithm::ES256, RS256_DIR, "2024-01").is_err());
+    }
+
+    #[test]
+    fn should_not_publish_secret() {
+        let keys = JwtKeys::from_secret("secret");
+        assert!(keys.jwks().keys.is_empty());
+        assert!(keys.decoding_key(None).is_some());
+    }
+}
@@ -0,0 +1,65 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use chrono::Utc;
+
+use crate::{base::domain_error::DomainError, traits::notifier::Notifier};
+
+// Sem provedor de e-mail configurado, as mensagens vão para o log do serviço e,
+// se houver, para um arquivo local
+#[derive(Clone, Default)]
+pub struct LogNotifier {
+    arquivo: Option<String>,
+}
+
+impl LogNotifier {
+    pub fn new() -> Self {
+        LogNotifier { arquivo: None }
+    }
+
+    pub fn with_file(arquivo: String) -> Self {
+        LogNotifier { arquivo: Some(arquivo) }
+    }
+}
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, destinatario: String, assunto: String, mensagem: String) -> Result<(), DomainError> {
+        println!("Notificação para {}: {} - {}", destinatario, assunto, mensagem);
+        if let Some(arquivo) = &self.arquivo {
+            let mut file = OpenOptions::new().create(true).append(true).open(arquivo).map_err(|err| {
+                println!("Failed to open notification file {}: {}", arquivo, err);
+                DomainError::Internal("Notificação".to_string())
+            })?;
+            writeln!(file, "{} {}: {} - {}", Utc::now().to_rfc3339(), destinatario, assunto, mensagem).map_err(|err| {
+                println!("Failed to write notification file {}: {}", arquivo, err);
+                DomainError::Internal("Notificação".to_string())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio;
+
+    #[tokio::test]
+    async fn should_append_to_file() {
+        let arquivo = std::env::temp_dir().join(format!("notificacoes-{}.log", uuid::Uuid::new_v4()));
+        let notifier = LogNotifier::with_file(arquivo.to_string_lossy().to_string());
+        for codigo in ["111111", "222222"] {
+            notifier
+                .notify("fulano@email.com".to_string(), "Código".to_string(), codigo.to_string())
+                .await
+                .unwrap();
+        }
+        let conteudo = std::fs::read_to_string(&arquivo).unwrap();
+        std::fs::remove_file(&arquivo).unwrap();
+        let linhas: Vec<&str> = conteudo.lines().collect();
+        assert_eq!(linhas.len(), 2);
+        assert!(linhas[0].ends_with("fulano@email.com: Código - 111111"));
+        assert!(linhas[1].ends_with("222222"));
+    }
+}
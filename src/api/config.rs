@@ -1,4 +1,4 @@
-use std::{env, str::FromStr,process};
+use std::{env, fmt, str::FromStr,process};
 
 #[derive(Debug, PartialEq)]
 pub enum Env {
@@ -30,20 +30,130 @@ impl ToString for Env {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub enum Backend {
+    Cognito,
+    Postgres,
+    InMemory,
+}
+
+impl FromStr for Backend {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Backend, Self::Err> {
+        match input {
+            "cognito" => Ok(Backend::Cognito),
+            "postgres" => Ok(Backend::Postgres),
+            "memory" => Ok(Backend::InMemory),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Backend::Cognito => "cognito",
+                Backend::Postgres => "postgres",
+                Backend::InMemory => "memory",
+            }
+        )
+    }
+}
+
 pub struct Config {
     pub secret: String,
+    pub jwt_algorithm: String,
+    pub jwt_keys_dir: String,
+    pub jwt_key_id: String,
+    pub access_token_ttl: i64,
+    pub refresh_token_ttl: i64,
+    pub verification_code_ttl: i64,
+    pub cliente_codigo_obrigatorio: bool,
+    pub cliente_anonimo_ttl: i64,
+    pub notifier_file: Option<String>,
+    pub login_max_falhas_conta: u32,
+    pub login_max_falhas_ip: u32,
+    pub login_max_anonimos_ip: u32,
+    pub login_bloqueio_ttl: i64,
     pub env: Env,
+    pub backend: Backend,
+    pub db_url: String,
     pub user_pool_id_cliente: String,
     pub user_pool_id_usuario: String,
+    pub admin_cpf: Option<String>,
+    pub admin_email: String,
+    pub admin_senha: Option<String>,
 }
 
 impl Config {
     pub fn build() -> Config {
         let secret = env::var("SECRET").unwrap_or("secret".to_string());
+        let jwt_algorithm = env::var("JWT_ALGORITHM").unwrap_or("HS512".to_string());
+        let jwt_keys_dir = env::var("JWT_KEYS_DIR").unwrap_or("certs/jwt".to_string());
+        let jwt_key_id = env::var("JWT_KEY_ID").unwrap_or_default();
+        let access_token_ttl = env::var("ACCESS_TOKEN_TTL")
+            .ok()
+            .and_then(|ttl| ttl.parse::<i64>().ok())
+            .unwrap_or(15 * 60);
+        let refresh_token_ttl = env::var("REFRESH_TOKEN_TTL")
+            .ok()
+            .and_then(|ttl| ttl.parse::<i64>().ok())
+            .unwrap_or(7 * 24 * 60 * 60);
+        let verification_code_ttl = env::var("VERIFICATION_CODE_TTL")
+            .ok()
+            .and_then(|ttl| ttl.parse::<i64>().ok())
+            .unwrap_or(5 * 60);
+        let cliente_codigo_obrigatorio = env::var("CLIENTE_CODIGO_OBRIGATORIO")
+            .map(|value| value == "true")
+            .unwrap_or(false);
+        let cliente_anonimo_ttl = env::var("CLIENTE_ANONIMO_TTL")
+            .ok()
+            .and_then(|ttl| ttl.parse::<i64>().ok())
+            .unwrap_or(24 * 60 * 60);
+        let notifier_file = env::var("NOTIFIER_FILE").ok();
+        let login_max_falhas_conta = env::var("LOGIN_MAX_FALHAS_CONTA")
+            .ok()
+            .and_then(|max| max.parse::<u32>().ok())
+            .unwrap_or(5);
+        let login_max_falhas_ip = env::var("LOGIN_MAX_FALHAS_IP")
+            .ok()
+            .and_then(|max| max.parse::<u32>().ok())
+            .unwrap_or(20);
+        let login_max_anonimos_ip = env::var("LOGIN_MAX_ANONIMOS_IP")
+            .ok()
+            .and_then(|max| max.parse::<u32>().ok())
+            .unwrap_or(10);
+        let login_bloqueio_ttl = env::var("LOGIN_BLOQUEIO_TTL")
+            .ok()
+            .and_then(|ttl| ttl.parse::<i64>().ok())
+            .unwrap_or(15 * 60);
         let env = env::var("ENV").unwrap_or("dev".to_string());
         let env = Env::from_str(&env).unwrap_or(Env::Dev);
+        let default_backend = match env {
+            Env::Test => Backend::InMemory,
+            _ => Backend::Cognito,
+        };
+        let backend = match env::var("BACKEND") {
+            Ok(backend) => Backend::from_str(&backend).unwrap_or(default_backend),
+            Err(_) => default_backend,
+        };
+
+        let db_url = match env::var("DB_URL") {
+            Ok(val) => val,
+            Err(_) if backend != Backend::Postgres => String::new(),
+            Err(_) => {
+                eprintln!("DB_URL environment variable not set.");
+                process::exit(1);
+            }
+        };
+
         let user_pool_id_cliente = match std::env::var("AWS_COGNITO_USER_POOL_ID_CLIENTE") {
             Ok(val) => val,
+            Err(_) if backend != Backend::Cognito => String::new(),
             Err(_) => {
                 eprintln!("AWS_COGNITO_USER_POOL_ID_CLIENTE environment variable not set.");
                 process::exit(1);
@@ -52,24 +162,56 @@ impl Config {
 
         let user_pool_id_usuario = match std::env::var("AWS_COGNITO_USER_POOL_ID_USUARIO") {
             Ok(val) => val,
+            Err(_) if backend != Backend::Cognito => String::new(),
             Err(_) => {
                 eprintln!("AWS_COGNITO_USER_POOL_ID_USUARIO environment variable not set.");
                 process::exit(1);
             }
         };
 
+        // Variáveis vazias, como as repassadas pelo compose sem valor no .env,
+        // contam como não configuradas
+        let admin_cpf = env::var("ADMIN_CPF").ok().filter(|cpf| !cpf.is_empty());
+        let admin_email = match env::var("ADMIN_EMAIL") {
+            Ok(val) => val,
+            Err(_) if admin_cpf.is_none() => String::new(),
+            Err(_) => {
+                eprintln!("ADMIN_EMAIL environment variable not set.");
+                process::exit(1);
+            }
+        };
+        let admin_senha = env::var("ADMIN_SENHA").ok().filter(|senha| !senha.is_empty());
+
         Config {
             secret,
+            jwt_algorithm,
+            jwt_keys_dir,
+            jwt_key_id,
+            access_token_ttl,
+            refresh_token_ttl,
+            verification_code_ttl,
+            cliente_codigo_obrigatorio,
+            cliente_anonimo_ttl,
+            notifier_file,
+            login_max_falhas_conta,
+            login_max_falhas_ip,
+            login_max_anonimos_ip,
+            login_bloqueio_ttl,
             env,
+            backend,
+            db_url,
             user_pool_id_cliente,
-            user_pool_id_usuario
+            user_pool_id_usuario,
+            admin_cpf,
+            admin_email,
+            admin_senha,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::api::config::{Config, Env};
+    use crate::api::config::{Backend, Config, Env};
     use std::{env, str::FromStr};
     #[tokio::test]
     async fn test_env_to_string() {
@@ -93,15 +235,42 @@ mod tests {
         assert_eq!(env, Env::Test);
     }
     
+    #[tokio::test]
+    async fn test_backend_to_string() {
+        let mut backend = Backend::Cognito;
+        assert_eq!(backend.to_string(), "cognito");
+
+        backend = Backend::Postgres;
+        assert_eq!(backend.to_string(), "postgres");
+
+        backend = Backend::InMemory;
+        assert_eq!(backend.to_string(), "memory");
+    }
+
+    #[tokio::test]
+    async fn test_backend_from_string(){
+        let mut backend = Backend::from_str("cognito").unwrap();
+        assert_eq!(backend, Backend::Cognito);
+        backend = Backend::from_str("postgres").unwrap();
+        assert_eq!(backend, Backend::Postgres);
+        backend = Backend::from_str("memory").unwrap();
+        assert_eq!(backend, Backend::InMemory);
+        assert!(Backend::from_str("mysql").is_err());
+    }
+
     #[tokio::test]
     async fn test_build_env() {
         env::set_var("SECRET", "test_secret");
+        env::set_var("ACCESS_TOKEN_TTL", "60");
         env::set_var("ENV", "dev");
         env::set_var("AWS_COGNITO_USER_POOL_ID_CLIENTE", "test_cliente_pool");
         env::set_var("AWS_COGNITO_USER_POOL_ID_USUARIO", "test_cliente_usuario");
         let config = Config::build();
         
         assert_eq!(config.secret.clone(), "test_secret");
+        assert_eq!(config.access_token_ttl, 60);
+        assert_eq!(config.refresh_token_ttl, 7 * 24 * 60 * 60);
         assert_eq!(config.env, Env::Dev);
+        assert_eq!(config.jwt_algorithm, "HS512");
     }
 }
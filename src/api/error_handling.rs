@@ -1,32 +1,161 @@
-use crate::base::domain_error::DomainError;
+use crate::base::domain_error::{DomainError, FieldError};
 use rocket::http::Status;
+use rocket::request::Request;
+use rocket::response::status::Custom;
+use rocket::response::{self, Responder};
 use rocket::serde::json::Json;
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::{MediaType, RefOr, Response, Responses};
+use rocket_okapi::okapi::Map;
+use rocket_okapi::response::OpenApiResponderInner;
 use schemars::JsonSchema;
 use serde::Serialize;
 
 impl From<DomainError> for Status {
     fn from(error: DomainError) -> Self {
         match error {
-            DomainError::AlreadyExists => Status::Conflict,
+            DomainError::AlreadyExists(_) => Status::Conflict,
+            DomainError::Conflict(_) => Status::Conflict,
             DomainError::NotFound => Status::NotFound,
+            DomainError::Unauthorized => Status::Unauthorized,
+            DomainError::InactiveUser => Status::Forbidden,
+            DomainError::PasswordChangeRequired => Status::Forbidden,
+            DomainError::TooManyAttempts(_) => Status::TooManyRequests,
             DomainError::Empty => Status::BadRequest,
             DomainError::Invalid(_) => Status::BadRequest,
-            _ => Status::InternalServerError,
+            DomainError::Validation(_) => Status::BadRequest,
+            DomainError::NonPositive => Status::BadRequest,
+            DomainError::Internal(_) => Status::InternalServerError,
         }
     }
 }
 
+// Código estável para que os clientes da API tratem o erro sem depender da
+// mensagem
+fn error_code(error: &DomainError) -> &'static str {
+    match error {
+        DomainError::AlreadyExists(_) => "already_exists",
+        DomainError::Conflict(_) => "conflict",
+        DomainError::Empty => "empty",
+        DomainError::Unauthorized => "unauthorized",
+        DomainError::InactiveUser => "inactive_user",
+        DomainError::PasswordChangeRequired => "password_change_required",
+        DomainError::TooManyAttempts(_) => "too_many_attempts",
+        DomainError::NotFound => "not_found",
+        DomainError::Invalid(_) => "invalid",
+        DomainError::Validation(_) => "validation_failed",
+        DomainError::NonPositive => "non_positive",
+        DomainError::Internal(_) => "internal_error",
+    }
+}
+
+fn error_message(error: &DomainError) -> String {
+    match error {
+        DomainError::AlreadyExists(field) => format!("{} já cadastrado", field),
+        DomainError::Conflict(message) => message.clone(),
+        DomainError::Empty => "Campo obrigatório não informado".to_string(),
+        DomainError::Unauthorized => "Credenciais invalidas".to_string(),
+        DomainError::InactiveUser => "Usuário inativo".to_string(),
+        DomainError::PasswordChangeRequired => "Troca de senha obrigatória".to_string(),
+        DomainError::TooManyAttempts(_) => "Muitas tentativas. Tente novamente mais tarde".to_string(),
+        DomainError::NotFound => "Não encontrado".to_string(),
+        DomainError::Invalid(message) => message.clone(),
+        DomainError::Validation(_) => "Input inválido".to_string(),
+        DomainError::NonPositive => "Valor deve ser positivo".to_string(),
+        DomainError::Internal(_) => "Erro inesperado. Tente novamente mais tarde".to_string(),
+    }
+}
+
 #[derive(Debug, Serialize, JsonSchema)]
 pub struct ErrorResponse {
     pub msg: String,
     pub status: usize,
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<FieldError>>,
+}
+
+// Conflitos informam qual campo já está cadastrado e erros de validação listam
+// as violações de cada campo
+impl From<DomainError> for Custom<Json<ErrorResponse>> {
+    fn from(error: DomainError) -> Self {
+        let code = error_code(&error).to_string();
+        let msg = error_message(&error);
+        let (field, errors) = match &error {
+            DomainError::AlreadyExists(field) => (Some(field.clone()), None),
+            DomainError::Validation(errors) => (None, Some(errors.clone())),
+            _ => (None, None),
+        };
+        let status = Status::from(error);
+        Custom(
+            status,
+            Json(ErrorResponse {
+                msg,
+                status: status.code as usize,
+                code,
+                field,
+                errors,
+            }),
+        )
+    }
+}
+
+impl<'r> Responder<'r, 'static> for DomainError {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let retry_after = match &self {
+            DomainError::TooManyAttempts(segundos) => Some(*segundos),
+            _ => None,
+        };
+        let mut response = Custom::<Json<ErrorResponse>>::from(self).respond_to(request)?;
+        if let Some(segundos) = retry_after {
+            response.set_raw_header("Retry-After", segundos.to_string());
+        }
+        Ok(response)
+    }
+}
+
+impl OpenApiResponderInner for DomainError {
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let schema = gen.json_schema::<ErrorResponse>();
+        let mut responses = Responses::default();
+        for (status, description) in [
+            (400, "Input inválido; `errors` lista as violações de cada campo"),
+            (401, "Credenciais ausentes ou inválidas"),
+            (403, "Usuário inativo ou com troca de senha pendente"),
+            (404, "Não encontrado"),
+            (409, "Valor já cadastrado (`field` indica o campo) ou recurso em estado que não admite a operação"),
+            (429, "Login bloqueado por excesso de tentativas; `Retry-After` indica a espera"),
+            (500, "Erro inesperado"),
+        ] {
+            let mut content = Map::new();
+            content.insert(
+                "application/json".to_string(),
+                MediaType {
+                    schema: Some(schema.clone()),
+                    ..MediaType::default()
+                },
+            );
+            let response = Response {
+                description: description.to_string(),
+                content,
+                ..Response::default()
+            };
+            responses.responses.insert(status.to_string(), RefOr::Object(response));
+        }
+        Ok(responses)
+    }
 }
 
 #[catch(400)]
 fn bad_request() -> Json<ErrorResponse> {
     let error = ErrorResponse {
         msg: "Input inválido".to_string(),
-        status: 401,
+        status: 400,
+        code: "invalid".to_string(),
+        field: None,
+        errors: None,
     };
     Json(error)
 }
@@ -36,6 +165,21 @@ fn unauthorized() -> Json<ErrorResponse> {
     let error = ErrorResponse {
         msg: "Credenciais invalidas".to_string(),
         status: 401,
+        code: "unauthorized".to_string(),
+        field: None,
+        errors: None,
+    };
+    Json(error)
+}
+
+#[catch(403)]
+fn forbidden() -> Json<ErrorResponse> {
+    let error = ErrorResponse {
+        msg: "Usuário inativo".to_string(),
+        status: 403,
+        code: "inactive_user".to_string(),
+        field: None,
+        errors: None,
     };
     Json(error)
 }
@@ -45,10 +189,96 @@ fn internal() -> Json<ErrorResponse> {
     let error = ErrorResponse {
         msg: "Erro inesperado. Tente novamente mais tarde".to_string(),
         status: 500,
+        code: "internal_error".to_string(),
+        field: None,
+        errors: None,
     };
     Json(error)
 }
 
 pub fn generic_catchers() -> Vec<rocket::Catcher> {
-    catchers![bad_request, unauthorized, internal]
+    catchers![bad_request, unauthorized, forbidden, internal]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_name_conflicting_field() {
+        let Custom(status, Json(body)) = Custom::from(DomainError::AlreadyExists("email".to_string()));
+        assert_eq!(status, Status::Conflict);
+        assert_eq!(body.status, 409);
+        assert_eq!(body.field, Some("email".to_string()));
+        assert_eq!(body.msg, "email já cadastrado");
+        assert_eq!(body.code, "already_exists");
+    }
+
+    #[test]
+    fn should_map_conflict_to_409() {
+        let Custom(status, Json(body)) = Custom::from(DomainError::Conflict("Cliente já identificado".to_string()));
+        assert_eq!(status, Status::Conflict);
+        assert_eq!(body.msg, "Cliente já identificado");
+        assert_eq!(body.code, "conflict");
+        assert_eq!(body.field, None);
+    }
+
+    #[test]
+    fn should_keep_status_for_other_errors() {
+        let Custom(status, Json(body)) = Custom::from(DomainError::NotFound);
+        assert_eq!(status, Status::NotFound);
+        assert_eq!(body.status, 404);
+        assert_eq!(body.code, "not_found");
+        assert_eq!(body.field, None);
+        assert_eq!(body.errors, None);
+    }
+
+    #[test]
+    fn should_carry_invalid_detail() {
+        let Custom(status, Json(body)) = Custom::from(DomainError::Invalid("cursor inválido".to_string()));
+        assert_eq!(status, Status::BadRequest);
+        assert_eq!(body.code, "invalid");
+        assert_eq!(body.msg, "cursor inválido");
+    }
+
+    #[test]
+    fn should_send_retry_after_when_locked() {
+        let rocket = rocket::build();
+        let client = rocket::local::blocking::Client::untracked(rocket).unwrap();
+        let request = client.get("/");
+        let response = DomainError::TooManyAttempts(90).respond_to(request.inner()).unwrap();
+        assert_eq!(response.status(), Status::TooManyRequests);
+        assert_eq!(response.headers().get_one("Retry-After"), Some("90"));
+    }
+
+    #[test]
+    fn should_hide_internal_detail() {
+        let Custom(status, Json(body)) = Custom::from(DomainError::Internal("Usuario".to_string()));
+        assert_eq!(status, Status::InternalServerError);
+        assert_eq!(body.status, 500);
+        assert_eq!(body.code, "internal_error");
+        assert_eq!(body.msg, "Erro inesperado. Tente novamente mais tarde");
+    }
+
+    #[test]
+    fn should_list_field_violations() {
+        let errors = vec![
+            FieldError::required("nome"),
+            FieldError::new("cpf", "invalid_check_digits", "CPF inválido"),
+        ];
+        let Custom(status, Json(body)) = Custom::from(DomainError::Validation(errors.clone()));
+        assert_eq!(status, Status::BadRequest);
+        assert_eq!(body.status, 400);
+        assert_eq!(body.msg, "Input inválido");
+        assert_eq!(body.code, "validation_failed");
+        assert_eq!(body.errors, Some(errors));
+    }
+
+    #[test]
+    fn should_document_error_responses() {
+        let mut gen = OpenApiGenerator::new(&rocket_okapi::settings::OpenApiSettings::default());
+        let responses = DomainError::responses(&mut gen).unwrap();
+        let codes: Vec<&String> = responses.responses.keys().collect();
+        assert_eq!(codes, vec!["400", "401", "403", "404", "409", "429", "500"]);
+    }
 }
@@ -1,3 +1,23 @@
 use rocket::request::FromParam;
 use rocket::http::RawStr;
 
+use crate::entities::email::Email;
+
+impl<'a> FromParam<'a> for Email {
+    type Error = String;
+    fn from_param(param: &'a str) -> Result<Self, Self::Error> {
+        Email::new(param.to_string()).map_err(|_| format!("Email inválido: {}", param))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_email_param() {
+        let email = Email::from_param("Fulano@Exemplo.COM").unwrap();
+        assert_eq!(email.0, "Fulano@exemplo.com");
+        assert!(Email::from_param("fulano").is_err());
+    }
+}
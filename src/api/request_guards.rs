@@ -1,2 +1,3 @@
 pub mod authentication_guard;
-pub mod admin_guard;
\ No newline at end of file
+pub mod admin_guard;
+pub mod cliente_guard;
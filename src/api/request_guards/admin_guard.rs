@@ -31,6 +31,9 @@ impl<'r> FromRequest<'r> for AdminUser {
                 let auth_adapter = req.rocket().state::<Arc<dyn AuthenticationAdapter + Sync + Send>>().unwrap();
                 match auth_adapter.validate_token(token.to_string(), Some(Tipo::Admin)).await {
                     Ok(user_id) => Outcome::Success(AdminUser { user_id }),
+                    Err(DomainError::InactiveUser) => {
+                        Outcome::Error((Status::Forbidden, DomainError::InactiveUser))
+                    }
                     Err(_) => {
                         return Outcome::Error((Status::Unauthorized, DomainError::Unauthorized))
                     }
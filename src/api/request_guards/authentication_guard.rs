@@ -16,6 +16,17 @@ use crate::traits::authentication_adapter::AuthenticationAdapter;
 
 pub struct AuthenticatedUser {
     user_id: String,
+    token: String,
+}
+
+impl AuthenticatedUser {
+    pub fn user_id(&self) -> &String {
+        &self.user_id
+    }
+
+    pub fn token(&self) -> &String {
+        &self.token
+    }
 }
 
 #[rocket::async_trait]
@@ -28,7 +39,10 @@ impl<'r> FromRequest<'r> for AuthenticatedUser {
                 let token = token.replace("Bearer ", "");
                 let auth_adapter = req.rocket().state::<Arc<dyn AuthenticationAdapter + Sync + Send>>().unwrap();
                 match auth_adapter.validate_token(token.to_string(), None).await {
-                    Ok(user_id) => Outcome::Success(AuthenticatedUser { user_id }),
+                    Ok(user_id) => Outcome::Success(AuthenticatedUser { user_id, token }),
+                    Err(DomainError::InactiveUser) => {
+                        Outcome::Error((Status::Forbidden, DomainError::InactiveUser))
+                    }
                     Err(_) => {
                         return Outcome::Error((Status::Unauthorized, DomainError::Unauthorized))
                     }
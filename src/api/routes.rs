@@ -1,3 +1,4 @@
 pub mod auth_route;
 pub mod usuario_route;
 pub mod cliente_route;
+pub mod well_known_route;
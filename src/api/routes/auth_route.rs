@@ -1,36 +1,300 @@
+use std::net::IpAddr;
 use std::sync::Arc;
 
-use rocket::http::Status;
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket_okapi::{openapi, openapi_get_routes};
-use tokio::sync::Mutex;
 
+use crate::api::request_guards::admin_guard::AdminUser;
+use crate::api::request_guards::authentication_guard::AuthenticatedUser;
+use crate::base::domain_error::DomainError;
 use crate::controllers::auth_controller::{
     AuthController,
     LoginInput,
+    RefreshTokenInput,
+    TrocaSenhaInput,
     AuthenticationResponse,
 };
+use crate::controllers::password_reset_controller::{
+    ConfirmaResetSenhaInput,
+    PasswordResetController,
+    SolicitaResetSenhaInput,
+};
+use crate::controllers::cliente_auth_controller::{
+    ClienteAuthController,
+    ClienteAuthSettings,
+    ClienteAuthenticationResponse,
+    ClienteLoginInput,
+    SolicitaCodigoInput,
+};
 
 use crate::traits::authentication_adapter::AuthenticationAdapter;
+use crate::entities::cpf::Cpf;
+use crate::traits::cliente_gateway::ClienteGateway;
+use crate::traits::login_attempt_store::{Lockout, LoginAttemptKey, LoginAttemptStore};
+use crate::traits::notifier::Notifier;
+use crate::traits::password_hasher::PasswordHasher;
 use crate::traits::usuario_gateway::UsuarioGateway;
+use crate::traits::verification_code_store::VerificationCodeStore;
 
 
 #[openapi(tag = "Auth")]
 #[post("/login", data = "<login_input>")]
 async fn login(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Send + Sync>>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Send + Sync>>,
     authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    client_ip: Option<IpAddr>,
     login_input: Json<LoginInput>,
-) -> Result<Json<AuthenticationResponse>, Status> {
+) -> Result<Json<AuthenticationResponse>, DomainError> {
     let usuario_repository = usuario_repository.inner().clone();
     let authentication_adapter = authentication_adapter.inner().clone();
-    let auth_controller = AuthController::new(usuario_repository, authentication_adapter);
+    let password_hasher = password_hasher.inner().clone();
+    let login_attempt_store = login_attempt_store.inner().clone();
+    let auth_controller = AuthController::new(usuario_repository, authentication_adapter, password_hasher, login_attempt_store);
     let login_input = login_input.into_inner();
-    let authentication_response = auth_controller.login(login_input).await?;
+    let authentication_response = auth_controller.login(login_input, client_ip.map(|ip| ip.to_string())).await?;
+    Ok(Json(authentication_response))
+}
+
+#[openapi(tag = "Auth")]
+#[post("/troca-senha", data = "<troca_senha_input>")]
+async fn troca_senha(
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Send + Sync>>,
+    authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    client_ip: Option<IpAddr>,
+    troca_senha_input: Json<TrocaSenhaInput>,
+) -> Result<Json<AuthenticationResponse>, DomainError> {
+    let usuario_repository = usuario_repository.inner().clone();
+    let authentication_adapter = authentication_adapter.inner().clone();
+    let password_hasher = password_hasher.inner().clone();
+    let login_attempt_store = login_attempt_store.inner().clone();
+    let auth_controller = AuthController::new(usuario_repository, authentication_adapter, password_hasher, login_attempt_store);
+    let authentication_response = auth_controller
+        .troca_senha(troca_senha_input.into_inner(), client_ip.map(|ip| ip.to_string()))
+        .await?;
+    Ok(Json(authentication_response))
+}
+
+#[openapi(tag = "Auth")]
+#[post("/refresh", data = "<refresh_token_input>")]
+async fn refresh(
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Send + Sync>>,
+    authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    refresh_token_input: Json<RefreshTokenInput>,
+) -> Result<Json<AuthenticationResponse>, DomainError> {
+    let usuario_repository = usuario_repository.inner().clone();
+    let authentication_adapter = authentication_adapter.inner().clone();
+    let password_hasher = password_hasher.inner().clone();
+    let login_attempt_store = login_attempt_store.inner().clone();
+    let auth_controller = AuthController::new(usuario_repository, authentication_adapter, password_hasher, login_attempt_store);
+    let refresh_token_input = refresh_token_input.into_inner();
+    let authentication_response = auth_controller.refresh(refresh_token_input).await?;
+    Ok(Json(authentication_response))
+}
+
+#[openapi(tag = "Auth")]
+#[post("/logout")]
+async fn logout(
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Send + Sync>>,
+    authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    logged_user_info: AuthenticatedUser,
+) -> Result<Json<String>, DomainError> {
+    let usuario_repository = usuario_repository.inner().clone();
+    let authentication_adapter = authentication_adapter.inner().clone();
+    let password_hasher = password_hasher.inner().clone();
+    let login_attempt_store = login_attempt_store.inner().clone();
+    let auth_controller = AuthController::new(usuario_repository, authentication_adapter, password_hasher, login_attempt_store);
+    auth_controller.logout(logged_user_info.token().clone()).await?;
+    Ok(Json("success".to_string()))
+}
+
+/// Contas e IPs com login bloqueado por excesso de tentativas.
+#[openapi(tag = "Auth")]
+#[get("/lockouts")]
+async fn get_lockouts(
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Send + Sync>>,
+    authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    _logged_user_info: AdminUser,
+) -> Result<Json<Vec<Lockout>>, DomainError> {
+    let usuario_repository = usuario_repository.inner().clone();
+    let authentication_adapter = authentication_adapter.inner().clone();
+    let password_hasher = password_hasher.inner().clone();
+    let login_attempt_store = login_attempt_store.inner().clone();
+    let auth_controller = AuthController::new(usuario_repository, authentication_adapter, password_hasher, login_attempt_store);
+    let lockouts = auth_controller.lista_bloqueios().await?;
+    Ok(Json(lockouts))
+}
+
+#[openapi(tag = "Auth")]
+#[delete("/lockouts/conta/<cpf>")]
+async fn delete_lockout_conta(
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Send + Sync>>,
+    authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    cpf: Cpf,
+    _logged_user_info: AdminUser,
+) -> Result<Json<String>, DomainError> {
+    let usuario_repository = usuario_repository.inner().clone();
+    let authentication_adapter = authentication_adapter.inner().clone();
+    let password_hasher = password_hasher.inner().clone();
+    let login_attempt_store = login_attempt_store.inner().clone();
+    let auth_controller = AuthController::new(usuario_repository, authentication_adapter, password_hasher, login_attempt_store);
+    auth_controller.limpa_bloqueio(LoginAttemptKey::Conta(cpf.formatted())).await?;
+    Ok(Json("success".to_string()))
+}
+
+#[openapi(tag = "Auth")]
+#[delete("/lockouts/ip/<ip>")]
+async fn delete_lockout_ip(
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Send + Sync>>,
+    authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    ip: String,
+    _logged_user_info: AdminUser,
+) -> Result<Json<String>, DomainError> {
+    let usuario_repository = usuario_repository.inner().clone();
+    let authentication_adapter = authentication_adapter.inner().clone();
+    let password_hasher = password_hasher.inner().clone();
+    let login_attempt_store = login_attempt_store.inner().clone();
+    let auth_controller = AuthController::new(usuario_repository, authentication_adapter, password_hasher, login_attempt_store);
+    auth_controller.limpa_bloqueio(LoginAttemptKey::Ip(ip)).await?;
+    Ok(Json("success".to_string()))
+}
+
+/// Envia um código de redefinição de senha para o e-mail do usuário. A
+/// resposta não indica se o CPF ou e-mail está cadastrado.
+#[openapi(tag = "Auth")]
+#[post("/password-reset", data = "<solicita_reset_input>")]
+async fn solicita_reset_senha(
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Send + Sync>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    verification_code_store: &State<Arc<dyn VerificationCodeStore + Sync + Send>>,
+    notifier: &State<Arc<dyn Notifier + Sync + Send>>,
+    solicita_reset_input: Json<SolicitaResetSenhaInput>,
+) -> Result<Json<String>, DomainError> {
+    let password_reset_controller = PasswordResetController::new(
+        usuario_repository.inner().clone(),
+        password_hasher.inner().clone(),
+        verification_code_store.inner().clone(),
+        notifier.inner().clone(),
+    );
+    password_reset_controller.solicita(solicita_reset_input.into_inner()).await?;
+    Ok(Json("success".to_string()))
+}
+
+#[openapi(tag = "Auth")]
+#[post("/password-reset/confirm", data = "<confirma_reset_input>")]
+async fn confirma_reset_senha(
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Send + Sync>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    verification_code_store: &State<Arc<dyn VerificationCodeStore + Sync + Send>>,
+    notifier: &State<Arc<dyn Notifier + Sync + Send>>,
+    confirma_reset_input: Json<ConfirmaResetSenhaInput>,
+) -> Result<Json<String>, DomainError> {
+    let password_reset_controller = PasswordResetController::new(
+        usuario_repository.inner().clone(),
+        password_hasher.inner().clone(),
+        verification_code_store.inner().clone(),
+        notifier.inner().clone(),
+    );
+    password_reset_controller.confirma(confirma_reset_input.into_inner()).await?;
+    Ok(Json("success".to_string()))
+}
+
+#[openapi(tag = "Auth")]
+#[post("/cliente/codigo", data = "<solicita_codigo_input>")]
+async fn solicita_codigo_cliente(
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
+    authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
+    verification_code_store: &State<Arc<dyn VerificationCodeStore + Sync + Send>>,
+    notifier: &State<Arc<dyn Notifier + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    cliente_auth_settings: &State<ClienteAuthSettings>,
+    solicita_codigo_input: Json<SolicitaCodigoInput>,
+) -> Result<Json<String>, DomainError> {
+    let cliente_auth_controller = ClienteAuthController::new(
+        cliente_repository.inner().clone(),
+        authentication_adapter.inner().clone(),
+        verification_code_store.inner().clone(),
+        notifier.inner().clone(),
+        login_attempt_store.inner().clone(),
+        cliente_auth_settings.inner().clone(),
+    );
+    cliente_auth_controller.solicita_codigo(solicita_codigo_input.into_inner()).await?;
+    Ok(Json("success".to_string()))
+}
+
+#[openapi(tag = "Auth")]
+#[post("/cliente", data = "<cliente_login_input>")]
+async fn login_cliente(
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
+    authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
+    verification_code_store: &State<Arc<dyn VerificationCodeStore + Sync + Send>>,
+    notifier: &State<Arc<dyn Notifier + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    cliente_auth_settings: &State<ClienteAuthSettings>,
+    cliente_login_input: Json<ClienteLoginInput>,
+) -> Result<Json<ClienteAuthenticationResponse>, DomainError> {
+    let cliente_auth_controller = ClienteAuthController::new(
+        cliente_repository.inner().clone(),
+        authentication_adapter.inner().clone(),
+        verification_code_store.inner().clone(),
+        notifier.inner().clone(),
+        login_attempt_store.inner().clone(),
+        cliente_auth_settings.inner().clone(),
+    );
+    let authentication_response = cliente_auth_controller.login(cliente_login_input.into_inner()).await?;
+    Ok(Json(authentication_response))
+}
+
+#[openapi(tag = "Auth")]
+#[post("/cliente/anonimo")]
+async fn login_cliente_anonimo(
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
+    authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
+    verification_code_store: &State<Arc<dyn VerificationCodeStore + Sync + Send>>,
+    notifier: &State<Arc<dyn Notifier + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    cliente_auth_settings: &State<ClienteAuthSettings>,
+    client_ip: Option<IpAddr>,
+) -> Result<Json<ClienteAuthenticationResponse>, DomainError> {
+    let cliente_auth_controller = ClienteAuthController::new(
+        cliente_repository.inner().clone(),
+        authentication_adapter.inner().clone(),
+        verification_code_store.inner().clone(),
+        notifier.inner().clone(),
+        login_attempt_store.inner().clone(),
+        cliente_auth_settings.inner().clone(),
+    );
+    let authentication_response = cliente_auth_controller.login_anonimo(client_ip.map(|ip| ip.to_string())).await?;
     Ok(Json(authentication_response))
 }
 
 pub fn routes() -> Vec<rocket::Route> {
-    openapi_get_routes![login]
+    openapi_get_routes![
+        login,
+        troca_senha,
+        refresh,
+        logout,
+        get_lockouts,
+        delete_lockout_conta,
+        delete_lockout_ip,
+        solicita_reset_senha,
+        confirma_reset_senha,
+        login_cliente,
+        login_cliente_anonimo,
+        solicita_codigo_cliente,
+    ]
 }
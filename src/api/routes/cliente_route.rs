@@ -1,17 +1,19 @@
 use std::sync::Arc;
 
-use rocket::http::Status;
 use rocket::request::FromParam;
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket_okapi::{openapi, openapi_get_routes};
-use tokio::sync::Mutex;
 
 use crate::api::error_handling::ErrorResponse;
+use crate::api::request_guards::admin_guard::AdminUser;
 use crate::api::request_guards::authentication_guard::AuthenticatedUser;
+use crate::api::request_guards::cliente_guard::AuthenticatedCliente;
+use crate::base::domain_error::DomainError;
+use crate::base::pagination::Page;
 use crate::controllers::cliente_controller::ClienteController;
 use crate::traits::cliente_gateway::ClienteGateway;
-use crate::use_cases::gerenciamento_de_clientes_use_case::CreateClienteInput;
+use crate::use_cases::gerenciamento_de_clientes_use_case::{CreateClienteInput, ListaClientesInput, UpdateClienteInput};
 use crate::entities::cliente::Cliente;
 use crate::entities::cpf::Cpf;
 
@@ -22,42 +24,153 @@ impl<'a> FromParam<'a> for Cpf {
     }
 }
 
+/// Lista os clientes cadastrados, uma página por vez.
+///
+/// Aceita `limit` (máximo 100) e o `cursor` devolvido em `next_cursor`, os
+/// filtros `nome` (prefixo), `email`, `criado_de` e `criado_ate` (RFC 3339) e a
+/// ordenação `ordenar_por` (`id`, `nome`, `data_criacao`) com `ordem` (`asc`, `desc`).
 #[openapi(tag = "Clientes")]
-#[get("/")]
+#[get("/?<filtro..>")]
 async fn lista_clientes(
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
+    filtro: ListaClientesInput,
     _logged_user_info: AuthenticatedUser,
-) -> Result<Json<Vec<Cliente>>, Status> {
+) -> Result<Json<Page<Cliente>>, DomainError> {
     let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
-    let clientes = cliente_controller.lista_clientes().await?;
+    let clientes = cliente_controller.lista_clientes(filtro).await?;
     Ok(Json(clientes))
 }
 
+/// Retorna o cliente dono do token de cliente informado.
+#[openapi(tag = "Clientes")]
+#[get("/me")]
+async fn cliente_autenticado(
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
+    logged_cliente_info: AuthenticatedCliente,
+) -> Result<Json<Cliente>, DomainError> {
+    let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
+    let cliente_id = logged_cliente_info
+        .cliente_id()
+        .parse::<usize>()
+        .map_err(|_| DomainError::Unauthorized)?;
+    let cliente = cliente_controller.busca_cliente_por_id(cliente_id).await?;
+    Ok(Json(cliente))
+}
+
+/// Identifica um cliente anônimo com nome, e-mail e CPF, mantendo o mesmo id.
+/// Responde 409 se o cliente já estiver identificado ou se o CPF ou o e-mail
+/// já estiverem cadastrados.
+#[openapi(tag = "Clientes")]
+#[post("/me/identificacao", data = "<cliente_input>")]
+async fn identifica_cliente_anonimo(
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
+    logged_cliente_info: AuthenticatedCliente,
+    cliente_input: Json<CreateClienteInput>,
+) -> Result<Json<Cliente>, DomainError> {
+    let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
+    let cliente_id = logged_cliente_info
+        .cliente_id()
+        .parse::<usize>()
+        .map_err(|_| DomainError::Unauthorized)?;
+    let cliente_input = cliente_input.into_inner();
+    let cliente = cliente_controller.identifica_cliente_anonimo(cliente_id, cliente_input).await?;
+    Ok(Json(cliente))
+}
+
+/// Busca um cliente pelo CPF.
 #[openapi(tag = "Clientes")]
 #[get("/<cpf>")]
 async fn busca_cliente_por_cpf(
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
     cpf: Cpf,
-) -> Result<Json<Cliente>, Status> {
+) -> Result<Json<Cliente>, DomainError> {
     let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
     let cliente = cliente_controller.busca_cliente_por_cpf(cpf).await?;
     Ok(Json(cliente))
 }
 
+/// Cadastra um novo cliente. CPF ou e-mail já cadastrados retornam 409.
 #[openapi(tag = "Clientes")]
 #[post("/", data = "<cliente_input>")]
 async fn cadastro_cliente(
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
     cliente_input: Json<CreateClienteInput>,
-) -> Result<Json<Cliente>, Status> {
+) -> Result<Json<Cliente>, DomainError> {
     let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
     let cliente_input = cliente_input.into_inner();
     let cliente = cliente_controller.cadastro_cliente(cliente_input).await?;
     Ok(Json(cliente))
 }
 
+/// Busca um cliente pelo id. Apenas usuários autenticados, já que os ids são
+/// sequenciais.
+#[openapi(tag = "Clientes")]
+#[get("/id/<id>")]
+async fn busca_cliente_por_id(
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
+    id: usize,
+    _logged_user_info: AuthenticatedUser,
+) -> Result<Json<Cliente>, DomainError> {
+    let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
+    let cliente = cliente_controller.busca_cliente_por_id(id).await?;
+    Ok(Json(cliente))
+}
+
+/// Substitui nome, e-mail e CPF do cliente. Apenas administradores.
+#[openapi(tag = "Clientes")]
+#[put("/<cpf>", data = "<cliente_input>")]
+async fn atualiza_cliente(
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
+    cpf: Cpf,
+    cliente_input: Json<CreateClienteInput>,
+    _logged_user_info: AdminUser,
+) -> Result<Json<Cliente>, DomainError> {
+    let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
+    let cliente_input = cliente_input.into_inner();
+    let cliente = cliente_controller.atualiza_cliente(cpf, cliente_input).await?;
+    Ok(Json(cliente))
+}
+
+/// Atualiza apenas os campos informados do cliente. Apenas administradores.
+#[openapi(tag = "Clientes")]
+#[patch("/<cpf>", data = "<cliente_input>")]
+async fn atualiza_parcialmente_cliente(
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
+    cpf: Cpf,
+    cliente_input: Json<UpdateClienteInput>,
+    _logged_user_info: AdminUser,
+) -> Result<Json<Cliente>, DomainError> {
+    let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
+    let cliente_input = cliente_input.into_inner();
+    let cliente = cliente_controller.atualiza_parcialmente_cliente(cpf, cliente_input).await?;
+    Ok(Json(cliente))
+}
+
+/// Remove o cliente. Apenas administradores.
+#[openapi(tag = "Clientes")]
+#[delete("/<cpf>")]
+async fn remove_cliente(
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
+    cpf: Cpf,
+    _logged_user_info: AdminUser,
+) -> Result<Json<String>, DomainError> {
+    let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
+    cliente_controller.remove_cliente(cpf).await?;
+    Ok(Json("success".to_string()))
+}
+
 pub fn routes() -> Vec<rocket::Route> {
-    openapi_get_routes![lista_clientes, busca_cliente_por_cpf, cadastro_cliente]
+    openapi_get_routes![
+        lista_clientes,
+        cliente_autenticado,
+        identifica_cliente_anonimo,
+        busca_cliente_por_cpf,
+        busca_cliente_por_id,
+        cadastro_cliente,
+        atualiza_cliente,
+        atualiza_parcialmente_cliente,
+        remove_cliente
+    ]
 }
 
 #[catch(404)]
@@ -65,6 +178,9 @@ fn cliente_not_found() -> Json<ErrorResponse> {
     let error = ErrorResponse {
         msg: "Cliente não encontrado!".to_string(),
         status: 404,
+        code: "not_found".to_string(),
+        field: None,
+        errors: None,
     };
     Json(error)
 }
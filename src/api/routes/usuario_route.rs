@@ -1,39 +1,94 @@
 use std::sync::Arc;
 
-use rocket::http::Status;
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket_okapi::{openapi, openapi_get_routes};
-use tokio::sync::Mutex;
 
 use crate::api::error_handling::ErrorResponse;
 use crate::api::request_guards::admin_guard::AdminUser;
+use crate::api::request_guards::authentication_guard::AuthenticatedUser;
+use crate::base::domain_error::DomainError;
+use crate::base::pagination::Page;
 use crate::controllers::usuario_controller::UsuarioController;
 use crate::entities::usuario::Usuario;
 use crate::entities::cpf::Cpf;
+use crate::traits::login_attempt_store::LoginAttemptStore;
+use crate::traits::password_hasher::PasswordHasher;
 use crate::traits::usuario_gateway::UsuarioGateway;
-use crate::use_cases::gerenciamento_de_usuarios_use_case::CreateUsuarioInput;
+use crate::use_cases::gerenciamento_de_usuarios_use_case::{AlteraSenhaInput, CreateUsuarioInput, ListaUsuariosInput, UpdateUsuarioInput};
 
 
 #[openapi(tag = "Usuarios")]
-#[get("/")]
+#[get("/?<filtro..>")]
 async fn get_usuarios(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Sync + Send>>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    filtro: ListaUsuariosInput,
     _logged_user_info: AdminUser,
-) -> Result<Json<Vec<Usuario>>, Status> {
-    let usuario_controller = UsuarioController::new(usuario_repository.inner().clone());
-    let usuarios = usuario_controller.get_usuarios().await?;
+) -> Result<Json<Page<Usuario>>, DomainError> {
+    let usuario_controller = UsuarioController::new(
+        usuario_repository.inner().clone(),
+        password_hasher.inner().clone(),
+        login_attempt_store.inner().clone(),
+    );
+    let usuarios = usuario_controller.get_usuarios(filtro).await?;
     Ok(Json(usuarios))
 }
 
+/// Perfil do usuário logado.
+#[openapi(tag = "Usuarios")]
+#[get("/me")]
+async fn get_me(
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    logged_user_info: AuthenticatedUser,
+) -> Result<Json<Usuario>, DomainError> {
+    let usuario_controller = UsuarioController::new(
+        usuario_repository.inner().clone(),
+        password_hasher.inner().clone(),
+        login_attempt_store.inner().clone(),
+    );
+    let usuario = usuario_controller.get_me(logged_user_info.user_id()).await?;
+    Ok(Json(usuario))
+}
+
+/// Troca a senha do usuário logado, que precisa informar a senha atual.
+#[openapi(tag = "Usuarios")]
+#[put("/me/senha", data = "<senha_input>")]
+async fn altera_senha_me(
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    senha_input: Json<AlteraSenhaInput>,
+    logged_user_info: AuthenticatedUser,
+) -> Result<Json<Usuario>, DomainError> {
+    let usuario_controller = UsuarioController::new(
+        usuario_repository.inner().clone(),
+        password_hasher.inner().clone(),
+        login_attempt_store.inner().clone(),
+    );
+    let usuario = usuario_controller
+        .altera_senha_me(logged_user_info.user_id(), senha_input.into_inner())
+        .await?;
+    Ok(Json(usuario))
+}
+
 #[openapi(tag = "Usuarios")]
 #[get("/<id>")]
 async fn get_usuario(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Sync + Send>>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
     id: usize,
     _logged_user_info: AdminUser,
-) -> Result<Json<Usuario>, Status> {
-    let usuario_controller = UsuarioController::new(usuario_repository.inner().clone());
+) -> Result<Json<Usuario>, DomainError> {
+    let usuario_controller = UsuarioController::new(
+        usuario_repository.inner().clone(),
+        password_hasher.inner().clone(),
+        login_attempt_store.inner().clone(),
+    );
     let usuario = usuario_controller.get_usuario(id).await?;
     Ok(Json(usuario))
 }
@@ -41,11 +96,17 @@ async fn get_usuario(
 #[openapi(tag = "Usuarios")]
 #[post("/", data = "<usuario_input>")]
 async fn create_usuario(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Sync + Send>>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
     usuario_input: Json<CreateUsuarioInput>,
     _logged_user_info: AdminUser,
-) -> Result<Json<Usuario>, Status> {
-    let usuario_controller = UsuarioController::new(usuario_repository.inner().clone());
+) -> Result<Json<Usuario>, DomainError> {
+    let usuario_controller = UsuarioController::new(
+        usuario_repository.inner().clone(),
+        password_hasher.inner().clone(),
+        login_attempt_store.inner().clone(),
+    );
     let usuario_input: CreateUsuarioInput = usuario_input.into_inner();
     let usuario = usuario_controller.create_usuario(usuario_input).await?;
     Ok(Json(usuario))
@@ -54,31 +115,65 @@ async fn create_usuario(
 #[openapi(tag = "Usuarios")]
 #[put("/<id>", data = "<usuario_input>")]
 async fn update_usuario(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Sync + Send>>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
     usuario_input: Json<CreateUsuarioInput>,
     id: usize,
     _logged_user_info: AdminUser,
-) -> Result<Json<Usuario>, Status> {
-    let usuario_controller = UsuarioController::new(usuario_repository.inner().clone());
+) -> Result<Json<Usuario>, DomainError> {
+    let usuario_controller = UsuarioController::new(
+        usuario_repository.inner().clone(),
+        password_hasher.inner().clone(),
+        login_attempt_store.inner().clone(),
+    );
     let usuario_input: CreateUsuarioInput = usuario_input.into_inner();
     let usuario = usuario_controller.update_usuario(id, usuario_input).await?;
     Ok(Json(usuario))
 }
 
+/// Atualiza apenas os campos informados do usuário. A senha só é trocada
+/// quando informada.
+#[openapi(tag = "Usuarios")]
+#[patch("/<id>", data = "<usuario_input>")]
+async fn patch_usuario(
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
+    usuario_input: Json<UpdateUsuarioInput>,
+    id: usize,
+    _logged_user_info: AdminUser,
+) -> Result<Json<Usuario>, DomainError> {
+    let usuario_controller = UsuarioController::new(
+        usuario_repository.inner().clone(),
+        password_hasher.inner().clone(),
+        login_attempt_store.inner().clone(),
+    );
+    let usuario_input: UpdateUsuarioInput = usuario_input.into_inner();
+    let usuario = usuario_controller.patch_usuario(id, usuario_input).await?;
+    Ok(Json(usuario))
+}
+
 #[openapi(tag = "Usuarios")]
 #[delete("/<cpf>")]
 async fn delete_usuario(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Sync + Send>>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    password_hasher: &State<Arc<dyn PasswordHasher + Sync + Send>>,
+    login_attempt_store: &State<Arc<dyn LoginAttemptStore + Sync + Send>>,
     cpf: Cpf,
     _logged_user_info: AdminUser,
-) -> Result<Json<String>, Status> {
-    let usuario_controller = UsuarioController::new(usuario_repository.inner().clone());
+) -> Result<Json<String>, DomainError> {
+    let usuario_controller = UsuarioController::new(
+        usuario_repository.inner().clone(),
+        password_hasher.inner().clone(),
+        login_attempt_store.inner().clone(),
+    );
     usuario_controller.delete_usuario(cpf).await?;
     Ok(Json("success".to_string()))
 }
 
 pub fn routes() -> Vec<rocket::Route> {
-    openapi_get_routes![get_usuarios, get_usuario, create_usuario, update_usuario, delete_usuario]
+    openapi_get_routes![get_usuarios, get_me, altera_senha_me, get_usuario, create_usuario, update_usuario, patch_usuario, delete_usuario]
 }
 
 #[catch(404)]
@@ -86,6 +181,9 @@ fn usuario_not_found() -> Json<ErrorResponse> {
     let error = ErrorResponse {
         msg: "Usuário não encontrado!".to_string(),
         status: 404,
+        code: "not_found".to_string(),
+        field: None,
+        errors: None,
     };
     Json(error)
 }
@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::base::domain_error::DomainError;
+use crate::traits::authentication_adapter::{AuthenticationAdapter, JwkSet};
+
+#[get("/jwks.json")]
+async fn jwks(
+    authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
+) -> Result<Json<JwkSet>, DomainError> {
+    let jwks = authentication_adapter.get_jwks().await?;
+    Ok(Json(jwks))
+}
+
+pub fn routes() -> Vec<rocket::Route> {
+    routes![jwks]
+}
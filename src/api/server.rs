@@ -3,54 +3,228 @@ use rocket_okapi::settings::UrlObject;
 use rocket_okapi::swagger_ui::*;
 use std::collections::HashMap;
 use std::process;
+use std::str::FromStr;
+use jsonwebtoken::Algorithm;
 use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio_postgres::{Client, NoTls};
+use uuid::Uuid;
 
 use super::error_handling::generic_catchers;
-use super::routes::{auth_route, cliente_route, usuario_route};
+use super::routes::{auth_route, cliente_route, usuario_route, well_known_route};
+use crate::adapters::argon2_password_hasher::Argon2PasswordHasher;
+use crate::adapters::in_memory_login_attempt_store::{InMemoryLoginAttemptStore, LoginThrottleSettings};
+use crate::adapters::in_memory_token_revocation_store::InMemoryTokenRevocationStore;
+use crate::adapters::in_memory_verification_code_store::InMemoryVerificationCodeStore;
 use crate::adapters::jwt_authentication_adapter::JWTAuthenticationAdapter;
-use crate::api::config::{Config, Env};
+use crate::adapters::jwt_keys::JwtKeys;
+use crate::adapters::log_notifier::LogNotifier;
+use crate::api::config::{Backend, Config, Env};
+use crate::controllers::cliente_auth_controller::ClienteAuthSettings;
 use crate::gateways::aws_cognito_cliente_gateway::AwsCognitoClienteRepository;
 use crate::gateways::aws_cognito_usuario_gateway::AwsCognitoUsuarioRepository;
+use crate::gateways::in_memory_cliente_gateway::InMemoryClienteRepository;
+use crate::gateways::in_memory_usuario_gateway::InMemoryUsuarioRepository;
+use crate::gateways::postgres_cliente_gateway::PostgresClienteRepository;
+use crate::gateways::postgres_usuario_gateway::PostgresUsuarioRepository;
 use crate::traits::authentication_adapter::AuthenticationAdapter;
+use crate::traits::login_attempt_store::LoginAttemptStore;
+use crate::traits::notifier::Notifier;
+use crate::traits::password_hasher::PasswordHasher;
+use crate::traits::token_revocation_store::TokenRevocationStore;
+use crate::traits::verification_code_store::VerificationCodeStore;
+use crate::use_cases::gerenciamento_de_clientes_use_case::ClienteUseCase;
+use crate::use_cases::gerenciamento_de_usuarios_use_case::{BootstrapAdminInput, UsuarioUseCase};
 use crate::traits::{
     cliente_gateway::ClienteGateway,
     usuario_gateway::UsuarioGateway,
 };
 
+const VERIFICATION_CODE_MAX_ATTEMPTS: u32 = 5;
+const PURGE_CLIENTES_ANONIMOS_INTERVALO: Duration = Duration::from_secs(60 * 60);
+
 #[get("/")]
 fn redirect_to_docs() -> Redirect {
     Redirect::to(uri!("/docs"))
 }
 
+async fn connect_to_database(db_url: &str) -> Client {
+    match tokio_postgres::connect(db_url, NoTls).await {
+        Ok((client, connection)) => {
+            tokio::spawn(async move {
+                if let Err(e) = connection.await {
+                    eprintln!("Database connection error: {}", e);
+                }
+            });
+            client
+        }
+        Err(e) => {
+            eprintln!("Failed to connect to database: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn load_jwt_keys(config: &Config) -> JwtKeys {
+    if config.jwt_algorithm == "HS512" {
+        return JwtKeys::from_secret(&config.secret);
+    }
+    let keys = match Algorithm::from_str(&config.jwt_algorithm) {
+        Ok(algorithm) => JwtKeys::from_pem_dir(algorithm, &config.jwt_keys_dir, &config.jwt_key_id),
+        Err(_) => Err(format!("Algoritmo não suportado: {}", config.jwt_algorithm)),
+    };
+    match keys {
+        Ok(keys) => keys,
+        Err(e) => {
+            eprintln!("Failed to load JWT keys: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+// Cria o primeiro administrador quando ADMIN_CPF está configurado. Sem
+// ADMIN_SENHA, gera uma senha de uso único; nos dois casos o primeiro login
+// exige a troca. Sem ADMIN_CPF e sem nenhum admin ativo, as rotas de administração
+// ficam inacessíveis, então o aviso precisa aparecer no log
+
+async fn bootstrap_admin(
+    config: &Config,
+    usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+    password_hasher: Arc<dyn PasswordHasher + Sync + Send>,
+) {
+    let usuario_use_case = UsuarioUseCase::new(usuario_repository, password_hasher);
+    let Some(cpf) = config.admin_cpf.clone() else {
+        match usuario_use_case.existe_admin().await {
+            Ok(true) => {}
+            Ok(false) => eprintln!(
+                "WARNING: nenhum usuário Admin ativo e ADMIN_CPF não configurado; \
+                 defina ADMIN_CPF e ADMIN_EMAIL para criar o administrador inicial"
+            ),
+            Err(e) => eprintln!("Failed to check for admin usuario: {:?}", e),
+        }
+        return;
+    };
+    let senha_gerada = config.admin_senha.is_none();
+    let senha = config.admin_senha.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
+    let admin = BootstrapAdminInput {
+        cpf,
+        email: config.admin_email.clone(),
+        senha: senha.clone(),
+    };
+    match usuario_use_case.bootstrap_admin(admin).await {
+        Ok(Some(usuario)) => {
+            println!("Administrador inicial criado: {}", usuario.id());
+            if senha_gerada {
+                println!("Senha de uso único do administrador: {}", senha);
+            }
+        }
+        Ok(None) => println!("Administrador já existe, bootstrap ignorado"),
+        Err(e) => {
+            eprintln!("Failed to bootstrap admin: {:?}", e);
+            process::exit(1);
+        }
+    }
+}
+
+// Cada login anônimo cria um cliente; os que não se identificaram dentro do
+// TTL são removidos periodicamente
+fn agenda_purge_clientes_anonimos(cliente_repository: Arc<dyn ClienteGateway + Sync + Send>, ttl: i64) {
+    tokio::spawn(async move {
+        let cliente_use_case = ClienteUseCase::new(cliente_repository);
+        let mut intervalo = tokio::time::interval(PURGE_CLIENTES_ANONIMOS_INTERVALO);
+        loop {
+            intervalo.tick().await;
+            match cliente_use_case.purge_clientes_anonimos(ttl).await {
+                Ok(0) => {}
+                Ok(removidos) => println!("Clientes anônimos removidos: {}", removidos),
+                Err(e) => eprintln!("Failed to purge clientes anonimos: {:?}", e),
+            }
+        }
+    });
+}
+
 #[rocket::main]
 pub async fn main() -> Result<(), rocket::Error> {
     let config = Config::build();
 
-    let jwt_authentication_adapter: Arc<dyn AuthenticationAdapter + Sync + Send> =
-        Arc::new(JWTAuthenticationAdapter::new(config.secret.clone()));
+    let password_hasher: Arc<dyn PasswordHasher + Sync + Send> =
+        Arc::new(Argon2PasswordHasher::new());
 
     println!("Loading environment variables...");
-    let usuario_repository: Arc<Mutex<dyn UsuarioGateway + Sync + Send>> = {
-        println!("Connecting to Usuario pool");
-        Arc::new(Mutex::new(
-            AwsCognitoUsuarioRepository::new(String::from(config.user_pool_id_usuario)).await,
-        ))
+    let usuario_repository: Arc<dyn UsuarioGateway + Sync + Send> = match config.backend {
+        Backend::Cognito => {
+            println!("Connecting to Usuario pool");
+            Arc::new(AwsCognitoUsuarioRepository::new(config.user_pool_id_usuario.clone()).await)
+        }
+        Backend::Postgres => {
+            println!("Connecting to database for Usuario");
+            let client = connect_to_database(&config.db_url).await;
+            Arc::new(PostgresUsuarioRepository::new(client))
+        }
+        Backend::InMemory => {
+            println!("Using in-memory Usuario repository");
+            Arc::new(InMemoryUsuarioRepository::new())
+        }
     };
 
-    let cliente_repository: Arc<Mutex<dyn ClienteGateway + Sync + Send>> = {
-        println!("Connecting to Cliente pool");
+    bootstrap_admin(&config, usuario_repository.clone(), password_hasher.clone()).await;
 
-        Arc::new(Mutex::new(
-            AwsCognitoClienteRepository::new(String::from(config.user_pool_id_cliente)).await,
-        ))
+    let cliente_repository: Arc<dyn ClienteGateway + Sync + Send> = match config.backend {
+        Backend::Cognito => {
+            println!("Connecting to Cliente pool");
+            Arc::new(AwsCognitoClienteRepository::new(config.user_pool_id_cliente.clone()).await)
+        }
+        Backend::Postgres => {
+            println!("Connecting to database for Cliente");
+            let client = connect_to_database(&config.db_url).await;
+            Arc::new(PostgresClienteRepository::new(client))
+        }
+        Backend::InMemory => {
+            println!("Using in-memory Cliente repository");
+            Arc::new(InMemoryClienteRepository::new())
+        }
     };
 
+    agenda_purge_clientes_anonimos(cliente_repository.clone(), config.cliente_anonimo_ttl);
+
+    let token_revocation_store: Arc<dyn TokenRevocationStore + Sync + Send> =
+        Arc::new(InMemoryTokenRevocationStore::new());
+
+    let jwt_authentication_adapter: Arc<dyn AuthenticationAdapter + Sync + Send> =
+        Arc::new(JWTAuthenticationAdapter::new(
+            load_jwt_keys(&config),
+            config.access_token_ttl,
+            config.refresh_token_ttl,
+            usuario_repository.clone(),
+            cliente_repository.clone(),
+            token_revocation_store,
+        ));
+
+    let verification_code_store: Arc<dyn VerificationCodeStore + Sync + Send> = Arc::new(
+        InMemoryVerificationCodeStore::new(config.verification_code_ttl, VERIFICATION_CODE_MAX_ATTEMPTS),
+    );
+    let notifier: Arc<dyn Notifier + Sync + Send> = match config.notifier_file.clone() {
+        Some(arquivo) => Arc::new(LogNotifier::with_file(arquivo)),
+        None => Arc::new(LogNotifier::new()),
+    };
+    let login_attempt_store: Arc<dyn LoginAttemptStore + Sync + Send> =
+        Arc::new(InMemoryLoginAttemptStore::new(LoginThrottleSettings {
+            max_falhas_conta: config.login_max_falhas_conta,
+            max_falhas_ip: config.login_max_falhas_ip,
+            max_anonimos_ip: config.login_max_anonimos_ip,
+            bloqueio_ttl: config.login_bloqueio_ttl,
+        }));
+    let cliente_auth_settings = ClienteAuthSettings {
+        codigo_obrigatorio: config.cliente_codigo_obrigatorio,
+    };
 
     let server_config = rocket::Config::figment()
         .merge(("address", IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0))))
-        .merge(("port", 3000));
+        .merge(("port", 3000))
+        // O limite de login por IP usa o IP da conexão; X-Real-IP (padrão do
+        // Rocket) pode ser forjado pelo cliente
+        .merge(("ip_header", false));
 
     rocket::build()
         .mount("/", routes![redirect_to_docs])
@@ -69,9 +243,15 @@ pub async fn main() -> Result<(), rocket::Error> {
         .mount("/auth", auth_route::routes())
         .mount("/usuarios", usuario_route::routes())
         .mount("/clientes", cliente_route::routes())
+        .mount("/.well-known", well_known_route::routes())
         .register("/usuarios", usuario_route::catchers())
         .register("/clientes", cliente_route::catchers())
         .manage(jwt_authentication_adapter)
+        .manage(password_hasher)
+        .manage(verification_code_store)
+        .manage(notifier)
+        .manage(login_attempt_store)
+        .manage(cliente_auth_settings)
         .manage(usuario_repository)
         .manage(cliente_repository)
         .configure(server_config)
@@ -1,2 +1,4 @@
 pub mod assertion_concern;
-pub mod domain_error;
\ No newline at end of file
+pub mod domain_error;
+pub mod pagination;
+pub mod timestamp;
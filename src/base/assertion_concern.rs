@@ -1,23 +1,13 @@
-use chrono::DateTime;
+use crate::base::domain_error::{DomainError, FieldError};
 
-use crate::base::domain_error::DomainError;
-
-pub fn assert_argument_not_empty(value: String) -> Result<(), DomainError> {
-    if value.is_empty() {
-        Err(DomainError::Empty)
+pub fn assert_field_not_empty(field: &str, value: &str) -> Result<(), DomainError> {
+    if value.trim().is_empty() {
+        Err(DomainError::Validation(vec![FieldError::required(field)]))
     } else {
         Ok(())
     }
 }
 
-pub fn assert_argument_timestamp_format(value: String) -> Result<(), DomainError> {
-    let format = "%Y-%m-%d %H:%M:%S%.3f%z";
-    match DateTime::parse_from_str(&value, format) {
-        Ok(_) => Ok(()),
-        Err(_) => Err(DomainError::Invalid(value))
-    }
-}
-
 pub fn assert_argument_not_negative(value: f64) -> Result<(), DomainError> {
     if value < 0.0 {
         Err(DomainError::NonPositive)
@@ -25,3 +15,76 @@ pub fn assert_argument_not_negative(value: f64) -> Result<(), DomainError> {
         Ok(())
     }
 }
+
+// Acumula as violações de vários campos para devolver todas de uma vez
+#[derive(Debug, Default)]
+pub struct Violations(Vec<FieldError>);
+
+impl Violations {
+    pub fn new() -> Self {
+        Violations::default()
+    }
+
+    pub fn push(&mut self, error: FieldError) {
+        self.0.push(error);
+    }
+
+    // Guarda o erro de `result`, atribuindo ao campo informado os erros que
+    // não trazem campo próprio
+    pub fn check<T>(&mut self, field: &str, result: Result<T, DomainError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(DomainError::Validation(errors)) => {
+                self.0.extend(errors);
+                None
+            }
+            Err(DomainError::Empty) => {
+                self.push(FieldError::required(field));
+                None
+            }
+            Err(err) => {
+                let message = match err {
+                    DomainError::Invalid(message) => message,
+                    err => format!("{:?}", err),
+                };
+                self.push(FieldError::new(field, "invalid", &message));
+                None
+            }
+        }
+    }
+
+    pub fn into_result(self) -> Result<(), DomainError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(DomainError::Validation(self.0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_collect_all_violations() {
+        let mut violations = Violations::new();
+        violations.check("nome", assert_field_not_empty("nome", " "));
+        violations.check::<()>("senha", Err(DomainError::Empty));
+        let valor = violations.check("tipo", Ok::<_, DomainError>(1));
+        assert_eq!(valor, Some(1));
+
+        let result = violations.into_result();
+        match result {
+            Err(DomainError::Validation(errors)) => {
+                assert_eq!(errors, vec![FieldError::required("nome"), FieldError::required("senha")]);
+            }
+            _ => panic!("Esperado Err(DomainError::Validation), obtido {:?}", result),
+        }
+    }
+
+    #[test]
+    fn should_pass_without_violations() {
+        assert!(Violations::new().into_result().is_ok());
+    }
+}
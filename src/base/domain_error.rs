@@ -1,9 +1,43 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
 #[derive(Debug)]
 pub enum DomainError {
-    AlreadyExists,
+    AlreadyExists(String),
+    // O recurso está num estado que não admite a operação
+    Conflict(String),
     Empty,
     Unauthorized,
+    InactiveUser,
+    PasswordChangeRequired,
+    // Segundos até o bloqueio expirar
+    TooManyAttempts(i64),
     NotFound,
     Invalid(String),
-    NonPositive
-}
\ No newline at end of file
+    Validation(Vec<FieldError>),
+    NonPositive,
+    // Falha de infraestrutura (banco, Cognito, arquivo); o texto vai só para o log
+    Internal(String),
+}
+
+// Violação de uma regra de validação em um campo de entrada
+#[derive(Clone, Debug, PartialEq, Serialize, JsonSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: &str, message: &str) -> Self {
+        FieldError {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn required(field: &str) -> Self {
+        FieldError::new(field, "required", &format!("{} é obrigatório", field))
+    }
+}
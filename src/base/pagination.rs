@@ -0,0 +1,211 @@
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::base::domain_error::DomainError;
+
+pub const DEFAULT_LIMIT: usize = 50;
+pub const MAX_LIMIT: usize = 100;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Ordem {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl FromStr for Ordem {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Ordem, Self::Err> {
+        match input {
+            "asc" => Ok(Ordem::Asc),
+            "desc" => Ok(Ordem::Desc),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CampoOrdenacao {
+    #[default]
+    Id,
+    Nome,
+    DataCriacao,
+}
+
+impl FromStr for CampoOrdenacao {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<CampoOrdenacao, Self::Err> {
+        match input {
+            "id" => Ok(CampoOrdenacao::Id),
+            "nome" => Ok(CampoOrdenacao::Nome),
+            "data_criacao" => Ok(CampoOrdenacao::DataCriacao),
+            _ => Err(()),
+        }
+    }
+}
+
+// Página pedida pelo cliente da API. O cursor é opaco para quem chama; hoje
+// ele codifica apenas o deslocamento a partir do início da listagem.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageRequest {
+    pub limit: usize,
+    pub offset: usize,
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        PageRequest {
+            limit: DEFAULT_LIMIT,
+            offset: 0,
+        }
+    }
+}
+
+impl PageRequest {
+    pub fn new(limit: Option<usize>, cursor: Option<String>) -> Result<Self, DomainError> {
+        let limit = match limit {
+            Some(0) => return Err(DomainError::Invalid("limit".to_string())),
+            Some(limit) => limit.min(MAX_LIMIT),
+            None => DEFAULT_LIMIT,
+        };
+        let offset = match cursor {
+            Some(cursor) => decode_cursor(&cursor).ok_or(DomainError::Invalid("cursor".to_string()))?,
+            None => 0,
+        };
+        Ok(PageRequest { limit, offset })
+    }
+
+    fn next_cursor(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("offset:{}", self.offset + self.limit))
+    }
+}
+
+fn decode_cursor(cursor: &str) -> Option<usize> {
+    let decoded = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    let decoded = String::from_utf8(decoded).ok()?;
+    decoded.strip_prefix("offset:")?.parse::<usize>().ok()
+}
+
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    // Recorta a página de uma listagem completa, já filtrada e ordenada
+    pub fn from_all(items: Vec<T>, page: &PageRequest) -> Self {
+        let has_more = items.len() > page.offset + page.limit;
+        let items = items.into_iter().skip(page.offset).take(page.limit).collect();
+        Page {
+            items,
+            next_cursor: if has_more { Some(page.next_cursor()) } else { None },
+        }
+    }
+
+    // Monta a página a partir de uma busca que pediu `limit + 1` registros
+    // a partir do deslocamento, usando o excedente só para saber se há mais
+    pub fn from_fetched(mut items: Vec<T>, page: &PageRequest) -> Self {
+        let has_more = items.len() > page.limit;
+        items.truncate(page.limit);
+        Page {
+            items,
+            next_cursor: if has_more { Some(page.next_cursor()) } else { None },
+        }
+    }
+}
+
+pub fn parse_data_filtro(value: Option<String>, campo: &str) -> Result<Option<DateTime<Utc>>, DomainError> {
+    match value {
+        Some(value) => DateTime::parse_from_rfc3339(&value)
+            .map(|date| Some(date.with_timezone(&Utc)))
+            .map_err(|_| DomainError::Invalid(campo.to_string())),
+        None => Ok(None),
+    }
+}
+
+pub fn parse_campo_filtro<T: FromStr>(value: Option<String>, campo: &str) -> Result<Option<T>, DomainError> {
+    match value {
+        Some(value) => value
+            .parse::<T>()
+            .map(Some)
+            .map_err(|_| DomainError::Invalid(campo.to_string())),
+        None => Ok(None),
+    }
+}
+
+pub fn timestamp_in_range(
+    timestamp: &DateTime<Utc>,
+    de: &Option<DateTime<Utc>>,
+    ate: &Option<DateTime<Utc>>,
+) -> bool {
+    de.is_none_or(|de| *timestamp >= de) && ate.is_none_or(|ate| *timestamp <= ate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_use_defaults() {
+        let page = PageRequest::new(None, None).unwrap();
+        assert_eq!(page, PageRequest { limit: DEFAULT_LIMIT, offset: 0 });
+        let page = PageRequest::new(Some(1000), None).unwrap();
+        assert_eq!(page.limit, MAX_LIMIT);
+        assert!(PageRequest::new(Some(0), None).is_err());
+    }
+
+    #[test]
+    fn should_follow_cursor() {
+        let page = PageRequest::new(Some(2), None).unwrap();
+        let first = Page::from_all(vec![1, 2, 3, 4, 5], &page);
+        assert_eq!(first.items, vec![1, 2]);
+
+        let page = PageRequest::new(Some(2), first.next_cursor).unwrap();
+        let second = Page::from_all(vec![1, 2, 3, 4, 5], &page);
+        assert_eq!(second.items, vec![3, 4]);
+
+        let page = PageRequest::new(Some(2), second.next_cursor).unwrap();
+        let last = Page::from_all(vec![1, 2, 3, 4, 5], &page);
+        assert_eq!(last.items, vec![5]);
+        assert_eq!(last.next_cursor, None);
+    }
+
+    #[test]
+    fn should_detect_more_from_fetched() {
+        let page = PageRequest::new(Some(2), None).unwrap();
+        let fetched = Page::from_fetched(vec![1, 2, 3], &page);
+        assert_eq!(fetched.items, vec![1, 2]);
+        assert!(fetched.next_cursor.is_some());
+        let fetched = Page::from_fetched(vec![1, 2], &page);
+        assert_eq!(fetched.next_cursor, None);
+    }
+
+    #[test]
+    fn should_reject_invalid_cursor() {
+        let result = PageRequest::new(None, Some("nao-e-um-cursor".to_string()));
+        assert!(
+            matches!(result, Err(DomainError::Invalid(_))),
+            "Esperado Err(DomainError::Invalid), obtido {:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn should_filter_by_date_range() {
+        let de = parse_data_filtro(Some("2024-01-01T00:00:00Z".to_string()), "criado_de").unwrap();
+        let ate = parse_data_filtro(Some("2024-01-31T23:59:59Z".to_string()), "criado_ate").unwrap();
+        let dentro = DateTime::parse_from_rfc3339("2024-01-15T10:00:00Z").unwrap().with_timezone(&Utc);
+        let fora = DateTime::parse_from_rfc3339("2024-02-01T10:00:00Z").unwrap().with_timezone(&Utc);
+        assert!(timestamp_in_range(&dentro, &de, &ate));
+        assert!(!timestamp_in_range(&fora, &de, &ate));
+        assert!(timestamp_in_range(&fora, &None, &None));
+        assert!(parse_data_filtro(Some("ontem".to_string()), "criado_de").is_err());
+    }
+}
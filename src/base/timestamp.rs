@@ -0,0 +1,46 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::base::domain_error::DomainError;
+
+// Formato usado antes das datas passarem a RFC 3339; ainda aparece nos
+// atributos já gravados no Cognito
+pub const LEGACY_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f%z";
+
+pub fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, DomainError> {
+    DateTime::parse_from_rfc3339(value)
+        .or_else(|_| DateTime::parse_from_str(value, LEGACY_FORMAT))
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| DomainError::Invalid(value.to_string()))
+}
+
+pub fn format_timestamp(value: &DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_rfc3339() {
+        let timestamp = parse_timestamp("2024-02-18T13:45:10.250-03:00").unwrap();
+        assert_eq!(timestamp, Utc.with_ymd_and_hms(2024, 2, 18, 16, 45, 10).unwrap() + chrono::Duration::milliseconds(250));
+    }
+
+    #[test]
+    fn test_parse_legacy_format() {
+        let timestamp = parse_timestamp("2024-02-18 16:45:10.250+0000").unwrap();
+        assert_eq!(format_timestamp(&timestamp), "2024-02-18T16:45:10.250Z");
+    }
+
+    #[test]
+    fn test_parse_invalid_format() {
+        let result = parse_timestamp("18-02-2024");
+        assert!(
+            matches!(result, Err(DomainError::Invalid(_))),
+            "Esperado Err(DomainError::Invalid), obtido {:?}",
+            result
+        );
+    }
+}
@@ -1,3 +1,5 @@
 pub mod auth_controller;
+pub mod cliente_auth_controller;
 pub mod cliente_controller;
+pub mod password_reset_controller;
 pub mod usuario_controller;
@@ -2,14 +2,15 @@ use std::sync::Arc;
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::sync::Mutex;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::cpf::Cpf;
-use crate::traits::authentication_adapter::AuthenticationAdapter;
+use crate::traits::authentication_adapter::{AuthenticationAdapter, AuthenticationTokens};
+use crate::traits::login_attempt_store::{Lockout, LoginAttemptKey, LoginAttemptStore};
+use crate::traits::password_hasher::PasswordHasher;
 use crate::use_cases::gerenciamento_de_usuarios_use_case::UsuarioUseCase;
 use crate::traits::usuario_gateway::UsuarioGateway;
-use crate::entities::usuario::Usuario;
+use crate::entities::usuario::{Status, Usuario};
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct LoginInput {
@@ -17,41 +18,388 @@ pub struct LoginInput {
     senha: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TrocaSenhaInput {
+    cpf: String,
+    senha: String,
+    nova_senha: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RefreshTokenInput {
+    refresh_token: String,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct AuthenticationResponse {
-    token: String,
+    #[serde(flatten)]
+    tokens: AuthenticationTokens,
     usuario: Usuario,
 }
 
 pub struct AuthController {
     usuario_use_case: UsuarioUseCase,
     authentication_adapter: Arc<dyn AuthenticationAdapter + Sync + Send>,
+    login_attempt_store: Arc<dyn LoginAttemptStore + Sync + Send>,
 }
 
 impl AuthController {
     pub fn new(
-            usuario_repository: Arc<Mutex<dyn UsuarioGateway + Sync + Send>>,
-            authentication_adapter: Arc<dyn AuthenticationAdapter + Sync + Send>
+            usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+            authentication_adapter: Arc<dyn AuthenticationAdapter + Sync + Send>,
+            password_hasher: Arc<dyn PasswordHasher + Sync + Send>,
+            login_attempt_store: Arc<dyn LoginAttemptStore + Sync + Send>,
         ) -> AuthController {
-        let usuario_use_case = UsuarioUseCase::new(usuario_repository);
-        AuthController { usuario_use_case, authentication_adapter }
-    }
-
-    pub async fn login(&self, login_input: LoginInput) -> Result<AuthenticationResponse, DomainError> {
-        let cpf = Cpf::new(login_input.cpf.clone())?;
-        let usuario = self.usuario_use_case.get_usuario_by_cpf(cpf).await;
-        match usuario {
-            Ok(usuario) => {
-                if !usuario.validate_senha(&login_input.senha) {
-                    return Err(DomainError::Invalid("Senha inválida".to_string()));
-                }
-                let token = &self.authentication_adapter.get_token(usuario.clone()).await?;
-                Ok(AuthenticationResponse {
-                    token: token.clone(),
-                    usuario,
-                })
+        let usuario_use_case = UsuarioUseCase::new(usuario_repository, password_hasher);
+        AuthController { usuario_use_case, authentication_adapter, login_attempt_store }
+    }
+
+    // Falhas contam para o CPF e para o IP de origem. CPF desconhecido e senha
+    // errada têm a mesma resposta, para não revelar quais CPFs estão cadastrados
+    async fn autentica(&self, cpf: String, senha: &str, ip: Option<String>) -> Result<Usuario, DomainError> {
+        let cpf = Cpf::new(cpf)?;
+        let conta = LoginAttemptKey::Conta(cpf.formatted());
+        let mut chaves = vec![conta.clone()];
+        if let Some(ip) = ip {
+            chaves.push(LoginAttemptKey::Ip(ip));
+        }
+        for (reservadas, chave) in chaves.iter().enumerate() {
+            if let Err(err) = self.login_attempt_store.reserve(chave.clone()).await {
+                self.libera(&chaves[..reservadas]).await?;
+                return Err(err);
+            }
+        }
+        let usuario = match self.usuario_use_case.get_usuario_by_cpf(cpf).await {
+            Ok(usuario) if self.usuario_use_case.validate_senha(&usuario, senha) => usuario,
+            Ok(_) => return self.registra_falha(chaves).await,
+            Err(DomainError::NotFound) => {
+                self.usuario_use_case.simula_validacao_senha(senha);
+                return self.registra_falha(chaves).await;
             }
-            Err(_) => return Err(DomainError::Invalid("Usuário não encontrado".to_string())),
+            Err(err) => {
+                self.libera(&chaves).await?;
+                return Err(err);
+            }
+        };
+        self.login_attempt_store.register_success(conta).await?;
+        // O sucesso zera só a conta; o IP apenas devolve a reserva
+        self.libera(&chaves[1..]).await?;
+        if *usuario.status() == Status::Inativo {
+            return Err(DomainError::InactiveUser);
+        }
+        Ok(usuario)
+    }
+
+    async fn registra_falha(&self, chaves: Vec<LoginAttemptKey>) -> Result<Usuario, DomainError> {
+        for chave in chaves {
+            self.login_attempt_store.register_failure(chave).await?;
+        }
+        Err(DomainError::Unauthorized)
+    }
+
+    async fn libera(&self, chaves: &[LoginAttemptKey]) -> Result<(), DomainError> {
+        for chave in chaves {
+            self.login_attempt_store.release(chave.clone()).await?;
         }
+        Ok(())
     }
-}
\ No newline at end of file
+
+    pub async fn login(&self, login_input: LoginInput, ip: Option<String>) -> Result<AuthenticationResponse, DomainError> {
+        let usuario = self.autentica(login_input.cpf, &login_input.senha, ip).await?;
+        if usuario.troca_senha_obrigatoria() {
+            return Err(DomainError::PasswordChangeRequired);
+        }
+        let usuario = match self.usuario_use_case.upgrade_senha(usuario.clone(), &login_input.senha).await {
+            Ok(usuario) => usuario,
+            Err(err) => {
+                println!("Failed to upgrade senha for usuario {}: {:?}", usuario.id(), err);
+                usuario
+            }
+        };
+        let tokens = self.authentication_adapter.get_token(usuario.clone()).await?;
+        Ok(AuthenticationResponse {
+            tokens,
+            usuario,
+        })
+    }
+
+    // Também serve para o primeiro acesso, quando o login fica bloqueado até a
+    // troca; por isso autentica com a senha atual em vez de exigir token
+    pub async fn troca_senha(
+        &self,
+        troca_senha_input: TrocaSenhaInput,
+        ip: Option<String>,
+    ) -> Result<AuthenticationResponse, DomainError> {
+        let usuario = self.autentica(troca_senha_input.cpf, &troca_senha_input.senha, ip).await?;
+        let usuario = self.usuario_use_case.troca_senha(usuario, troca_senha_input.nova_senha).await?;
+        let tokens = self.authentication_adapter.get_token(usuario.clone()).await?;
+        Ok(AuthenticationResponse {
+            tokens,
+            usuario,
+        })
+    }
+
+    pub async fn refresh(&self, refresh_token_input: RefreshTokenInput) -> Result<AuthenticationResponse, DomainError> {
+        let refresh_token = refresh_token_input.refresh_token;
+        let user_id = self.authentication_adapter.validate_refresh_token(refresh_token.clone()).await?;
+        let user_id = user_id.parse::<usize>().map_err(|_| DomainError::Unauthorized)?;
+        let usuario = self
+            .usuario_use_case
+            .get_usuario_by_id(user_id)
+            .await
+            .map_err(|_| DomainError::Unauthorized)?;
+        let tokens = self
+            .authentication_adapter
+            .rotate_refresh_token(refresh_token, usuario.clone())
+            .await?;
+        Ok(AuthenticationResponse {
+            tokens,
+            usuario,
+        })
+    }
+
+    pub async fn logout(&self, token: String) -> Result<(), DomainError> {
+        self.authentication_adapter.revoke_token(token).await
+    }
+
+    pub async fn lista_bloqueios(&self) -> Result<Vec<Lockout>, DomainError> {
+        self.login_attempt_store.list_lockouts().await
+    }
+
+    pub async fn limpa_bloqueio(&self, chave: LoginAttemptKey) -> Result<(), DomainError> {
+        self.login_attempt_store.clear(chave).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::email::Email;
+    use crate::entities::usuario::Tipo;
+    use crate::traits::authentication_adapter::MockAuthenticationAdapter;
+    use crate::traits::login_attempt_store::MockLoginAttemptStore;
+    use crate::traits::password_hasher::MockPasswordHasher;
+    use crate::traits::usuario_gateway::MockUsuarioGateway;
+    use tokio;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn data_fixa() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2021, 10, 10, 10, 0, 0).unwrap()
+    }
+
+    fn login_attempt_store() -> MockLoginAttemptStore {
+        let mut login_attempt_store = MockLoginAttemptStore::new();
+        login_attempt_store.expect_reserve().returning(|_| Ok(()));
+        login_attempt_store.expect_register_failure().returning(|_| Ok(()));
+        login_attempt_store.expect_register_success().returning(|_| Ok(()));
+        login_attempt_store.expect_release().returning(|_| Ok(()));
+        login_attempt_store
+    }
+
+    fn auth_controller(status: Status) -> AuthController {
+        auth_controller_com(status, false, login_attempt_store())
+    }
+
+    fn auth_controller_com_troca(status: Status, troca_senha_obrigatoria: bool) -> AuthController {
+        auth_controller_com(status, troca_senha_obrigatoria, login_attempt_store())
+    }
+
+    fn auth_controller_com(
+        status: Status,
+        troca_senha_obrigatoria: bool,
+        login_attempt_store: MockLoginAttemptStore,
+    ) -> AuthController {
+        let mut usuario_repository = MockUsuarioGateway::new();
+        usuario_repository.expect_get_usuario_by_cpf().returning(move |cpf| {
+            let mut usuario = Usuario::new(
+                1,
+                "nome".to_string(),
+                Email::new("fulano@email.com".to_string()).unwrap(),
+                cpf,
+                "hash:senha".to_string(),
+                Tipo::Cozinha,
+                status.clone(),
+                data_fixa(),
+                data_fixa(),
+            );
+            usuario.set_troca_senha_obrigatoria(troca_senha_obrigatoria);
+            Ok(usuario)
+        });
+        usuario_repository.expect_update_usuario().returning(Ok);
+
+        let mut password_hasher = MockPasswordHasher::new();
+        password_hasher.expect_verify().returning(|senha, hash| format!("hash:{}", senha) == hash);
+        password_hasher.expect_needs_rehash().returning(|_| false);
+        password_hasher.expect_hash().returning(|senha| Ok(format!("hash:{}", senha)));
+
+        let mut authentication_adapter = MockAuthenticationAdapter::new();
+        authentication_adapter.expect_get_token().returning(|_| {
+            Ok(AuthenticationTokens {
+                token: "token".to_string(),
+                token_expiration: 1,
+                refresh_token: "refresh_token".to_string(),
+                refresh_token_expiration: 2,
+            })
+        });
+
+        AuthController::new(
+            Arc::new(usuario_repository),
+            Arc::new(authentication_adapter),
+            Arc::new(password_hasher),
+            Arc::new(login_attempt_store),
+        )
+    }
+
+    fn login_input(senha: &str) -> LoginInput {
+        LoginInput {
+            cpf: "123.456.789-09".to_string(),
+            senha: senha.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_login_active_usuario() {
+        let result = auth_controller(Status::Ativo).login(login_input("senha"), None).await;
+        assert_eq!(result.unwrap().tokens.token, "token");
+    }
+
+    #[tokio::test]
+    async fn test_login_inactive_usuario() {
+        let result = auth_controller(Status::Inativo).login(login_input("senha"), None).await;
+        assert!(
+            matches!(result, Err(DomainError::InactiveUser)),
+            "Esperado Err(DomainError::InactiveUser), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_login_wrong_senha() {
+        let result = auth_controller(Status::Inativo).login(login_input("errada"), None).await;
+        assert!(matches!(result, Err(DomainError::Unauthorized)));
+    }
+
+    #[tokio::test]
+    async fn test_login_failure_counts_for_cpf_and_ip() {
+        let mut login_attempt_store = MockLoginAttemptStore::new();
+        login_attempt_store.expect_reserve().times(2).returning(|_| Ok(()));
+        login_attempt_store
+            .expect_register_failure()
+            .withf(|chave| {
+                *chave == LoginAttemptKey::Conta("123.456.789-09".to_string())
+                    || *chave == LoginAttemptKey::Ip("10.0.0.1".to_string())
+            })
+            .times(2)
+            .returning(|_| Ok(()));
+        login_attempt_store.expect_register_success().times(0);
+        login_attempt_store.expect_release().times(0);
+
+        let controller = auth_controller_com(Status::Ativo, false, login_attempt_store);
+        let result = controller.login(login_input("errada"), Some("10.0.0.1".to_string())).await;
+        assert!(
+            matches!(result, Err(DomainError::Unauthorized)),
+            "Esperado Err(DomainError::Unauthorized), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_login_unknown_cpf_looks_like_wrong_senha() {
+        let mut usuario_repository = MockUsuarioGateway::new();
+        usuario_repository.expect_get_usuario_by_cpf().returning(|_| Err(DomainError::NotFound));
+        let mut login_attempt_store = MockLoginAttemptStore::new();
+        login_attempt_store.expect_reserve().returning(|_| Ok(()));
+        login_attempt_store.expect_register_failure().times(1).returning(|_| Ok(()));
+        let mut password_hasher = MockPasswordHasher::new();
+        password_hasher.expect_verify().times(1).returning(|_, _| false);
+
+        let controller = AuthController::new(
+            Arc::new(usuario_repository),
+            Arc::new(MockAuthenticationAdapter::new()),
+            Arc::new(password_hasher),
+            Arc::new(login_attempt_store),
+        );
+        let result = controller.login(login_input("senha"), None).await;
+        assert!(
+            matches!(result, Err(DomainError::Unauthorized)),
+            "Esperado Err(DomainError::Unauthorized), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_login_locked_out() {
+        let mut login_attempt_store = MockLoginAttemptStore::new();
+        login_attempt_store
+            .expect_reserve()
+            .returning(|_| Err(DomainError::TooManyAttempts(30)));
+        login_attempt_store.expect_register_success().times(0);
+
+        let controller = auth_controller_com(Status::Ativo, false, login_attempt_store);
+        let result = controller.login(login_input("senha"), None).await;
+        assert!(
+            matches!(result, Err(DomainError::TooManyAttempts(30))),
+            "Esperado Err(DomainError::TooManyAttempts), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_login_success_releases_ip() {
+        let mut login_attempt_store = MockLoginAttemptStore::new();
+        login_attempt_store.expect_reserve().times(2).returning(|_| Ok(()));
+        login_attempt_store
+            .expect_register_success()
+            .withf(|chave| *chave == LoginAttemptKey::Conta("123.456.789-09".to_string()))
+            .times(1)
+            .returning(|_| Ok(()));
+        login_attempt_store
+            .expect_release()
+            .withf(|chave| *chave == LoginAttemptKey::Ip("10.0.0.1".to_string()))
+            .times(1)
+            .returning(|_| Ok(()));
+        login_attempt_store.expect_register_failure().times(0);
+
+        let controller = auth_controller_com(Status::Ativo, false, login_attempt_store);
+        let result = controller.login(login_input("senha"), Some("10.0.0.1".to_string())).await;
+        assert!(result.is_ok(), "Esperado Ok, obtido {:?}", result.err());
+    }
+
+    #[tokio::test]
+    async fn test_login_requires_password_change() {
+        let result = auth_controller_com_troca(Status::Ativo, true).login(login_input("senha"), None).await;
+        assert!(
+            matches!(result, Err(DomainError::PasswordChangeRequired)),
+            "Esperado Err(DomainError::PasswordChangeRequired), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_troca_senha_clears_flag() {
+        let troca_senha_input = TrocaSenhaInput {
+            cpf: "123.456.789-09".to_string(),
+            senha: "senha".to_string(),
+            nova_senha: "nova".to_string(),
+        };
+        let result = auth_controller_com_troca(Status::Ativo, true).troca_senha(troca_senha_input, None).await.unwrap();
+        assert_eq!(result.tokens.token, "token");
+        assert_eq!(result.usuario.senha(), "hash:nova");
+        assert!(!result.usuario.troca_senha_obrigatoria());
+    }
+
+    #[tokio::test]
+    async fn test_troca_senha_rejects_same_senha() {
+        let troca_senha_input = TrocaSenhaInput {
+            cpf: "123.456.789-09".to_string(),
+            senha: "senha".to_string(),
+            nova_senha: "senha".to_string(),
+        };
+        let result = auth_controller_com_troca(Status::Ativo, true).troca_senha(troca_senha_input, None).await;
+        assert!(
+            matches!(result, Err(DomainError::Validation(_))),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
+            result
+        );
+    }
+}
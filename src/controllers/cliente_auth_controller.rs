@@ -0,0 +1,307 @@
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::base::domain_error::DomainError;
+use crate::entities::cliente::Cliente;
+use crate::entities::cpf::Cpf;
+use crate::traits::authentication_adapter::{AuthenticationAdapter, ClienteAuthenticationToken};
+use crate::traits::cliente_gateway::ClienteGateway;
+use crate::traits::login_attempt_store::{LoginAttemptKey, LoginAttemptStore};
+use crate::traits::notifier::Notifier;
+use crate::traits::verification_code_store::VerificationCodeStore;
+use crate::use_cases::gerenciamento_de_clientes_use_case::ClienteUseCase;
+
+#[derive(Clone, Debug)]
+pub struct ClienteAuthSettings {
+    pub codigo_obrigatorio: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SolicitaCodigoInput {
+    cpf: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ClienteLoginInput {
+    cpf: String,
+    codigo: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ClienteAuthenticationResponse {
+    #[serde(flatten)]
+    token: ClienteAuthenticationToken,
+    cliente: Cliente,
+}
+
+pub struct ClienteAuthController {
+    cliente_use_case: ClienteUseCase,
+    authentication_adapter: Arc<dyn AuthenticationAdapter + Sync + Send>,
+    verification_code_store: Arc<dyn VerificationCodeStore + Sync + Send>,
+    notifier: Arc<dyn Notifier + Sync + Send>,
+    login_attempt_store: Arc<dyn LoginAttemptStore + Sync + Send>,
+    settings: ClienteAuthSettings,
+}
+
+fn codigo_key(cpf: &Cpf) -> String {
+    format!("cliente:{}", cpf.formatted())
+}
+
+impl ClienteAuthController {
+    pub fn new(
+            cliente_repository: Arc<dyn ClienteGateway + Sync + Send>,
+            authentication_adapter: Arc<dyn AuthenticationAdapter + Sync + Send>,
+            verification_code_store: Arc<dyn VerificationCodeStore + Sync + Send>,
+            notifier: Arc<dyn Notifier + Sync + Send>,
+            login_attempt_store: Arc<dyn LoginAttemptStore + Sync + Send>,
+            settings: ClienteAuthSettings,
+        ) -> ClienteAuthController {
+        let cliente_use_case = ClienteUseCase::new(cliente_repository);
+        ClienteAuthController {
+            cliente_use_case,
+            authentication_adapter,
+            verification_code_store,
+            notifier,
+            login_attempt_store,
+            settings,
+        }
+    }
+
+    pub async fn solicita_codigo(&self, input: SolicitaCodigoInput) -> Result<(), DomainError> {
+        let cpf = Cpf::new(input.cpf)?;
+        let cliente = self.cliente_use_case.get_cliente_by_cpf(cpf.clone()).await?;
+        // Todo cliente encontrado pelo CPF foi identificado e tem e-mail
+        let email = cliente.email().clone().ok_or(DomainError::NotFound)?;
+        let codigo = self.verification_code_store.issue(codigo_key(&cpf)).await?;
+        self.notifier
+            .notify(
+                email.0,
+                "Código de identificação".to_string(),
+                format!("Seu código de identificação é {}", codigo),
+            )
+            .await
+    }
+
+    pub async fn login(&self, input: ClienteLoginInput) -> Result<ClienteAuthenticationResponse, DomainError> {
+        let cpf = Cpf::new(input.cpf)?;
+        let cliente = self.cliente_use_case.get_cliente_by_cpf(cpf.clone()).await?;
+        match input.codigo {
+            Some(codigo) => self.verification_code_store.verify(codigo_key(&cpf), codigo).await?,
+            None if self.settings.codigo_obrigatorio => return Err(DomainError::Unauthorized),
+            None => {}
+        }
+        let token = self.authentication_adapter.get_cliente_token(cliente.clone()).await?;
+        Ok(ClienteAuthenticationResponse { token, cliente })
+    }
+
+    // Cada login anônimo cria um cliente, então conta para o limite do IP de
+    // origem mesmo dando certo
+    pub async fn login_anonimo(&self, ip: Option<String>) -> Result<ClienteAuthenticationResponse, DomainError> {
+        let chave = ip.map(LoginAttemptKey::Anonimo);
+        if let Some(chave) = &chave {
+            self.login_attempt_store.reserve(chave.clone()).await?;
+        }
+        let cliente = match self.cliente_use_case.create_cliente_anonimo().await {
+            Ok(cliente) => cliente,
+            Err(err) => {
+                if let Some(chave) = chave {
+                    self.login_attempt_store.release(chave).await?;
+                }
+                return Err(err);
+            }
+        };
+        if let Some(chave) = chave {
+            self.login_attempt_store.register_failure(chave).await?;
+        }
+        let token = self.authentication_adapter.get_cliente_token(cliente.clone()).await?;
+        Ok(ClienteAuthenticationResponse { token, cliente })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::email::Email;
+    use crate::traits::authentication_adapter::MockAuthenticationAdapter;
+    use crate::traits::cliente_gateway::MockClienteGateway;
+    use crate::traits::login_attempt_store::MockLoginAttemptStore;
+    use crate::traits::notifier::MockNotifier;
+    use crate::traits::verification_code_store::MockVerificationCodeStore;
+    use mockall::predicate::*;
+    use tokio;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn data_fixa() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2021, 10, 10, 10, 0, 0).unwrap()
+    }
+
+    fn cliente_auth_controller(
+        codigo_obrigatorio: bool,
+        verification_code_store: MockVerificationCodeStore,
+        notifier: MockNotifier,
+    ) -> ClienteAuthController {
+        cliente_auth_controller_com(codigo_obrigatorio, verification_code_store, notifier, MockLoginAttemptStore::new())
+    }
+
+    fn cliente_auth_controller_com(
+        codigo_obrigatorio: bool,
+        verification_code_store: MockVerificationCodeStore,
+        notifier: MockNotifier,
+        login_attempt_store: MockLoginAttemptStore,
+    ) -> ClienteAuthController {
+        let mut cliente_repository = MockClienteGateway::new();
+        cliente_repository
+            .expect_create_cliente()
+            .returning(|cliente| Ok(Cliente::anonimo(2, *cliente.data_criacao(), *cliente.data_atualizacao())));
+        cliente_repository.expect_get_cliente_by_cpf().returning(|cpf| {
+            Ok(Cliente::new(
+                1,
+                "nome".to_string(),
+                Email::new("cliente@email.com".to_string()).unwrap(),
+                cpf,
+                data_fixa(),
+                data_fixa(),
+            ))
+        });
+
+        let mut authentication_adapter = MockAuthenticationAdapter::new();
+        authentication_adapter.expect_get_cliente_token().returning(|_| {
+            Ok(ClienteAuthenticationToken {
+                token: "token".to_string(),
+                token_expiration: 1,
+            })
+        });
+
+        ClienteAuthController::new(
+            Arc::new(cliente_repository),
+            Arc::new(authentication_adapter),
+            Arc::new(verification_code_store),
+            Arc::new(notifier),
+            Arc::new(login_attempt_store),
+            ClienteAuthSettings { codigo_obrigatorio },
+        )
+    }
+
+    fn login_input(codigo: Option<&str>) -> ClienteLoginInput {
+        ClienteLoginInput {
+            cpf: "123.456.789-09".to_string(),
+            codigo: codigo.map(|codigo| codigo.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_login_by_cpf() {
+        let controller = cliente_auth_controller(false, MockVerificationCodeStore::new(), MockNotifier::new());
+        let result = controller.login(login_input(None)).await.unwrap();
+        assert_eq!(result.token.token, "token");
+        assert_eq!(result.cliente.id(), &1);
+    }
+
+    #[tokio::test]
+    async fn test_login_requires_codigo() {
+        let controller = cliente_auth_controller(true, MockVerificationCodeStore::new(), MockNotifier::new());
+        let result = controller.login(login_input(None)).await;
+        assert!(
+            matches!(result, Err(DomainError::Unauthorized)),
+            "Esperado Err(DomainError::Unauthorized), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_login_with_codigo() {
+        let mut verification_code_store = MockVerificationCodeStore::new();
+        verification_code_store
+            .expect_verify()
+            .with(eq("cliente:123.456.789-09".to_string()), always())
+            .returning(|_, codigo| {
+                if codigo == "123456" {
+                    Ok(())
+                } else {
+                    Err(DomainError::Unauthorized)
+                }
+            });
+        let controller = cliente_auth_controller(true, verification_code_store, MockNotifier::new());
+        assert!(controller.login(login_input(Some("123456"))).await.is_ok());
+        let result = controller.login(login_input(Some("654321"))).await;
+        assert!(matches!(result, Err(DomainError::Unauthorized)));
+    }
+
+    #[tokio::test]
+    async fn test_solicita_codigo_sends_email() {
+        let mut verification_code_store = MockVerificationCodeStore::new();
+        verification_code_store
+            .expect_issue()
+            .returning(|_| Ok("123456".to_string()));
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|destinatario, _, mensagem| {
+                destinatario == "cliente@email.com" && mensagem.contains("123456")
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        let controller = cliente_auth_controller(true, verification_code_store, notifier);
+        let input = SolicitaCodigoInput {
+            cpf: "123.456.789-09".to_string(),
+        };
+        assert!(controller.solicita_codigo(input).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_login_anonimo() {
+        let controller = cliente_auth_controller(true, MockVerificationCodeStore::new(), MockNotifier::new());
+        let result = controller.login_anonimo(None).await.unwrap();
+        assert_eq!(result.token.token, "token");
+        assert!(result.cliente.is_anonimo());
+        assert_eq!(result.cliente.id(), &2);
+    }
+
+    #[tokio::test]
+    async fn test_login_anonimo_counts_for_ip() {
+        let mut login_attempt_store = MockLoginAttemptStore::new();
+        login_attempt_store
+            .expect_reserve()
+            .with(eq(LoginAttemptKey::Anonimo("10.0.0.1".to_string())))
+            .times(1)
+            .returning(|_| Ok(()));
+        login_attempt_store
+            .expect_register_failure()
+            .with(eq(LoginAttemptKey::Anonimo("10.0.0.1".to_string())))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let controller = cliente_auth_controller_com(
+            false,
+            MockVerificationCodeStore::new(),
+            MockNotifier::new(),
+            login_attempt_store,
+        );
+        let result = controller.login_anonimo(Some("10.0.0.1".to_string())).await;
+        assert!(result.is_ok(), "Esperado Ok, obtido {:?}", result.err());
+    }
+
+    #[tokio::test]
+    async fn test_login_anonimo_limited_by_ip() {
+        let mut login_attempt_store = MockLoginAttemptStore::new();
+        login_attempt_store
+            .expect_reserve()
+            .returning(|_| Err(DomainError::TooManyAttempts(60)));
+        login_attempt_store.expect_register_failure().times(0);
+
+        let controller = cliente_auth_controller_com(
+            false,
+            MockVerificationCodeStore::new(),
+            MockNotifier::new(),
+            login_attempt_store,
+        );
+        let result = controller.login_anonimo(Some("10.0.0.1".to_string())).await;
+        assert!(
+            matches!(result, Err(DomainError::TooManyAttempts(60))),
+            "Esperado Err(DomainError::TooManyAttempts), obtido {:?}",
+            result
+        );
+    }
+}
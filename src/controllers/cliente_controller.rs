@@ -1,9 +1,11 @@
-use tokio::sync::Mutex;
 use std::sync::Arc;
 
 use crate::base::domain_error::DomainError;
 use crate::traits::cliente_gateway::ClienteGateway;
-use crate::use_cases::gerenciamento_de_clientes_use_case::{ClienteUseCase, CreateClienteInput};
+use crate::base::pagination::Page;
+use crate::use_cases::gerenciamento_de_clientes_use_case::{
+    ClienteUseCase, CreateClienteInput, ListaClientesInput, UpdateClienteInput,
+};
 use crate::entities::cliente::Cliente;
 use crate::entities::cpf::Cpf;
 
@@ -12,15 +14,15 @@ pub struct ClienteController {
 }
 
 impl ClienteController {
-    pub fn new(cliente_repository: Arc<Mutex<dyn ClienteGateway + Sync + Send>> ) -> ClienteController {
+    pub fn new(cliente_repository: Arc<dyn ClienteGateway + Sync + Send> ) -> ClienteController {
         let cliente_use_case = ClienteUseCase::new(cliente_repository);
         ClienteController {
             cliente_use_case
         }
     }
 
-    pub async fn lista_clientes(&self) -> Result<Vec<Cliente>, DomainError> {
-        self.cliente_use_case.get_clientes().await
+    pub async fn lista_clientes(&self, filtro: ListaClientesInput) -> Result<Page<Cliente>, DomainError> {
+        self.cliente_use_case.lista_clientes(filtro).await
     }
 
     pub async fn busca_cliente_por_cpf(
@@ -30,6 +32,13 @@ impl ClienteController {
         self.cliente_use_case.get_cliente_by_cpf(cpf).await
     }
 
+    pub async fn busca_cliente_por_id(
+        &self,
+        id: usize,
+    ) -> Result<Cliente, DomainError> {
+        self.cliente_use_case.get_cliente_by_id(id).await
+    }
+
     pub async fn cadastro_cliente(
         &self,
         cliente_input: CreateClienteInput,
@@ -37,4 +46,35 @@ impl ClienteController {
         self.cliente_use_case.create_cliente(cliente_input).await
     }
 
+    pub async fn atualiza_cliente(
+        &self,
+        cpf: Cpf,
+        cliente_input: CreateClienteInput,
+    ) -> Result<Cliente, DomainError> {
+        self.cliente_use_case.update_cliente(cpf, cliente_input).await
+    }
+
+    pub async fn atualiza_parcialmente_cliente(
+        &self,
+        cpf: Cpf,
+        cliente_input: UpdateClienteInput,
+    ) -> Result<Cliente, DomainError> {
+        self.cliente_use_case.patch_cliente(cpf, cliente_input).await
+    }
+
+    pub async fn remove_cliente(
+        &self,
+        cpf: Cpf,
+    ) -> Result<(), DomainError> {
+        self.cliente_use_case.delete_cliente(cpf).await
+    }
+
+    pub async fn identifica_cliente_anonimo(
+        &self,
+        id: usize,
+        cliente_input: CreateClienteInput,
+    ) -> Result<Cliente, DomainError> {
+        self.cliente_use_case.identifica_cliente_anonimo(id, cliente_input).await
+    }
+
 }
@@ -0,0 +1,284 @@
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::base::assertion_concern::assert_field_not_empty;
+use crate::base::domain_error::{DomainError, FieldError};
+use crate::entities::cpf::Cpf;
+use crate::entities::email::Email;
+use crate::entities::usuario::{Status, Usuario};
+use crate::traits::notifier::Notifier;
+use crate::traits::password_hasher::PasswordHasher;
+use crate::traits::usuario_gateway::UsuarioGateway;
+use crate::traits::verification_code_store::VerificationCodeStore;
+use crate::use_cases::gerenciamento_de_usuarios_use_case::UsuarioUseCase;
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SolicitaResetSenhaInput {
+    cpf: Option<String>,
+    email: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ConfirmaResetSenhaInput {
+    cpf: Option<String>,
+    email: Option<String>,
+    codigo: String,
+    nova_senha: String,
+}
+
+pub struct PasswordResetController {
+    usuario_use_case: UsuarioUseCase,
+    verification_code_store: Arc<dyn VerificationCodeStore + Sync + Send>,
+    notifier: Arc<dyn Notifier + Sync + Send>,
+}
+
+fn codigo_key(usuario: &Usuario) -> String {
+    format!("reset_senha:{}", usuario.id())
+}
+
+impl PasswordResetController {
+    pub fn new(
+            usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+            password_hasher: Arc<dyn PasswordHasher + Sync + Send>,
+            verification_code_store: Arc<dyn VerificationCodeStore + Sync + Send>,
+            notifier: Arc<dyn Notifier + Sync + Send>,
+        ) -> PasswordResetController {
+        let usuario_use_case = UsuarioUseCase::new(usuario_repository, password_hasher);
+        PasswordResetController {
+            usuario_use_case,
+            verification_code_store,
+            notifier,
+        }
+    }
+
+    async fn identifica(&self, cpf: Option<String>, email: Option<String>) -> Result<Usuario, DomainError> {
+        match (cpf, email) {
+            (Some(cpf), _) => self.usuario_use_case.get_usuario_by_cpf(Cpf::new(cpf)?).await,
+            (None, Some(email)) => self.usuario_use_case.get_usuario_by_email(Email::new(email)?).await,
+            (None, None) => Err(DomainError::Validation(vec![FieldError::new(
+                "cpf",
+                "required",
+                "Informe cpf ou email",
+            )])),
+        }
+    }
+
+    // A resposta é a mesma quando o usuário não existe ou está inativo, para
+    // não revelar quais CPFs e e-mails estão cadastrados
+    pub async fn solicita(&self, input: SolicitaResetSenhaInput) -> Result<(), DomainError> {
+        let usuario = match self.identifica(input.cpf, input.email).await {
+            Ok(usuario) => usuario,
+            Err(DomainError::NotFound) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        if *usuario.status() == Status::Inativo {
+            return Ok(());
+        }
+        // Um código ainda válido já foi enviado; recusar aqui revelaria que o
+        // usuário existe
+        let codigo = match self.verification_code_store.issue(codigo_key(&usuario)).await {
+            Ok(codigo) => codigo,
+            Err(DomainError::TooManyAttempts(_)) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        self.notifier
+            .notify(
+                usuario.email().0.clone(),
+                "Redefinição de senha".to_string(),
+                format!("Seu código para redefinir a senha é {}", codigo),
+            )
+            .await
+    }
+
+    pub async fn confirma(&self, input: ConfirmaResetSenhaInput) -> Result<(), DomainError> {
+        // Valida antes de consumir o código, que só pode ser usado uma vez
+        assert_field_not_empty("nova_senha", &input.nova_senha)?;
+        let usuario = match self.identifica(input.cpf, input.email).await {
+            Ok(usuario) => usuario,
+            Err(DomainError::NotFound) => return Err(DomainError::Unauthorized),
+            Err(err) => return Err(err),
+        };
+        self.verification_code_store
+            .verify(codigo_key(&usuario), input.codigo)
+            .await?;
+        self.usuario_use_case.troca_senha(usuario, input.nova_senha).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::usuario::Tipo;
+    use crate::traits::notifier::MockNotifier;
+    use crate::traits::password_hasher::MockPasswordHasher;
+    use crate::traits::usuario_gateway::MockUsuarioGateway;
+    use crate::traits::verification_code_store::MockVerificationCodeStore;
+    use mockall::predicate::*;
+    use tokio;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn data_fixa() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2021, 10, 10, 10, 0, 0).unwrap()
+    }
+
+    fn usuario(cpf: Cpf) -> Usuario {
+        Usuario::new(
+            7,
+            "nome".to_string(),
+            Email::new("fulano@email.com".to_string()).unwrap(),
+            cpf,
+            "hash:senha".to_string(),
+            Tipo::Cozinha,
+            Status::Ativo,
+            data_fixa(),
+            data_fixa(),
+        )
+    }
+
+    fn password_reset_controller(
+        usuario_repository: MockUsuarioGateway,
+        verification_code_store: MockVerificationCodeStore,
+        notifier: MockNotifier,
+    ) -> PasswordResetController {
+        let mut password_hasher = MockPasswordHasher::new();
+        password_hasher.expect_hash().returning(|senha| Ok(format!("hash:{}", senha)));
+        password_hasher.expect_verify().returning(|senha, hash| format!("hash:{}", senha) == hash);
+
+        PasswordResetController::new(
+            Arc::new(usuario_repository),
+            Arc::new(password_hasher),
+            Arc::new(verification_code_store),
+            Arc::new(notifier),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_solicita_sends_codigo_by_email() {
+        let mut usuario_repository = MockUsuarioGateway::new();
+        usuario_repository
+            .expect_get_usuario_by_email()
+            .returning(|_| Ok(usuario(Cpf::new("123.456.789-09".to_string()).unwrap())));
+        let mut verification_code_store = MockVerificationCodeStore::new();
+        verification_code_store
+            .expect_issue()
+            .with(eq("reset_senha:7".to_string()))
+            .returning(|_| Ok("123456".to_string()));
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|destinatario, _, mensagem| {
+                destinatario == "fulano@email.com" && mensagem.contains("123456")
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let controller = password_reset_controller(usuario_repository, verification_code_store, notifier);
+        let input = SolicitaResetSenhaInput {
+            cpf: None,
+            email: Some("fulano@email.com".to_string()),
+        };
+        assert!(controller.solicita(input).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_solicita_hides_unknown_usuario() {
+        let mut usuario_repository = MockUsuarioGateway::new();
+        usuario_repository.expect_get_usuario_by_cpf().returning(|_| Err(DomainError::NotFound));
+        let mut verification_code_store = MockVerificationCodeStore::new();
+        verification_code_store.expect_issue().times(0);
+
+        let controller = password_reset_controller(usuario_repository, verification_code_store, MockNotifier::new());
+        let input = SolicitaResetSenhaInput {
+            cpf: Some("123.456.789-09".to_string()),
+            email: None,
+        };
+        assert!(controller.solicita(input).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_solicita_requires_cpf_or_email() {
+        let controller = password_reset_controller(
+            MockUsuarioGateway::new(),
+            MockVerificationCodeStore::new(),
+            MockNotifier::new(),
+        );
+        let input = SolicitaResetSenhaInput { cpf: None, email: None };
+        let result = controller.solicita(input).await;
+        assert!(
+            matches!(result, Err(DomainError::Validation(_))),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
+            result
+        );
+    }
+
+    fn confirma_input(codigo: &str) -> ConfirmaResetSenhaInput {
+        ConfirmaResetSenhaInput {
+            cpf: Some("123.456.789-09".to_string()),
+            email: None,
+            codigo: codigo.to_string(),
+            nova_senha: "nova".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_confirma_changes_senha() {
+        let mut usuario_repository = MockUsuarioGateway::new();
+        usuario_repository.expect_get_usuario_by_cpf().returning(|cpf| Ok(usuario(cpf)));
+        usuario_repository
+            .expect_update_usuario()
+            .withf(|usuario| usuario.senha() == "hash:nova")
+            .times(1)
+            .returning(Ok);
+        let mut verification_code_store = MockVerificationCodeStore::new();
+        verification_code_store
+            .expect_verify()
+            .with(eq("reset_senha:7".to_string()), eq("123456".to_string()))
+            .returning(|_, _| Ok(()));
+
+        let controller = password_reset_controller(usuario_repository, verification_code_store, MockNotifier::new());
+        assert!(controller.confirma(confirma_input("123456")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_confirma_rejects_wrong_codigo() {
+        let mut usuario_repository = MockUsuarioGateway::new();
+        usuario_repository.expect_get_usuario_by_cpf().returning(|cpf| Ok(usuario(cpf)));
+        usuario_repository.expect_update_usuario().times(0);
+        let mut verification_code_store = MockVerificationCodeStore::new();
+        verification_code_store
+            .expect_verify()
+            .returning(|_, _| Err(DomainError::Unauthorized));
+
+        let controller = password_reset_controller(usuario_repository, verification_code_store, MockNotifier::new());
+        let result = controller.confirma(confirma_input("654321")).await;
+        assert!(
+            matches!(result, Err(DomainError::Unauthorized)),
+            "Esperado Err(DomainError::Unauthorized), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_solicita_hides_pending_codigo() {
+        let mut usuario_repository = MockUsuarioGateway::new();
+        usuario_repository
+            .expect_get_usuario_by_cpf()
+            .returning(|cpf| Ok(usuario(cpf)));
+        let mut verification_code_store = MockVerificationCodeStore::new();
+        verification_code_store
+            .expect_issue()
+            .returning(|_| Err(DomainError::TooManyAttempts(120)));
+        let mut notifier = MockNotifier::new();
+        notifier.expect_notify().times(0);
+
+        let controller = password_reset_controller(usuario_repository, verification_code_store, notifier);
+        let input = SolicitaResetSenhaInput {
+            cpf: Some("123.456.789-09".to_string()),
+            email: None,
+        };
+        assert!(controller.solicita(input).await.is_ok());
+    }
+}
@@ -1,28 +1,37 @@
 use std::sync::Arc;
-use tokio::sync::Mutex;
 
 use crate::base::domain_error::DomainError;
+use crate::base::pagination::Page;
 use crate::entities::usuario::Usuario;
 use crate::entities::cpf::Cpf;
+use crate::traits::login_attempt_store::{LoginAttemptKey, LoginAttemptStore};
+use crate::traits::password_hasher::PasswordHasher;
 use crate::traits::usuario_gateway::UsuarioGateway;
-use crate::use_cases::gerenciamento_de_usuarios_use_case::{CreateUsuarioInput, UsuarioUseCase};
+use crate::use_cases::gerenciamento_de_usuarios_use_case::{AlteraSenhaInput, CreateUsuarioInput, ListaUsuariosInput, UpdateUsuarioInput, UsuarioUseCase};
 
 pub struct UsuarioController {
     pub usuario_use_case: UsuarioUseCase,
+    login_attempt_store: Arc<dyn LoginAttemptStore + Sync + Send>,
 }
 
 impl UsuarioController {
-    pub fn new(usuario_repository: Arc<Mutex<dyn UsuarioGateway + Sync + Send>>) -> UsuarioController {
-        let usuario_use_case = UsuarioUseCase::new(usuario_repository);
+    pub fn new(
+        usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+        password_hasher: Arc<dyn PasswordHasher + Sync + Send>,
+        login_attempt_store: Arc<dyn LoginAttemptStore + Sync + Send>,
+    ) -> UsuarioController {
+        let usuario_use_case = UsuarioUseCase::new(usuario_repository, password_hasher);
         UsuarioController {
             usuario_use_case,
+            login_attempt_store,
         }
     }
 
     pub async fn get_usuarios(
         &self,
-    ) -> Result<Vec<Usuario>, DomainError> {
-        self.usuario_use_case.get_usuarios().await
+        filtro: ListaUsuariosInput,
+    ) -> Result<Page<Usuario>, DomainError> {
+        self.usuario_use_case.lista_usuarios(filtro).await
     }
 
     pub async fn get_usuario(
@@ -32,6 +41,34 @@ impl UsuarioController {
         self.usuario_use_case.get_usuario_by_id(id).await
     }
 
+    // O id do usuário logado vem do sub do token
+    pub async fn get_me(
+        &self,
+        user_id: &str,
+    ) -> Result<Usuario, DomainError> {
+        let id = user_id.parse::<usize>().map_err(|_| DomainError::Unauthorized)?;
+        self.usuario_use_case.get_usuario_by_id(id).await
+    }
+
+    pub async fn altera_senha_me(
+        &self,
+        user_id: &str,
+        senha_input: AlteraSenhaInput,
+    ) -> Result<Usuario, DomainError> {
+        let id = user_id.parse::<usize>().map_err(|_| DomainError::Unauthorized)?;
+        let usuario = self.usuario_use_case.get_usuario_by_id(id).await?;
+        // A senha atual errada conta como falha de login da conta, como em
+        // AuthController::autentica
+        let conta = LoginAttemptKey::Conta(usuario.cpf().formatted());
+        self.login_attempt_store.reserve(conta.clone()).await?;
+        if let Err(err) = self.usuario_use_case.confere_senha_atual(&usuario, &senha_input) {
+            self.login_attempt_store.register_failure(conta).await?;
+            return Err(err);
+        }
+        self.login_attempt_store.register_success(conta).await?;
+        self.usuario_use_case.altera_senha(usuario, senha_input).await
+    }
+
     pub async fn create_usuario(
         &self,
         usuario_input: CreateUsuarioInput,
@@ -47,6 +84,14 @@ impl UsuarioController {
         self.usuario_use_case.update_usuario(id, usuario_input).await
     }
 
+    pub async fn patch_usuario(
+        &self,
+        id: usize,
+        usuario_input: UpdateUsuarioInput,
+    ) -> Result<Usuario, DomainError> {
+        self.usuario_use_case.patch_usuario(id, usuario_input).await
+    }
+
     pub async fn delete_usuario(
         &self,
         cpf: Cpf,
@@ -54,3 +99,88 @@ impl UsuarioController {
         self.usuario_use_case.delete_usuario(cpf).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::email::Email;
+    use crate::entities::usuario::{Status, Tipo};
+    use crate::traits::login_attempt_store::MockLoginAttemptStore;
+    use crate::traits::password_hasher::MockPasswordHasher;
+    use crate::traits::usuario_gateway::MockUsuarioGateway;
+    use chrono::Utc;
+    use mockall::predicate::*;
+    use tokio;
+
+    fn usuario_controller(login_attempt_store: MockLoginAttemptStore) -> UsuarioController {
+        let mut usuario_repository = MockUsuarioGateway::new();
+        usuario_repository.expect_get_usuario_by_id().returning(|id| {
+            let now = Utc::now();
+            Ok(Usuario::new(
+                id,
+                "nome".to_string(),
+                Email::new("fulano@email.com".to_string()).unwrap(),
+                Cpf::new("529.982.247-25".to_string()).unwrap(),
+                "hash:senha".to_string(),
+                Tipo::Cozinha,
+                Status::Ativo,
+                now,
+                now,
+            ))
+        });
+        usuario_repository.expect_update_usuario().returning(Ok);
+        let mut password_hasher = MockPasswordHasher::new();
+        password_hasher.expect_hash().returning(|senha| Ok(format!("hash:{}", senha)));
+        password_hasher.expect_verify().returning(|senha, hash| format!("hash:{}", senha) == hash);
+        UsuarioController::new(Arc::new(usuario_repository), Arc::new(password_hasher), Arc::new(login_attempt_store))
+    }
+
+    fn altera_senha_input(senha_atual: &str) -> AlteraSenhaInput {
+        serde_json::from_value(serde_json::json!({ "senha_atual": senha_atual, "nova_senha": "nova" })).unwrap()
+    }
+
+    fn conta() -> LoginAttemptKey {
+        LoginAttemptKey::Conta("529.982.247-25".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_altera_senha_me_registers_failure_for_wrong_senha_atual() {
+        let mut login_attempt_store = MockLoginAttemptStore::new();
+        login_attempt_store.expect_reserve().with(eq(conta())).times(1).returning(|_| Ok(()));
+        login_attempt_store.expect_register_failure().with(eq(conta())).times(1).returning(|_| Ok(()));
+        login_attempt_store.expect_register_success().times(0);
+
+        let result = usuario_controller(login_attempt_store).altera_senha_me("1", altera_senha_input("errada")).await;
+        assert!(
+            matches!(result, Err(DomainError::Validation(ref erros)) if erros[0].field == "senha_atual"),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_altera_senha_me_is_blocked_when_conta_is_locked() {
+        let mut login_attempt_store = MockLoginAttemptStore::new();
+        login_attempt_store.expect_reserve().returning(|_| Err(DomainError::TooManyAttempts(60)));
+        login_attempt_store.expect_register_failure().times(0);
+        login_attempt_store.expect_register_success().times(0);
+
+        let result = usuario_controller(login_attempt_store).altera_senha_me("1", altera_senha_input("senha")).await;
+        assert!(
+            matches!(result, Err(DomainError::TooManyAttempts(60))),
+            "Esperado Err(DomainError::TooManyAttempts), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_altera_senha_me_registers_success() {
+        let mut login_attempt_store = MockLoginAttemptStore::new();
+        login_attempt_store.expect_reserve().with(eq(conta())).times(1).returning(|_| Ok(()));
+        login_attempt_store.expect_register_failure().times(0);
+        login_attempt_store.expect_register_success().with(eq(conta())).times(1).returning(|_| Ok(()));
+
+        let usuario = usuario_controller(login_attempt_store).altera_senha_me("1", altera_senha_input("senha")).await.unwrap();
+        assert_eq!(usuario.senha(), "hash:nova");
+    }
+}
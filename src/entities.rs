@@ -1,3 +1,4 @@
 pub mod usuario;
 pub mod cliente;
 pub mod cpf;
+pub mod email;
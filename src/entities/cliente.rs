@@ -1,49 +1,66 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::base::{
-        assertion_concern,
-        domain_error::DomainError,
+        assertion_concern::{self, Violations},
+        domain_error::{DomainError, FieldError},
 
 };
 use crate::entities::cpf::Cpf;
+use crate::entities::email::Email;
 
 #[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
 pub struct Cliente {
     id: usize,
     nome: String,
-    email: String,
-    cpf: Cpf,
-    data_criacao: String,
-    data_atualizacao: String,
+    email: Option<Email>,
+    cpf: Option<Cpf>,
+    data_criacao: DateTime<Utc>,
+    data_atualizacao: DateTime<Utc>,
 }
 
 impl Cliente {
     pub fn new(
         id: usize,
         nome: String,
-        email: String,
+        email: Email,
         cpf: Cpf,
-        data_criacao: String,
-        data_atualizacao: String,
+        data_criacao: DateTime<Utc>,
+        data_atualizacao: DateTime<Utc>,
     ) -> Self {
         Cliente {
             id,
             nome,
-            email,
-            cpf,
+            email: Some(email),
+            cpf: Some(cpf),
+            data_criacao,
+            data_atualizacao,
+        }
+    }
+
+    // Cliente que faz pedidos sem se identificar; pode ser promovido a um
+    // cliente completo com `identifica`, mantendo o mesmo id.
+    pub fn anonimo(id: usize, data_criacao: DateTime<Utc>, data_atualizacao: DateTime<Utc>) -> Self {
+        Cliente {
+            id,
+            nome: String::new(),
+            email: None,
+            cpf: None,
             data_criacao,
             data_atualizacao,
         }
     }
 
     pub fn validate_entity(&self) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(self.nome.clone())?;
-        assertion_concern::assert_argument_not_empty(self.email.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_criacao.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_atualizacao.clone())?;
-        Ok(())
+        let mut violations = Violations::new();
+        if !self.is_anonimo() {
+            violations.check("nome", assertion_concern::assert_field_not_empty("nome", &self.nome));
+            if self.email.is_none() {
+                violations.push(FieldError::required("email"));
+            }
+        }
+        violations.into_result()
     }
 
     // Getters
@@ -55,44 +72,54 @@ impl Cliente {
         &self.nome
     }
 
-    pub fn email(&self) -> &String {
+    pub fn email(&self) -> &Option<Email> {
         &self.email
     }
 
-    pub fn cpf(&self) -> &Cpf {
+    pub fn cpf(&self) -> &Option<Cpf> {
         &self.cpf
     }
 
-    pub fn data_criacao(&self) -> &String {
+    pub fn is_anonimo(&self) -> bool {
+        self.cpf.is_none()
+    }
+
+    pub fn data_criacao(&self) -> &DateTime<Utc> {
         &self.data_criacao
     }
 
-    pub fn data_atualizacao(&self) -> &String {
+    pub fn data_atualizacao(&self) -> &DateTime<Utc> {
         &self.data_atualizacao
     }
 
     // Setters
     pub fn set_nome(&mut self, nome: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(nome.clone())?;
+        assertion_concern::assert_field_not_empty("nome", &nome)?;
         self.nome = nome;
         Ok(())
     }
 
-    pub fn set_email(&mut self, email: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(email.clone())?;
-        self.email = email;
-        Ok(())
+    pub fn set_email(&mut self, email: Email) {
+        self.email = Some(email);
     }
 
     pub fn set_cpf(&mut self, cpf: Cpf) {
-        self.cpf = cpf;
+        self.cpf = Some(cpf);
     }
 
-    pub fn set_data_atualizacao(&mut self, data_atualizacao: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_timestamp_format(data_atualizacao.clone())?;
-        self.data_atualizacao = data_atualizacao;
+    pub fn identifica(&mut self, nome: String, email: Email, cpf: Cpf) -> Result<(), DomainError> {
+        if !self.is_anonimo() {
+            return Err(DomainError::Conflict("Cliente já identificado".to_string()));
+        }
+        self.set_nome(nome)?;
+        self.set_email(email);
+        self.set_cpf(cpf);
         Ok(())
     }
+
+    pub fn set_data_atualizacao(&mut self, data_atualizacao: DateTime<Utc>) {
+        self.data_atualizacao = data_atualizacao;
+    }
 }
 
 // Unit Tests
@@ -102,13 +129,13 @@ mod tests {
     use crate::entities::cpf::Cpf;
 
     fn create_valid_cliente() -> Cliente {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         Cliente::new(
             1,
             "Fulano da Silva".to_string(),
-            "fulano.silva@exemplo.com".to_string(),
+            Email::new("fulano.silva@exemplo.com".to_string()).unwrap(),
             Cpf::new("123.456.789-09".to_string()).unwrap(),
-            _now.clone(),
+            _now,
             _now,
         )
     }
@@ -118,7 +145,7 @@ mod tests {
         let cliente = create_valid_cliente();
         assert_eq!(cliente.id(), &1);
         assert_eq!(cliente.nome(), "Fulano da Silva");
-        assert_eq!(cliente.email(), "fulano.silva@exemplo.com");
+        assert_eq!(cliente.email().as_ref().unwrap().0, "fulano.silva@exemplo.com");
     }
 
     #[test]
@@ -129,40 +156,20 @@ mod tests {
 
     #[test]
     fn test_cliente_validate_entity_empty_nome() {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         let cliente = Cliente::new(
             1,
             "".to_string(),
-            "fulano.silva@exemplo.com".to_string(),
+            Email::new("fulano.silva@exemplo.com".to_string()).unwrap(),
             Cpf::new("123.456.789-09".to_string()).unwrap(),
-            _now.clone(),
             _now,
-        );
-
-        let result = cliente.validate_entity();
-        assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
-            result
-        );
-    }
-
-    #[test]
-    fn test_cliente_validate_entity_empty_email() {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-        let cliente = Cliente::new(
-            1,
-            "Fulano da Silva".to_string(),
-            "".to_string(),
-            Cpf::new("123.456.789-09".to_string()).unwrap(),
-            _now.clone(),
             _now,
         );
 
         let result = cliente.validate_entity();
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(&result, Err(DomainError::Validation(errors)) if errors == &vec![FieldError::required("nome")]),
+            "Esperado Err(DomainError::Validation(nome)), obtido {:?}",
             result
         );
     }
@@ -171,9 +178,9 @@ mod tests {
     fn test_cliente_setters_valid() {
         let mut cliente = create_valid_cliente();
         let _ = cliente.set_nome("Ciclano da Silva".to_string());
-        let _ = cliente.set_email("ciclano.silva@exemplo.com".to_string());
+        cliente.set_email(Email::new("ciclano.silva@exemplo.com".to_string()).unwrap());
         assert_eq!(cliente.nome(), "Ciclano da Silva");
-        assert_eq!(cliente.email(), "ciclano.silva@exemplo.com");
+        assert_eq!(cliente.email().as_ref().unwrap().0, "ciclano.silva@exemplo.com");
     }
 
     #[test]
@@ -181,31 +188,46 @@ mod tests {
         let mut cliente = create_valid_cliente();
         let result = cliente.set_nome("".to_string());
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(&result, Err(DomainError::Validation(errors)) if errors == &vec![FieldError::required("nome")]),
+            "Esperado Err(DomainError::Validation(nome)), obtido {:?}",
             result
         );
     }
 
     #[test]
-    fn test_cliente_set_email_empty() {
+    fn test_cliente_anonimo() {
+        let _now = Utc::now();
+        let mut cliente = Cliente::anonimo(1, _now, _now);
+        assert!(cliente.is_anonimo());
+        assert!(cliente.validate_entity().is_ok());
+
+        let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
+        cliente
+            .identifica("Fulano da Silva".to_string(), Email::new("fulano.silva@exemplo.com".to_string()).unwrap(), cpf.clone())
+            .unwrap();
+        assert!(!cliente.is_anonimo());
+        assert_eq!(cliente.id(), &1);
+        assert_eq!(cliente.cpf(), &Some(cpf));
+    }
+
+    #[test]
+    fn test_cliente_identifica_already_identified() {
         let mut cliente = create_valid_cliente();
-        let result = cliente.set_email("".to_string());
+        let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
+        let result = cliente.identifica("Ciclano".to_string(), Email::new("ciclano@exemplo.com".to_string()).unwrap(), cpf);
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(result, Err(DomainError::Conflict(_))),
+            "Esperado Err(DomainError::Conflict), obtido {:?}",
             result
         );
     }
 
     #[test]
-    fn test_cliente_set_data_atualizacao_invalid_format() {
+    fn test_cliente_set_data_atualizacao() {
         let mut cliente = create_valid_cliente();
-        let result = cliente.set_data_atualizacao("18-02-2024".to_string());
-        assert!(
-            matches!(result, Err(DomainError::Invalid(_))),
-            "Esperado Err(DomainError::Invalid), obtido {:?}",
-            result
-        );
+        let data_atualizacao = *cliente.data_criacao() + chrono::Duration::minutes(5);
+        cliente.set_data_atualizacao(data_atualizacao);
+        assert_eq!(cliente.data_atualizacao(), &data_atualizacao);
+        assert!(cliente.data_atualizacao() > cliente.data_criacao());
     }
 }
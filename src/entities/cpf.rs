@@ -2,37 +2,53 @@ use schemars::JsonSchema;
 use serde::{Serialize, Deserialize};
 use regex::Regex;
 
-use crate::base::domain_error::DomainError;
+use crate::base::domain_error::{DomainError, FieldError};
 
-#[derive(Clone, Deserialize, Debug, JsonSchema, Serialize)]
-pub struct Cpf(pub String);
+// Guarda só os 11 dígitos; a forma com pontuação sai de `formatted`, que é
+// também a usada no JSON
+#[derive(Clone, Deserialize, Debug, JsonSchema, Serialize, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct Cpf(String);
 
 impl Cpf {
     pub fn new(codigo: String) -> Result<Self, DomainError> {
+        let codigo = codigo.trim();
         if codigo.is_empty() {
-            return Err(DomainError::Empty);
+            return Err(DomainError::Validation(vec![FieldError::required("cpf")]));
         }
-        // Default admin user
-        if codigo == "000.000.000-00" {
-            return Ok(Cpf(codigo));
+        let regex_pattern = Regex::new(r"^(\d{3}\.\d{3}\.\d{3}-\d{2}|\d{11})$").unwrap();
+        if !regex_pattern.is_match(codigo) {
+            return Err(Cpf::invalid(
+                "invalid_format",
+                "CPF deve ter 11 dígitos, com ou sem pontuação (000.000.000-00)",
+            ));
         }
-        let regex_pattern = Regex::new(r"^\d{3}\.\d{3}\.\d{3}-\d{2}$").unwrap();
-        if regex_pattern.is_match(&codigo) {
-            if Cpf::validate(codigo.clone()) {
-                Ok(Cpf(codigo))
-            } else {
-                Err(DomainError::Invalid("CPF".to_string()))
-            }
+        let digits: String = codigo.chars().filter(|c| c.is_ascii_digit()).collect();
+        // Sequências como 111.111.111-11 passam no cálculo dos dígitos verificadores
+        if digits.chars().all(|d| d == digits.chars().next().unwrap()) {
+            return Err(Cpf::invalid("repeated_digits", "CPF não pode ter todos os dígitos iguais"));
+        }
+        if Cpf::validate(&digits) {
+            Ok(Cpf(digits))
         } else {
-            Err(DomainError::Invalid("CPF".to_string()))
+            Err(Cpf::invalid("invalid_check_digits", "Dígitos verificadores do CPF não conferem"))
         }
     }
 
-    fn validate(codigo: String) -> bool {
-        let cpf = codigo;
-        let cpf = cpf.replace(".", "");
-        let cpf = cpf.replace("-", "");
-        let cpf = cpf.chars().map(|d| d.to_digit(10).unwrap()).collect::<Vec<u32>>();
+    fn invalid(code: &str, message: &str) -> DomainError {
+        DomainError::Validation(vec![FieldError::new("cpf", code, message)])
+    }
+
+    pub fn digits(&self) -> &str {
+        &self.0
+    }
+
+    pub fn formatted(&self) -> String {
+        format!("{}.{}.{}-{}", &self.0[0..3], &self.0[3..6], &self.0[6..9], &self.0[9..])
+    }
+
+    fn validate(digits: &str) -> bool {
+        let cpf = digits.chars().map(|d| d.to_digit(10).unwrap()).collect::<Vec<u32>>();
         let dv1 = (0..9).map(|i| cpf[i] * (10 - i as u32)).sum::<u32>() % 11;
         let dv1 = if dv1 < 2 { 0 } else { 11 - dv1 };
         let dv2 = (0..10).map(|i| cpf[i] * (11 - i as u32)).sum::<u32>() % 11;
@@ -41,9 +57,16 @@ impl Cpf {
     }
 }
 
-impl PartialEq for Cpf {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+impl TryFrom<String> for Cpf {
+    type Error = String;
+    fn try_from(codigo: String) -> Result<Self, Self::Error> {
+        Cpf::new(codigo.clone()).map_err(|_| format!("CPF inválido: {}", codigo))
+    }
+}
+
+impl From<Cpf> for String {
+    fn from(cpf: Cpf) -> Self {
+        cpf.formatted()
     }
 }
 
@@ -52,9 +75,15 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_cpf_admin() {
-        let cpf = Cpf::new("000.000.000-00".to_string());
-        assert!(cpf.is_ok());
+    fn test_cpf_zeros_rejected() {
+        for codigo in ["000.000.000-00", "00000000000"] {
+            let cpf = Cpf::new(codigo.to_string());
+            assert!(
+                matches!(&cpf, Err(DomainError::Validation(errors)) if errors[0].code == "repeated_digits"),
+                "Esperado Err(DomainError::Validation(repeated_digits)), obtido {:?}",
+                cpf
+            );
+        }
     }
     #[test]
     fn test_cpf_valid() {
@@ -62,13 +91,50 @@ mod tests {
         assert!(cpf.is_ok());
     }
     #[test]
+    fn test_cpf_valid_unmasked() {
+        let cpf = Cpf::new("09785545660".to_string()).unwrap();
+        assert_eq!(cpf, Cpf::new("097.855.456-60".to_string()).unwrap());
+        assert_eq!(cpf.digits(), "09785545660");
+        assert_eq!(cpf.formatted(), "097.855.456-60");
+    }
+    #[test]
     fn test_cpf_invalid_number() {
         let cpf = Cpf::new("000.000.000-01".to_string());
-        assert!(cpf.is_err());
+        assert!(
+            matches!(&cpf, Err(DomainError::Validation(errors))
+                if errors == &vec![FieldError::new("cpf", "invalid_check_digits", "Dígitos verificadores do CPF não conferem")]),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
+            cpf
+        );
+    }
+    #[test]
+    fn test_cpf_repeated_digits() {
+        for codigo in ["111.111.111-11", "99999999999"] {
+            let cpf = Cpf::new(codigo.to_string());
+            assert!(
+                matches!(&cpf, Err(DomainError::Validation(errors)) if errors[0].code == "repeated_digits"),
+                "Esperado Err(DomainError::Validation(repeated_digits)) para {}, obtido {:?}",
+                codigo,
+                cpf
+            );
+        }
     }
     #[test]
     fn test_cpf_invalid_value() {
-        let cpf = Cpf::new("wrong".to_string());
-        assert!(cpf.is_err());
+        for codigo in ["wrong", "097.855.45660", "0978554566"] {
+            let cpf = Cpf::new(codigo.to_string());
+            assert!(cpf.is_err(), "Esperado erro para {}", codigo);
+        }
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_cpf_deserialize_canonical() {
+        let cpf: Cpf = serde_json::from_str("\"097.855.456-60\"").unwrap();
+        assert_eq!(cpf.digits(), "09785545660");
+        assert!(serde_json::from_str::<Cpf>("\"111.111.111-11\"").is_err());
+    }
+    #[test]
+    fn test_cpf_serialize_formatted() {
+        let cpf = Cpf::new("09785545660".to_string()).unwrap();
+        assert_eq!(serde_json::to_string(&cpf).unwrap(), "\"097.855.456-60\"");
+    }
+}
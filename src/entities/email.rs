@@ -0,0 +1,91 @@
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+use regex::Regex;
+
+use crate::base::domain_error::{DomainError, FieldError};
+
+// O JSON passa pela mesma validação e normalização de `new`
+#[derive(Clone, Deserialize, Debug, JsonSchema, Serialize, PartialEq)]
+#[serde(try_from = "String")]
+pub struct Email(pub String);
+
+impl Email {
+    pub fn new(endereco: String) -> Result<Self, DomainError> {
+        let endereco = endereco.trim();
+        if endereco.is_empty() {
+            return Err(DomainError::Validation(vec![FieldError::required("email")]));
+        }
+        let regex_pattern = Regex::new(
+            r"^[A-Za-z0-9!#$%&'*+/=?^_`{|}~-]+(\.[A-Za-z0-9!#$%&'*+/=?^_`{|}~-]+)*@([A-Za-z0-9]([A-Za-z0-9-]*[A-Za-z0-9])?\.)+[A-Za-z]{2,}$",
+        )
+        .unwrap();
+        if !regex_pattern.is_match(endereco) {
+            return Err(Email::invalid("invalid_format", "email deve ter o formato nome@dominio.com"));
+        }
+        // Só o domínio é normalizado; a parte local pode diferenciar maiúsculas
+        let (local, dominio) = endereco.rsplit_once('@').unwrap();
+        if endereco.len() > 254 || local.len() > 64 {
+            return Err(Email::invalid("too_long", "email excede o tamanho máximo permitido"));
+        }
+        Ok(Email(format!("{}@{}", local, dominio.to_lowercase())))
+    }
+
+    fn invalid(code: &str, message: &str) -> DomainError {
+        DomainError::Validation(vec![FieldError::new("email", code, message)])
+    }
+
+    // Buscas por email não diferenciam maiúsculas, nem na parte local
+    pub fn eq_ignore_case(&self, other: &Email) -> bool {
+        self.0.to_lowercase() == other.0.to_lowercase()
+    }
+}
+
+impl TryFrom<String> for Email {
+    type Error = String;
+    fn try_from(endereco: String) -> Result<Self, Self::Error> {
+        Email::new(endereco.clone()).map_err(|_| format!("Email inválido: {}", endereco))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_valid() {
+        let email = Email::new("fulano.silva+pedidos@exemplo.com.br".to_string());
+        assert!(email.is_ok());
+    }
+    #[test]
+    fn test_email_normalizes_domain() {
+        let email = Email::new(" Fulano.Silva@EXEMPLO.com ".to_string()).unwrap();
+        assert_eq!(email.0, "Fulano.Silva@exemplo.com");
+    }
+    #[test]
+    fn test_email_empty() {
+        let email = Email::new("  ".to_string());
+        assert!(
+            matches!(&email, Err(DomainError::Validation(errors)) if errors == &vec![FieldError::required("email")]),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
+            email
+        );
+    }
+    #[test]
+    fn test_email_invalid_value() {
+        for endereco in ["email", "fulano@", "@exemplo.com", "fulano@exemplo", "fulano..silva@exemplo.com", "fulano silva@exemplo.com"] {
+            let email = Email::new(endereco.to_string());
+            assert!(
+                matches!(&email, Err(DomainError::Validation(errors)) if errors[0].field == "email" && errors[0].code == "invalid_format"),
+                "Esperado Err(DomainError::Validation(email)) para {}, obtido {:?}",
+                endereco,
+                email
+            );
+        }
+    }
+    #[test]
+    fn test_email_deserialize_normalized() {
+        let email: Email = serde_json::from_str("\" Fulano@Exemplo.COM \"").unwrap();
+        assert_eq!(email.0, "Fulano@exemplo.com");
+        assert!(serde_json::from_str::<Email>("\"sem-arroba\"").is_err());
+    }
+}
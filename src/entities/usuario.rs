@@ -1,4 +1,4 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -6,10 +6,11 @@ use std::str::FromStr;
 
 use crate::{
     base::{
-        assertion_concern,
+        assertion_concern::{self, Violations},
         domain_error::DomainError,
     },
     entities::cpf::Cpf,
+    entities::email::Email,
 };
 
 #[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq)]
@@ -54,27 +55,30 @@ impl fmt::Display for Tipo {
 pub struct Usuario {
     id: usize,
     nome: String,
-    email: String,
+    email: Email,
     cpf: Cpf,
     #[serde(skip_serializing)]
     senha: String,
     tipo: Tipo,
     status: Status,
-    data_criacao: String,
-    data_atualizacao: String,
+    data_criacao: DateTime<Utc>,
+    data_atualizacao: DateTime<Utc>,
+    // Enquanto marcada, o login é recusado até o usuário trocar a senha
+    #[serde(default)]
+    troca_senha_obrigatoria: bool,
 }
 
 impl Usuario {
     pub fn new(
         id: usize,
         nome: String,
-        email: String,
+        email: Email,
         cpf: Cpf,
         senha: String,
         tipo: Tipo,
         status: Status,
-        data_criacao: String,
-        data_atualizacao: String,
+        data_criacao: DateTime<Utc>,
+        data_atualizacao: DateTime<Utc>,
     ) -> Self {
         Usuario {
             id,
@@ -86,32 +90,15 @@ impl Usuario {
             status,
             data_criacao,
             data_atualizacao,
+            troca_senha_obrigatoria: false,
         }
     }
 
-    fn validate_entity(&self) -> Result<(), DomainError> {
-        match self.status {
-            Status::Ativo | Status::Inativo => (),
-            _ => {
-                return Err(DomainError::Invalid(
-                    "Status do Usuário é inválido".to_string(),
-                ))
-            }
-        };
-        match self.tipo {
-            Tipo::Admin | Tipo::Cozinha => (),
-            _ => {
-                return Err(DomainError::Invalid(
-                    "Tipo do Usuário é inválido".to_string(),
-                ))
-            }
-        };
-        assertion_concern::assert_argument_not_empty(self.nome.clone())?;
-        assertion_concern::assert_argument_not_empty(self.email.clone())?;
-        assertion_concern::assert_argument_not_empty(self.senha.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_criacao.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_atualizacao.clone())?;
-        Ok(())
+    pub fn validate_entity(&self) -> Result<(), DomainError> {
+        let mut violations = Violations::new();
+        violations.check("nome", assertion_concern::assert_field_not_empty("nome", &self.nome));
+        violations.check("senha", assertion_concern::assert_field_not_empty("senha", &self.senha));
+        violations.into_result()
     }
 
     // Getters
@@ -123,7 +110,7 @@ impl Usuario {
         &self.nome
     }
 
-    pub fn email(&self) -> &String {
+    pub fn email(&self) -> &Email {
         &self.email
     }
 
@@ -135,10 +122,6 @@ impl Usuario {
         &self.cpf
     }
 
-    pub fn validate_senha(&self, senha: &String) -> bool {
-        &self.senha == senha
-    }
-
     pub fn tipo(&self) -> &Tipo {
         &self.tipo
     }
@@ -147,25 +130,27 @@ impl Usuario {
         &self.status
     }
 
-    pub fn data_criacao(&self) -> &String {
+    pub fn data_criacao(&self) -> &DateTime<Utc> {
         &self.data_criacao
     }
 
-    pub fn data_atualizacao(&self) -> &String {
+    pub fn data_atualizacao(&self) -> &DateTime<Utc> {
         &self.data_atualizacao
     }
 
+    pub fn troca_senha_obrigatoria(&self) -> bool {
+        self.troca_senha_obrigatoria
+    }
+
     // Setters
     pub fn set_nome(&mut self, nome: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(nome.clone())?;
+        assertion_concern::assert_field_not_empty("nome", &nome)?;
         self.nome = nome;
         Ok(())
     }
 
-    pub fn set_email(&mut self, email: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(email.clone())?;
+    pub fn set_email(&mut self, email: Email) {
         self.email = email;
-        Ok(())
     }
 
     pub fn set_cpf(&mut self, cpf: Cpf) {
@@ -173,7 +158,7 @@ impl Usuario {
     }
 
     pub fn set_senha(&mut self, senha: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(senha.clone())?;
+        assertion_concern::assert_field_not_empty("senha", &senha)?;
         self.senha = senha;
         Ok(())
     }
@@ -186,28 +171,31 @@ impl Usuario {
         self.status = status;
     }
 
-    pub fn set_data_atualizacao(&mut self, data_atualizacao: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_timestamp_format(data_atualizacao.clone())?;
+    pub fn set_data_atualizacao(&mut self, data_atualizacao: DateTime<Utc>) {
         self.data_atualizacao = data_atualizacao;
-        Ok(())
+    }
+
+    pub fn set_troca_senha_obrigatoria(&mut self, troca_senha_obrigatoria: bool) {
+        self.troca_senha_obrigatoria = troca_senha_obrigatoria;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::base::domain_error::FieldError;
 
     fn create_valid_usuario() -> Usuario {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         Usuario::new(
             1,
             "Fulano da Silva".to_string(),
-            "fulano.silva@exemplo.com".to_string(),
+            Email::new("fulano.silva@exemplo.com".to_string()).unwrap(),
             Cpf::new("123.456.789-09".to_string()).unwrap(),
             "senha_segura".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            _now.clone(),
+            _now,
             _now,
         )
     }
@@ -217,9 +205,10 @@ mod tests {
         let usuario = create_valid_usuario();
         assert_eq!(usuario.id(), &1);
         assert_eq!(usuario.nome(), "Fulano da Silva");
-        assert_eq!(usuario.email(), "fulano.silva@exemplo.com");
+        assert_eq!(usuario.email().0, "fulano.silva@exemplo.com");
         assert_eq!(usuario.tipo(), &Tipo::Admin);
         assert_eq!(usuario.status(), &Status::Ativo);
+        assert!(!usuario.troca_senha_obrigatoria());
     }
 
     #[test]
@@ -228,17 +217,41 @@ mod tests {
         assert!(usuario.validate_entity().is_ok());
     }
 
+    #[test]
+    fn test_usuario_validate_entity_collects_violations() {
+        let _now = Utc::now();
+        let usuario = Usuario::new(
+            1,
+            "".to_string(),
+            Email::new("fulano.silva@exemplo.com".to_string()).unwrap(),
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
+            "".to_string(),
+            Tipo::Cozinha,
+            Status::Ativo,
+            _now,
+            _now,
+        );
+
+        let result = usuario.validate_entity();
+        assert!(
+            matches!(&result, Err(DomainError::Validation(errors))
+                if errors == &vec![FieldError::required("nome"), FieldError::required("senha")]),
+            "Esperado Err(DomainError::Validation(nome, senha)), obtido {:?}",
+            result
+        );
+    }
+
     #[test]
     fn test_usuario_setters_valid() {
         let mut usuario = create_valid_usuario();
         let _ = usuario.set_nome("Ciclano de Almeida".to_string());
-        let _ = usuario.set_email("ciclano.almeida@exemplo.com".to_string());
+        usuario.set_email(Email::new("ciclano.almeida@exemplo.com".to_string()).unwrap());
         let _ = usuario.set_senha("nova_senha_segura".to_string());
         usuario.set_tipo(Tipo::Cozinha);
         usuario.set_status(Status::Inativo);
         assert_eq!(usuario.nome(), "Ciclano de Almeida");
-        assert_eq!(usuario.email(), "ciclano.almeida@exemplo.com");
-        assert!(usuario.validate_senha(&"nova_senha_segura".to_string()));
+        assert_eq!(usuario.email().0, "ciclano.almeida@exemplo.com");
+        assert_eq!(usuario.senha(), "nova_senha_segura");
         assert_eq!(usuario.tipo(), &Tipo::Cozinha);
         assert_eq!(usuario.status(), &Status::Inativo);
     }
@@ -248,19 +261,8 @@ mod tests {
         let mut usuario = create_valid_usuario();
         let result = usuario.set_nome("".to_string());
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
-            result
-        );
-    }
-
-    #[test]
-    fn test_usuario_set_email_empty() {
-        let mut usuario = create_valid_usuario();
-        let result = usuario.set_email("".to_string());
-        assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(&result, Err(DomainError::Validation(errors)) if errors == &vec![FieldError::required("nome")]),
+            "Esperado Err(DomainError::Validation(nome)), obtido {:?}",
             result
         );
     }
@@ -270,20 +272,18 @@ mod tests {
         let mut usuario = create_valid_usuario();
         let result = usuario.set_senha("".to_string());
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(&result, Err(DomainError::Validation(errors)) if errors == &vec![FieldError::required("senha")]),
+            "Esperado Err(DomainError::Validation(senha)), obtido {:?}",
             result
         );
     }
 
     #[test]
-    fn test_usuario_set_data_atualizacao_invalid_format() {
+    fn test_usuario_set_data_atualizacao() {
         let mut usuario = create_valid_usuario();
-        let result = usuario.set_data_atualizacao("18-02-2024".to_string());
-        assert!(
-            matches!(result, Err(DomainError::Invalid(_))),
-            "Esperado Err(DomainError::Invalid), obtido {:?}",
-            result
-        );
+        let data_atualizacao = *usuario.data_criacao() + chrono::Duration::minutes(5);
+        usuario.set_data_atualizacao(data_atualizacao);
+        assert_eq!(usuario.data_atualizacao(), &data_atualizacao);
+        assert!(usuario.data_atualizacao() > usuario.data_criacao());
     }
 }
@@ -1,2 +1,11 @@
 pub mod aws_cognito_cliente_gateway;
-pub mod aws_cognito_usuario_gateway;
\ No newline at end of file
+pub mod aws_cognito_usuario_gateway;
+pub mod cognito_ids;
+#[cfg(test)]
+pub mod cognito_stand_in;
+pub mod in_memory_cliente_gateway;
+pub mod in_memory_usuario_gateway;
+pub mod postgres_cliente_gateway;
+pub mod postgres_errors;
+pub mod postgres_list_query;
+pub mod postgres_usuario_gateway;
\ No newline at end of file
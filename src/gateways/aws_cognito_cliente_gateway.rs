@@ -1,13 +1,21 @@
 use aws_config::from_env;
 use aws_config::meta::region::RegionProviderChain;
 use aws_sdk_cognitoidentityprovider::operation::list_users;
-use aws_sdk_cognitoidentityprovider::types::AttributeType;
+use aws_sdk_cognitoidentityprovider::error::SdkError;
+use aws_sdk_cognitoidentityprovider::types::{AttributeType, UserType};
 use aws_sdk_cognitoidentityprovider::{config::Region, meta::PKG_VERSION, Client};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
 
 use crate::{
-    base::domain_error::DomainError, entities::cliente::Cliente, entities::cpf::Cpf,
-    traits::cliente_gateway::ClienteGateway,
+    base::domain_error::DomainError,
+    base::pagination::{Page, PageRequest},
+    base::timestamp::{format_timestamp, parse_timestamp},
+    entities::cliente::Cliente,
+    entities::cpf::Cpf,
+    entities::email::Email,
+    gateways::cognito_ids::{email_attribute, legacy_username_from_id, next_id},
+    traits::cliente_gateway::{ClienteFiltro, ClienteGateway},
 };
 
 fn option_to_string(option: Option<&str>) -> String {
@@ -17,6 +25,76 @@ fn option_to_string(option: Option<&str>) -> String {
     }
 }
 
+fn username_from_id(id: usize) -> String {
+    format!("cliente-{}", id)
+}
+
+// O username é `cliente-<id>` e não muda com o CPF. Registros antigos usam o
+// CPF formatado (id derivado do CPF) ou `anonimo-<id>`
+fn usernames_from_id(id: usize) -> Vec<String> {
+    match legacy_username_from_id(id) {
+        Some(username) => vec![username],
+        None => vec![username_from_id(id), format!("anonimo-{}", id)],
+    }
+}
+
+fn email_to_attribute(email: &Option<Email>) -> String {
+    email.as_ref().map(|email| email.0.clone()).unwrap_or_default()
+}
+
+fn cliente_from_attributes(attributes: &[AttributeType]) -> Option<Cliente> {
+    let mut id = String::new();
+    let mut nome = String::new();
+    let mut email = String::new();
+    let mut cpf_string = String::new();
+    let mut data_criacao = String::new();
+    let mut data_atualizacao = String::new();
+
+    for attr in attributes {
+        match attr.name() {
+            "custom:id" => id = option_to_string(attr.value()),
+            "custom:nome" => nome = option_to_string(attr.value()),
+            "custom:email" => email = option_to_string(attr.value()),
+            "custom:cpf" => cpf_string = option_to_string(attr.value()),
+            "custom:data_criacao" => data_criacao = option_to_string(attr.value()),
+            "custom:data_atualizacao" => data_atualizacao = option_to_string(attr.value()),
+            _ => {}
+        }
+    }
+
+    let id_value = match id.parse::<usize>() {
+        Ok(id_value) => id_value,
+        Err(_) => {
+            println!("Failed to convert string, ID: {}", id);
+            return None;
+        }
+    };
+
+    let (data_criacao, data_atualizacao) = match (parse_timestamp(&data_criacao), parse_timestamp(&data_atualizacao)) {
+        (Ok(data_criacao), Ok(data_atualizacao)) => (data_criacao, data_atualizacao),
+        _ => {
+            println!("Invalid timestamps for user, ID: {}", id);
+            return None;
+        }
+    };
+
+    if cpf_string.is_empty() {
+        return Some(Cliente::anonimo(id_value, data_criacao, data_atualizacao));
+    }
+
+    match (Cpf::new(cpf_string), Email::new(email)) {
+        (Ok(cpf), Ok(email)) => Some(Cliente::new(id_value, nome, email, cpf, data_criacao, data_atualizacao)),
+        (Err(_), _) => {
+            println!("Invalid CPF for user: {}", nome);
+            None
+        }
+        (_, Err(_)) => {
+            println!("Invalid email for user: {}", nome);
+            None
+        }
+    }
+}
+
 pub struct AwsCognitoClienteRepository {
     client: Client,
     user_pool_id: String,
@@ -28,190 +106,309 @@ impl AwsCognitoClienteRepository {
 
         let config = aws_config::from_env().region(region_provider).load().await;
         let client = Client::new(&config);
+        AwsCognitoClienteRepository::from_client(client, user_pool_id)
+    }
+
+    pub fn from_client(client: Client, user_pool_id: String) -> Self {
         AwsCognitoClienteRepository {
             client,
             user_pool_id,
         }
     }
-}
 
-#[async_trait]
-impl ClienteGateway for AwsCognitoClienteRepository {
-    async fn get_clientes(&self) -> Result<Vec<Cliente>, DomainError> {
+    async fn get_user(&self, username: &str) -> Result<Option<(String, Cliente)>, DomainError> {
         let response = self
             .client
-            .list_users()
+            .admin_get_user()
             .user_pool_id(&self.user_pool_id)
+            .username(username)
             .send()
             .await;
 
-        let mut clientes: Vec<Cliente> = Vec::new();
-
         match response {
-            Ok(response) => {
-                let users = response.users();
-
-                for user in users {
-                    let mut id = String::new();
-                    let mut nome = String::new();
-                    let mut email = String::new();
-                    let mut cpf_string = String::new();
-                    let mut data_criacao = String::new();
-                    let mut data_atualizacao = String::new();
-
-                    for attr in user.attributes() {
-                        match attr.name() {
-                            "custom:id" => id =option_to_string(attr.value()),
-                            "custom:nome" => nome = option_to_string(attr.value()),
-                            "custom:email" => email = option_to_string(attr.value()),
-                            "custom:cpf" => cpf_string = option_to_string(attr.value()),
-                            "custom:data_criacao" => data_criacao = option_to_string(attr.value()),
-                            "custom:data_atualizacao" => data_atualizacao = option_to_string(attr.value()),
-                            _ => {}
-                        }
-                    };
-
-                    let cpf = Cpf::new(cpf_string.to_string());
-
-                    match cpf {
-                        Ok(cpf) => {
-                            match id.parse::<usize>() {
-                                Ok(id_value) => {
-                                    let cliente = Cliente::new(
-                                        id_value,
-                                        nome,
-                                        email,
-                                        cpf,
-                                        data_criacao,
-                                        data_atualizacao,
-                                    );
-                
-                                    clientes.push(cliente);
-                                },
-                                Err(error) => {
-                                    println!("Failed to convert string, ID: {}", id);
-                                }
-                            }
-                            
-                        },
-                        Err(err) => println!("Invalid CPF for user: {}", nome),
-                    };
-                }
-                Ok(clientes)
-            }
+            Ok(response) => Ok(cliente_from_attributes(response.user_attributes())
+                .map(|cliente| (response.username().to_string(), cliente))),
+            Err(SdkError::ServiceError(err)) if err.err().is_user_not_found_exception() => Ok(None),
             Err(err) => {
                 println!("Error during aws cognito request: {}", err);
-                Err(DomainError::NotFound)
+                Err(DomainError::Internal("Cliente".to_string()))
             }
+        }
+    }
 
+    async fn find_by_id(&self, id: usize) -> Result<Option<(String, Cliente)>, DomainError> {
+        for username in usernames_from_id(id) {
+            let found = self.get_user(&username).await?;
+            if let Some(found) = found.filter(|(_, cliente)| *cliente.id() == id) {
+                return Ok(Some(found));
+            }
         }
+        Ok(None)
     }
 
-    async fn get_cliente_by_cpf(&self, cpf: Cpf) -> Result<Cliente, DomainError> {
-        let clientes_result = self.get_clientes().await;
+    // O CPF fica em preferred_username, que o ListUsers aceita como filtro;
+    // registros antigos identificados no cadastro só têm o CPF como username
+    async fn find_by_cpf(&self, cpf: &Cpf) -> Result<Option<(String, Cliente)>, DomainError> {
+        let response = self
+            .client
+            .list_users()
+            .user_pool_id(&self.user_pool_id)
+            .filter(format!("preferred_username = \"{}\"", cpf.formatted()))
+            .limit(1)
+            .send()
+            .await
+            .map_err(|err| {
+                println!("Error during aws cognito request: {}", err);
+                DomainError::Internal("Cliente".to_string())
+            })?;
 
-        let clientes = match clientes_result {
-            Ok(clientes) => clientes,
-            Err(err) => {
-                println!("Error retrieving clientes");
-                return Err(err);
-            }
-        };
+        let found = response.users().first().and_then(|user| {
+            cliente_from_attributes(user.attributes())
+                .filter(|cliente| cliente.cpf().as_ref() == Some(cpf))
+                .map(|cliente| (option_to_string(user.username()), cliente))
+        });
+        match found {
+            Some(found) => Ok(Some(found)),
+            // Um registro antigo com CPF corrigido mantém o CPF original como
+            // username, então o CPF do registro precisa conferir
+            None => Ok(self
+                .get_user(&cpf.formatted())
+                .await?
+                .filter(|(_, cliente)| cliente.cpf().as_ref() == Some(cpf))),
+        }
+    }
+
+    // Registros sem o atributo `email` (anteriores a ele) só passam a ser
+    // encontrados por aqui depois da próxima atualização
+    async fn find_by_email(&self, email: &Email) -> Result<Option<Cliente>, DomainError> {
+        let response = self
+            .client
+            .list_users()
+            .user_pool_id(&self.user_pool_id)
+            .filter(format!("email = \"{}\"", email_attribute(email)))
+            .send()
+            .await
+            .map_err(|err| {
+                println!("Error during aws cognito request: {}", err);
+                DomainError::Internal("Cliente".to_string())
+            })?;
+
+        Ok(response
+            .users()
+            .iter()
+            .filter_map(|user| cliente_from_attributes(user.attributes()))
+            .find(|cliente| cliente.email().as_ref().is_some_and(|cliente_email| cliente_email.eq_ignore_case(email))))
+    }
 
-        for cliente in clientes {
-            if cpf.0 == cliente.cpf().0 {
-                return Ok(cliente);
+    // O ListUsers devolve no máximo 60 usuários por chamada; segue o
+    // pagination_token até trazer o pool inteiro
+    async fn list_all_users(&self) -> Result<Vec<UserType>, SdkError<list_users::ListUsersError>> {
+        let mut users: Vec<UserType> = Vec::new();
+        let mut pagination_token: Option<String> = None;
+        loop {
+            let response = self
+                .client
+                .list_users()
+                .user_pool_id(&self.user_pool_id)
+                .set_pagination_token(pagination_token.take())
+                .send()
+                .await?;
+            users.extend(response.users().iter().cloned());
+            pagination_token = response.pagination_token().map(str::to_string);
+            if pagination_token.is_none() {
+                return Ok(users);
             }
         }
+    }
+}
 
-        Err(DomainError::NotFound)
+fn build_attributes(attribute_specs: Vec<(&str, &str)>) -> Vec<AttributeType> {
+    let mut attributes = Vec::new();
+    for (name, value) in attribute_specs {
+        match AttributeType::builder()
+            .name(name)
+            .value(value)
+            .build()
+        {
+            Ok(attr) => attributes.push(attr),
+            Err(err) => println!("Failed to build attribute {}: {}", name, err),
+        }
+    }
+    attributes
+}
 
+#[async_trait]
+impl ClienteGateway for AwsCognitoClienteRepository {
+    async fn get_clientes(&self) -> Result<Vec<Cliente>, DomainError> {
+        let users = self.list_all_users().await.map_err(|err| {
+            println!("Error during aws cognito request: {}", err);
+            DomainError::NotFound
+        })?;
+        Ok(users
+            .iter()
+            .filter_map(|user| cliente_from_attributes(user.attributes()))
+            .collect())
     }
 
-    async fn get_cliente_by_id(&self, id: usize) -> Result<Cliente, DomainError> {
-        let clientes_result = self.get_clientes().await;
+    async fn list_clientes(&self, filtro: ClienteFiltro, page: PageRequest) -> Result<Page<Cliente>, DomainError> {
+        let clientes = self.get_clientes().await?;
+        Ok(Page::from_all(filtro.apply(clientes), &page))
+    }
 
-        let clientes = match clientes_result {
-            Ok(clientes) => clientes,
-            Err(err) => {
-                println!("Error retrieving clientes");
-                return Err(err);
-            }
-        };
+    async fn get_cliente_by_cpf(&self, cpf: Cpf) -> Result<Cliente, DomainError> {
+        match self.find_by_cpf(&cpf).await? {
+            Some((_, cliente)) => Ok(cliente),
+            None => Err(DomainError::NotFound),
+        }
+    }
 
-        for cliente in clientes {
+    async fn get_cliente_by_email(&self, email: Email) -> Result<Cliente, DomainError> {
+        self.find_by_email(&email).await?.ok_or(DomainError::NotFound)
+    }
 
-            if id == *cliente.id() {
-                return Ok(cliente);
-            }
+    async fn get_cliente_by_id(&self, id: usize) -> Result<Cliente, DomainError> {
+        match self.find_by_id(id).await? {
+            Some((_, cliente)) => Ok(cliente),
+            None => Err(DomainError::NotFound),
         }
-
-        Err(DomainError::NotFound)
     }
 
-    async fn create_cliente(&mut self, cliente: Cliente) -> Result<Cliente, DomainError> {
-        let cpf_string = &cliente.cpf().0;
-        // Initialize an empty vector to hold successfully built attributes
-        let mut attributes = Vec::new();
+    async fn create_cliente(&self, cliente: Cliente) -> Result<Cliente, DomainError> {
+        // O username não carrega o CPF, então a unicidade é conferida antes
+        if let Some(cpf) = cliente.cpf() {
+            if self.find_by_cpf(cpf).await?.is_some() {
+                return Err(DomainError::AlreadyExists("cpf".to_string()));
+            }
+        }
+        let data_criacao = format_timestamp(cliente.data_criacao());
+        let data_atualizacao = format_timestamp(cliente.data_atualizacao());
+        let cpf_string = match cliente.cpf() {
+            Some(cpf) => cpf.formatted(),
+            None => String::new(),
+        };
+        let email_string = email_to_attribute(cliente.email());
+        let email_busca = cliente.email().as_ref().map(email_attribute).unwrap_or_default();
+
+        // Um username repetido só acontece se outra instância gerou o mesmo id;
+        // nesse caso gera outro
+        for _ in 0..3 {
+            let id = next_id();
+            let id_string = id.to_string();
+            let username = username_from_id(id);
+            let mut attribute_specs = vec![
+                ("custom:id", id_string.as_str()),
+                ("custom:data_criacao", data_criacao.as_str()),
+                ("custom:data_atualizacao", data_atualizacao.as_str()),
+            ];
+            if !cpf_string.is_empty() {
+                attribute_specs.extend([
+                    ("custom:nome", cliente.nome().as_str()),
+                    ("custom:email", email_string.as_str()),
+                    ("email", email_busca.as_str()),
+                    ("email_verified", "true"),
+                    ("custom:cpf", cpf_string.as_str()),
+                    ("preferred_username", cpf_string.as_str()),
+                ]);
+            }
 
-        let id = cpf_string.replace(".", "").replace("-", "");
-        let string_id: &str = &id;
-        // List of attribute specifications
-        let attribute_specs = vec![
-            ("custom:id", string_id),
-            ("custom:nome", cliente.nome()),
-            ("custom:email", cliente.email()),
-            ("custom:cpf", cpf_string),
-            ("custom:data_criacao", cliente.data_criacao()),
-            ("custom:data_atualizacao", cliente.data_atualizacao()),
-        ];
+            let response = self.client
+                .admin_create_user()
+                .user_pool_id(&self.user_pool_id)
+                .username(&username)
+                .temporary_password(Uuid::new_v4().to_string())
+                .set_user_attributes(Some(build_attributes(attribute_specs)))
+                .send()
+                .await;
 
-        // Iterate over attribute specifications
-        for (name, value) in attribute_specs {
-            // Attempt to build an attribute
-            match AttributeType::builder()
-                .name(name)
-                .value(value)
-                .build()
-            {
-                Ok(attr) => {
-                    // Successfully built the attribute, add it to the vector
-                    attributes.push(attr);
+            match response {
+                Ok(_) => {
+                    println!("Successfully created user: {}", username);
+                    return Ok(match (cliente.cpf(), cliente.email()) {
+                        (Some(cpf), Some(email)) => Cliente::new(
+                            id,
+                            cliente.nome().clone(),
+                            email.clone(),
+                            cpf.clone(),
+                            *cliente.data_criacao(),
+                            *cliente.data_atualizacao(),
+                        ),
+                        _ => Cliente::anonimo(id, *cliente.data_criacao(), *cliente.data_atualizacao()),
+                    });
+                },
+                Err(SdkError::ServiceError(err)) if err.err().is_username_exists_exception() => {
+                    println!("Username {} already taken, generating another id", username);
                 },
                 Err(err) => {
-                    println!("Failed to build attribute {}: {}", name, err);
+                    println!("SDK ERROR: {}",err.to_string());
+                    println!("Failed to create user: {}", username);
+                    return Err(DomainError::Internal("Cliente".to_string()));
                 }
             }
         }
+        Err(DomainError::Internal("Cliente".to_string()))
+    }
+
+    async fn update_cliente(&self, dados_cliente_atualizado: Cliente) -> Result<Cliente, DomainError> {
+        let username = match self.find_by_id(*dados_cliente_atualizado.id()).await? {
+            Some((username, _)) => username,
+            None => return Err(DomainError::NotFound),
+        };
+        if let Some(cpf) = dados_cliente_atualizado.cpf() {
+            if let Some((_, existente)) = self.find_by_cpf(cpf).await? {
+                if existente.id() != dados_cliente_atualizado.id() {
+                    return Err(DomainError::AlreadyExists("cpf".to_string()));
+                }
+            }
+        }
+
+        let cpf_string = match dados_cliente_atualizado.cpf() {
+            Some(cpf) => cpf.formatted(),
+            None => String::new(),
+        };
+        let email_string = email_to_attribute(dados_cliente_atualizado.email());
+        let email_busca = dados_cliente_atualizado.email().as_ref().map(email_attribute).unwrap_or_default();
+        let data_atualizacao = format_timestamp(dados_cliente_atualizado.data_atualizacao());
+        let mut attribute_specs = vec![
+            ("custom:nome", dados_cliente_atualizado.nome().as_str()),
+            ("custom:email", email_string.as_str()),
+            ("custom:cpf", cpf_string.as_str()),
+            ("custom:data_atualizacao", data_atualizacao.as_str()),
+        ];
+        if !cpf_string.is_empty() {
+            attribute_specs.push(("preferred_username", cpf_string.as_str()));
+        }
+        if !email_busca.is_empty() {
+            attribute_specs.extend([("email", email_busca.as_str()), ("email_verified", "true")]);
+        }
+        let attributes = build_attributes(attribute_specs);
 
         let response = self.client
-            .admin_create_user()
+            .admin_update_user_attributes()
             .user_pool_id(&self.user_pool_id)
-            .username(cpf_string)
-            .temporary_password(cpf_string)
+            .username(&username)
             .set_user_attributes(Some(attributes))
             .send()
             .await;
 
         match response {
-            Ok(resp) => {
-                println!("Successfully created user: {}", cliente.id());
-                Ok(cliente)
-            },
+            Ok(_) => Ok(dados_cliente_atualizado),
             Err(err) => {
-                println!("SDK ERROR: {}",err.to_string());
-                println!("Failed to create user: {}", cpf_string);
-                Err(DomainError::Invalid("Cliente".to_string()))
+                println!("SDK ERROR: {}", err);
+                println!("Failed to update user: {}", username);
+                Err(DomainError::Internal("Cliente".to_string()))
             }
         }
     }
 
-    async fn delete_cliente(&mut self, cpf: Cpf) -> Result<(), DomainError> {
-        let cpf_string = cpf.0;
+    async fn delete_cliente(&self, cpf: Cpf) -> Result<(), DomainError> {
+        let username = match self.find_by_cpf(&cpf).await? {
+            Some((username, _)) => username,
+            None => return Err(DomainError::NotFound),
+        };
         let response = self.client
             .admin_delete_user()
             .user_pool_id(&self.user_pool_id)
-            .username(cpf_string.clone())
+            .username(username.clone())
             .send()
             .await;
 
@@ -220,9 +417,252 @@ impl ClienteGateway for AwsCognitoClienteRepository {
                 Ok(())
             },
             Err(err) => {
-                println!("Failed to delete user: {}", cpf_string);
+                println!("Failed to delete user: {}", username);
                 Err(DomainError::NotFound)
             }
         }
     }
+    async fn delete_clientes_anonimos(&self, criado_ate: DateTime<Utc>) -> Result<usize, DomainError> {
+        let users = self.list_all_users().await.map_err(|err| {
+            println!("Error during aws cognito request: {}", err);
+            DomainError::Internal("Cliente".to_string())
+        })?;
+        let mut removidos = 0;
+        for user in users {
+            let expirado = cliente_from_attributes(user.attributes())
+                .is_some_and(|cliente| cliente.is_anonimo() && *cliente.data_criacao() < criado_ate);
+            if !expirado {
+                continue;
+            }
+            let username = option_to_string(user.username());
+            self.client
+                .admin_delete_user()
+                .user_pool_id(&self.user_pool_id)
+                .username(username.clone())
+                .send()
+                .await
+                .map_err(|err| {
+                    println!("Failed to delete user {}: {}", username, err);
+                    DomainError::Internal("Cliente".to_string())
+                })?;
+            removidos += 1;
+        }
+        Ok(removidos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateways::cognito_stand_in::{cpf_valido, CognitoStandIn, USER_POOL_ID};
+    use chrono::Utc;
+    use tokio;
+
+    fn create_cliente(cpf: &str) -> Cliente {
+        let _now = Utc::now();
+        Cliente::new(
+            0,
+            "Fulano da Silva".to_string(),
+            Email::new("fulano.silva@exemplo.com".to_string()).unwrap(),
+            Cpf::new(cpf.to_string()).unwrap(),
+            _now,
+            _now,
+        )
+    }
+
+    async fn repository() -> (CognitoStandIn, AwsCognitoClienteRepository) {
+        let stand_in = CognitoStandIn::start().await;
+        let repository = AwsCognitoClienteRepository::from_client(stand_in.client(), USER_POOL_ID.to_string());
+        (stand_in, repository)
+    }
+
+    #[tokio::test]
+    async fn test_get_cliente_by_cpf_and_id_use_single_lookup() {
+        let (stand_in, repository) = repository().await;
+        let cpf = cpf_valido(123456789);
+        let cliente = repository.create_cliente(create_cliente(&cpf)).await.unwrap();
+        assert_eq!(stand_in.usernames(), vec![format!("cliente-{}", cliente.id())]);
+        stand_in.take_operations();
+
+        let by_cpf = repository.get_cliente_by_cpf(Cpf::new(cpf.clone()).unwrap()).await.unwrap();
+        assert_eq!(by_cpf.id(), cliente.id());
+        assert_eq!(stand_in.take_operations(), vec!["ListUsers"]);
+
+        let by_id = repository.get_cliente_by_id(*cliente.id()).await.unwrap();
+        assert_eq!(by_id.cpf().as_ref().unwrap().formatted(), cpf);
+        assert_eq!(stand_in.take_operations(), vec!["AdminGetUser"]);
+
+        let result = repository.create_cliente(create_cliente(&cpf)).await;
+        assert!(
+            matches!(result, Err(DomainError::AlreadyExists(_))),
+            "Esperado Err(DomainError::AlreadyExists), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_cliente_by_email_uses_filter() {
+        let (stand_in, repository) = repository().await;
+        let cliente = repository.create_cliente(create_cliente(&cpf_valido(123456789))).await.unwrap();
+        let _now = Utc::now();
+        let _ = repository.create_cliente(Cliente::anonimo(0, _now, _now)).await.unwrap();
+        stand_in.take_operations();
+
+        let by_email = repository
+            .get_cliente_by_email(Email::new("Fulano.Silva@exemplo.com".to_string()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(by_email.id(), cliente.id());
+        assert_eq!(stand_in.take_operations(), vec!["ListUsers"]);
+
+        let result = repository.get_cliente_by_email(Email::new("outro@exemplo.com".to_string()).unwrap()).await;
+        assert!(matches!(result, Err(DomainError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_promoted_anonimo_is_found_by_cpf_and_id() {
+        let (stand_in, repository) = repository().await;
+        let _now = Utc::now();
+        let anonimo = repository
+            .create_cliente(Cliente::anonimo(0, _now, _now))
+            .await
+            .unwrap();
+        assert_eq!(stand_in.usernames(), vec![format!("cliente-{}", anonimo.id())]);
+
+        let by_id = repository.get_cliente_by_id(*anonimo.id()).await.unwrap();
+        assert!(by_id.is_anonimo());
+
+        let cpf = Cpf::new(cpf_valido(987654321)).unwrap();
+        let mut promovido = anonimo.clone();
+        promovido
+            .identifica("Ciclano".to_string(), Email::new("ciclano@exemplo.com".to_string()).unwrap(), cpf.clone())
+            .unwrap();
+        repository.update_cliente(promovido).await.unwrap();
+        stand_in.take_operations();
+
+        let by_cpf = repository.get_cliente_by_cpf(cpf.clone()).await.unwrap();
+        assert_eq!(by_cpf.id(), anonimo.id());
+        assert_eq!(stand_in.take_operations(), vec!["ListUsers"]);
+
+        let by_id = repository.get_cliente_by_id(*anonimo.id()).await.unwrap();
+        assert_eq!(by_id.nome(), "Ciclano");
+
+        repository.delete_cliente(cpf.clone()).await.unwrap();
+        assert!(stand_in.usernames().is_empty());
+        let result = repository.get_cliente_by_cpf(cpf).await;
+        assert!(matches!(result, Err(DomainError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_delete_clientes_anonimos() {
+        let (stand_in, repository) = repository().await;
+        let _now = Utc::now();
+        let antigo = _now - chrono::Duration::days(2);
+        let identificado = repository.create_cliente(create_cliente(&cpf_valido(123456789))).await.unwrap();
+        let _ = repository.create_cliente(Cliente::anonimo(0, antigo, antigo)).await.unwrap();
+        let recente = repository.create_cliente(Cliente::anonimo(0, _now, _now)).await.unwrap();
+
+        let removidos = repository.delete_clientes_anonimos(_now - chrono::Duration::days(1)).await.unwrap();
+        assert_eq!(removidos, 1);
+        let mut usernames = stand_in.usernames();
+        usernames.sort();
+        assert_eq!(
+            usernames,
+            vec![format!("cliente-{}", identificado.id()), format!("cliente-{}", recente.id())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_cliente_rejects_cpf_of_another_cliente() {
+        let (stand_in, repository) = repository().await;
+        let _now = Utc::now();
+        let cpf = cpf_valido(111222333);
+        repository.create_cliente(create_cliente(&cpf)).await.unwrap();
+        let anonimo = repository
+            .create_cliente(Cliente::anonimo(0, _now, _now))
+            .await
+            .unwrap();
+
+        let mut promovido = anonimo;
+        promovido
+            .identifica("Ciclano".to_string(), Email::new("ciclano@exemplo.com".to_string()).unwrap(), Cpf::new(cpf).unwrap())
+            .unwrap();
+        let result = repository.update_cliente(promovido).await;
+        assert!(
+            matches!(result, Err(DomainError::AlreadyExists(_))),
+            "Esperado Err(DomainError::AlreadyExists), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_correcting_cpf_keeps_id_and_username() {
+        let (stand_in, repository) = repository().await;
+        let cliente = repository.create_cliente(create_cliente(&cpf_valido(555666777))).await.unwrap();
+
+        let novo_cpf = Cpf::new(cpf_valido(555666778)).unwrap();
+        let mut corrigido = cliente.clone();
+        corrigido.set_cpf(novo_cpf.clone());
+        repository.update_cliente(corrigido).await.unwrap();
+
+        assert_eq!(stand_in.usernames(), vec![format!("cliente-{}", cliente.id())]);
+        let by_cpf = repository.get_cliente_by_cpf(novo_cpf.clone()).await.unwrap();
+        assert_eq!(by_cpf.id(), cliente.id());
+        let by_id = repository.get_cliente_by_id(*cliente.id()).await.unwrap();
+        assert_eq!(by_id.cpf(), &Some(novo_cpf));
+    }
+
+    #[tokio::test]
+    async fn test_finds_legacy_cpf_username() {
+        let (stand_in, repository) = repository().await;
+        let cpf = cpf_valido(123456789);
+        let id = Cpf::new(cpf.clone()).unwrap().digits().to_string();
+        stand_in.add_user(
+            &cpf,
+            vec![
+                ("custom:id", &id),
+                ("custom:nome", "Fulano"),
+                ("custom:email", "fulano@exemplo.com"),
+                ("custom:cpf", &cpf),
+                ("custom:data_criacao", "2024-01-01 10:00:00.000+0000"),
+                ("custom:data_atualizacao", "2024-01-01 10:00:00.000+0000"),
+            ],
+        );
+
+        let by_cpf = repository.get_cliente_by_cpf(Cpf::new(cpf.clone()).unwrap()).await.unwrap();
+        assert_eq!(by_cpf.id().to_string(), id);
+        let by_id = repository.get_cliente_by_id(*by_cpf.id()).await.unwrap();
+        assert_eq!(by_id.nome(), "Fulano");
+    }
+
+    #[tokio::test]
+    async fn test_legacy_cliente_with_corrected_cpf_is_not_found_by_old_cpf() {
+        let (stand_in, repository) = repository().await;
+        let cpf = cpf_valido(123456789);
+        let novo_cpf = cpf_valido(987654321);
+        let id = Cpf::new(cpf.clone()).unwrap().digits().to_string();
+        stand_in.add_user(
+            &cpf,
+            vec![
+                ("custom:id", &id),
+                ("custom:nome", "Fulano"),
+                ("custom:email", "fulano@exemplo.com"),
+                ("custom:cpf", &novo_cpf),
+                ("preferred_username", &novo_cpf),
+                ("custom:data_criacao", "2024-01-01 10:00:00.000+0000"),
+                ("custom:data_atualizacao", "2024-01-01 10:00:00.000+0000"),
+            ],
+        );
+
+        let result = repository.get_cliente_by_cpf(Cpf::new(cpf.clone()).unwrap()).await;
+        assert!(
+            matches!(result, Err(DomainError::NotFound)),
+            "Esperado Err(DomainError::NotFound), obtido {:?}",
+            result
+        );
+        let result = repository.delete_cliente(Cpf::new(cpf).unwrap()).await;
+        assert!(matches!(result, Err(DomainError::NotFound)));
+        let by_cpf = repository.get_cliente_by_cpf(Cpf::new(novo_cpf).unwrap()).await.unwrap();
+        assert_eq!(by_cpf.id().to_string(), id);
+    }
 }
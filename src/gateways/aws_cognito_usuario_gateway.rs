@@ -3,19 +3,22 @@ use std::str::FromStr;
 use aws_config::from_env;
 use aws_config::meta::region::RegionProviderChain;
 use aws_sdk_cognitoidentityprovider::operation::list_users;
-use aws_sdk_cognitoidentityprovider::types::AttributeType;
+use aws_sdk_cognitoidentityprovider::types::{AttributeType, UserType};
 use aws_sdk_cognitoidentityprovider::error::SdkError;
 use aws_sdk_cognitoidentityprovider::error::UnknownVariantError;
 use aws_sdk_cognitoidentityprovider::{config::Region, meta::PKG_VERSION, Client};
-use chrono::Utc;
 
 use crate::base::domain_error::DomainError;
+use crate::base::pagination::{Page, PageRequest};
+use crate::base::timestamp::{format_timestamp, parse_timestamp};
+use crate::gateways::cognito_ids::{email_attribute, legacy_username_from_id, next_id};
 use crate::{
-    traits::usuario_gateway::UsuarioGateway,
+    traits::usuario_gateway::{UsuarioFiltro, UsuarioGateway},
 };
 
 use crate::entities::{
     cpf::Cpf,
+    email::Email,
     usuario::{Status, Usuario,Tipo},
 };
 
@@ -26,6 +29,88 @@ fn option_to_string(option: Option<&str>) -> String {
     }
 }
 
+// O username é `usuario-<id>` e não muda com o CPF; registros antigos usam o
+// CPF formatado, com o id derivado dele
+fn username_from_id(id: usize) -> String {
+    legacy_username_from_id(id).unwrap_or_else(|| format!("usuario-{}", id))
+}
+
+fn usuario_from_attributes(attributes: &[AttributeType]) -> Option<Usuario> {
+    let mut id = String::new();
+    let mut nome = String::new();
+    let mut email = String::new();
+    let mut cpf_string = String::new();
+    let mut senha = String::new();
+    let mut tipo_string = String::new();
+    let mut status_string = String::new();
+    let mut data_criacao = String::new();
+    let mut data_atualizacao = String::new();
+    let mut troca_senha = String::new();
+
+    for attr in attributes {
+        match attr.name() {
+            "custom:id" => id = option_to_string(attr.value()),
+            "custom:nome" => nome = option_to_string(attr.value()),
+            "custom:email" => email = option_to_string(attr.value()),
+            "custom:cpf" => cpf_string = option_to_string(attr.value()),
+            "custom:senha" => senha = option_to_string(attr.value()),
+            "custom:tipo" => tipo_string = option_to_string(attr.value()),
+            "custom:status" => status_string = option_to_string(attr.value()),
+            "custom:data_criacao" => data_criacao = option_to_string(attr.value()),
+            "custom:data_atualizacao" => data_atualizacao = option_to_string(attr.value()),
+            "custom:troca_senha" => troca_senha = option_to_string(attr.value()),
+            _ => {}
+        }
+    }
+
+    let cpf = match Cpf::new(cpf_string) {
+        Ok(cpf) => cpf,
+        Err(_) => {
+            println!("Error on cpf skiping");
+            return None;
+        }
+    };
+    let email = match Email::new(email) {
+        Ok(email) => email,
+        Err(_) => {
+            println!("Invalid email for user, ID: {}", id);
+            return None;
+        }
+    };
+    let tipo = Tipo::from_str(tipo_string.as_str()).ok()?;
+    let status = Status::from_str(status_string.as_str()).ok()?;
+    let (data_criacao, data_atualizacao) = match (parse_timestamp(&data_criacao), parse_timestamp(&data_atualizacao)) {
+        (Ok(data_criacao), Ok(data_atualizacao)) => (data_criacao, data_atualizacao),
+        _ => {
+            println!("Invalid timestamps for user, ID: {}", id);
+            return None;
+        }
+    };
+
+    match id.parse::<usize>() {
+        Ok(id_value) => {
+            let mut usuario = Usuario::new(
+                id_value,
+                nome,
+                email,
+                cpf,
+                senha,
+                tipo,
+                status,
+                data_criacao,
+                data_atualizacao,
+            );
+            // Registros sem o atributo são anteriores à troca obrigatória
+            usuario.set_troca_senha_obrigatoria(troca_senha == "true");
+            Some(usuario)
+        }
+        Err(_) => {
+            println!("Failed to convert string, ID: {}", id);
+            None
+        }
+    }
+}
+
 
 pub struct AwsCognitoUsuarioRepository {
     client: Client,
@@ -38,129 +123,135 @@ impl AwsCognitoUsuarioRepository {
 
         let config = aws_config::from_env().region(region_provider).load().await;
         let client = Client::new(&config);
-        
 
-        let mut repo = AwsCognitoUsuarioRepository {
+        AwsCognitoUsuarioRepository::from_client(client, user_pool_id)
+    }
+
+    pub fn from_client(client: Client, user_pool_id: String) -> Self {
+        AwsCognitoUsuarioRepository {
             client,
             user_pool_id,
-        };
+        }
+    }
 
-        repo.check_for_usuario_admin().await;
+    async fn get_user_attributes(&self, username: &str) -> Result<Option<Vec<AttributeType>>, DomainError> {
+        let response = self
+            .client
+            .admin_get_user()
+            .user_pool_id(&self.user_pool_id)
+            .username(username)
+            .send()
+            .await;
+
+        match response {
+            Ok(response) => Ok(Some(response.user_attributes().to_vec())),
+            Err(SdkError::ServiceError(err)) if err.err().is_user_not_found_exception() => Ok(None),
+            Err(err) => {
+                println!("Error during aws cognito request: {}", err);
+                Err(DomainError::Internal("Usuario".to_string()))
+            }
+        }
+    }
 
-        repo
+    async fn find_by_id(&self, id: usize) -> Result<Option<(String, Usuario)>, DomainError> {
+        let username = username_from_id(id);
+        let found = self.get_user_attributes(&username).await?;
+        Ok(found
+            .and_then(|attributes| usuario_from_attributes(&attributes))
+            .filter(|usuario| *usuario.id() == id)
+            .map(|usuario| (username, usuario)))
     }
 
-    async fn check_for_usuario_admin(&mut self) {
-        let admin_cpf = Cpf::new("000.000.000-00".to_string()).unwrap();
-        let usuario_admin = self.get_usuario_by_cpf(admin_cpf).await;
-        match usuario_admin {
-            Ok(usuario) => {
-                println!("Usuário Admin encontrado: {:?}", usuario);
-            }
-            _ => {
-                println!("Usuário Admin não encontrado. Criando...");
-                let _id = 0;
-                let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-                let cpf = Cpf::new("000.000.000-00".to_string()).unwrap();
-                let usuario_admin = Usuario::new(
-                    _id,
-                    "Administrador".to_string(),
-                    "admin@fastfood.com.br".to_string(),
-                    cpf,
-                    "melhor_projeto".to_string(),
-                    Tipo::Admin,
-                    Status::Ativo,
-                    _now.clone(),
-                    _now,
-                );
-                self.create_usuario(usuario_admin).await.unwrap();
-            }
+    // O CPF fica em preferred_username, que o ListUsers aceita como filtro;
+    // registros antigos só têm o CPF como username
+    async fn find_by_cpf(&self, cpf: &Cpf) -> Result<Option<(String, Usuario)>, DomainError> {
+        let response = self
+            .client
+            .list_users()
+            .user_pool_id(&self.user_pool_id)
+            .filter(format!("preferred_username = \"{}\"", cpf.formatted()))
+            .limit(1)
+            .send()
+            .await
+            .map_err(|err| {
+                println!("Error during aws cognito request: {}", err);
+                DomainError::Internal("Usuario".to_string())
+            })?;
+
+        let found = response.users().first().and_then(|user| {
+            usuario_from_attributes(user.attributes())
+                .filter(|usuario| usuario.cpf() == cpf)
+                .map(|usuario| (option_to_string(user.username()), usuario))
+        });
+        if found.is_some() {
+            return Ok(found);
         }
+        // Um registro antigo com CPF corrigido mantém o CPF original como
+        // username, então o CPF do registro precisa conferir
+        let username = cpf.formatted();
+        let found = self.get_user_attributes(&username).await?;
+        Ok(found
+            .and_then(|attributes| usuario_from_attributes(&attributes))
+            .filter(|usuario| usuario.cpf() == cpf)
+            .map(|usuario| (username, usuario)))
     }
-}
 
-#[async_trait]
-impl UsuarioGateway for AwsCognitoUsuarioRepository {
-    async fn get_usuarios(&self) -> Result<Vec<Usuario>, DomainError> {
+    // Registros sem o atributo `email` (anteriores a ele) só passam a ser
+    // encontrados por aqui depois da próxima atualização
+    async fn find_by_email(&self, email: &Email) -> Result<Option<Usuario>, DomainError> {
         let response = self
             .client
             .list_users()
             .user_pool_id(&self.user_pool_id)
+            .filter(format!("email = \"{}\"", email_attribute(email)))
             .send()
-            .await;
+            .await
+            .map_err(|err| {
+                println!("Error during aws cognito request: {}", err);
+                DomainError::Internal("Usuario".to_string())
+            })?;
+
+        Ok(response
+            .users()
+            .iter()
+            .filter_map(|user| usuario_from_attributes(user.attributes()))
+            .find(|usuario| usuario.email().eq_ignore_case(email)))
+    }
+
+    // O ListUsers devolve no máximo 60 usuários por chamada; segue o
+    // pagination_token até trazer o pool inteiro
+    async fn list_all_users(&self) -> Result<Vec<UserType>, SdkError<list_users::ListUsersError>> {
+        let mut users: Vec<UserType> = Vec::new();
+        let mut pagination_token: Option<String> = None;
+        loop {
+            let response = self
+                .client
+                .list_users()
+                .user_pool_id(&self.user_pool_id)
+                .set_pagination_token(pagination_token.take())
+                .send()
+                .await?;
+            users.extend(response.users().iter().cloned());
+            pagination_token = response.pagination_token().map(str::to_string);
+            if pagination_token.is_none() {
+                return Ok(users);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl UsuarioGateway for AwsCognitoUsuarioRepository {
+    async fn get_usuarios(&self) -> Result<Vec<Usuario>, DomainError> {
+        let response = self.list_all_users().await;
 
         let mut usuarios: Vec<Usuario> = Vec::new();
 
         match response {
-            Ok(response) => {
-                let users = response.users();
-
+            Ok(users) => {
                 for user in users {
-                    let mut id = String::new();
-                    let mut nome = String::new();
-                    let mut email = String::new();
-                    let mut cpf_string = String::new();
-                    let mut senha = String::new();
-                    let mut tipo_string = String::new();
-                    let mut status_string = String::new();
-                    let mut data_criacao = String::new();
-                    let mut data_atualizacao = String::new();
-
-                    for attr in user.attributes() {
-                        match attr.name() {
-                            "custom:id" => id =option_to_string(attr.value()),
-                            "custom:nome" => nome = option_to_string(attr.value()),
-                            "custom:email" => email = option_to_string(attr.value()),
-                            "custom:cpf" => cpf_string = option_to_string(attr.value()),
-                            "custom:senha" => senha = option_to_string(attr.value()),
-                            "custom:tipo" => tipo_string = option_to_string(attr.value()),
-                            "custom:status" => status_string = option_to_string(attr.value()),
-                            "custom:data_criacao" => data_criacao = option_to_string(attr.value()),
-                            "custom:data_atualizacao" => data_atualizacao = option_to_string(attr.value()),
-                            _ => {}
-                        }
-                    };
-
-                    let cpf = Cpf::new(cpf_string.to_string());
-
-                    let status = Status::from_str(status_string.as_str());
-
-                    if cpf.is_err() {
-                        println!("Error on cpf skiping");
-                        continue
-                    }
-
-                    let cpf = cpf.unwrap();
-
-                    let tipo = match Tipo::from_str(tipo_string.as_str()) {
-                        Ok(tipo) => tipo,
-                        Err(_) => continue, // Skip iteration if Tipo is invalid
-                    };
-
-                    let status = match Status::from_str(status_string.as_str()) {
-                        Ok(status) => status,
-                        Err(_) => continue, // Skip iteration if Status is invalid
-                    };
-
-                    match id.parse::<usize>() {
-                        Ok(id_value) => {
-                            let Usuario = Usuario::new(
-                                id_value,
-                                nome,
-                                email,
-                                cpf,
-                                senha,
-                                tipo,
-                                status,
-                                data_criacao,
-                                data_atualizacao,
-                            );
-        
-                            usuarios.push(Usuario);
-                        },
-                        Err(error) => {
-                            println!("Failed to convert string, ID: {}", id);
-                        }
+                    if let Some(usuario) = usuario_from_attributes(user.attributes()) {
+                        usuarios.push(usuario);
                     }
                 }
                 Ok(usuarios)
@@ -168,166 +259,180 @@ impl UsuarioGateway for AwsCognitoUsuarioRepository {
             Err(SdkError::ServiceError(err)) => {
                 println!("Service error: {:?}", err);
                 println!("Service error details: {:?}", err);
-                Err(DomainError::Invalid("Usuario".to_string()))
+                Err(DomainError::Internal("Usuario".to_string()))
             },
             Err(SdkError::TimeoutError(source)) => {
                 println!("Timeout error: {:?}", source);
-                Err(DomainError::Invalid("Usuario".to_string()))
+                Err(DomainError::Internal("Usuario".to_string()))
             },
             Err(SdkError::DispatchFailure (source)) => {
                 println!("Dispatch failure: {:?}", source);
-                Err(DomainError::Invalid("Usuario".to_string()))
+                Err(DomainError::Internal("Usuario".to_string()))
             },
             Err(SdkError::ResponseError (source)) => {
                 println!("Response error: {:?}", source);
-                Err(DomainError::Invalid("Usuario".to_string()))
+                Err(DomainError::Internal("Usuario".to_string()))
             },
             Err(err) => {
                 println!("Other SDK error: {:?}", err);
-                Err(DomainError::Invalid("Usuario".to_string()))
+                Err(DomainError::Internal("Usuario".to_string()))
             }
 
         }
     }
 
-    async fn get_usuario_by_cpf(&self, cpf: Cpf) -> Result<Usuario, DomainError> {
-        let usuario_result = self.get_usuarios().await;
-
-        let usuarios = match usuario_result {
-            Ok(usuarios) => usuarios,
-            Err(err) => {
-                println!("Error retrieving Usuarios");
-                return Err(err);
-            }
-        };
+    async fn list_usuarios(&self, filtro: UsuarioFiltro, page: PageRequest) -> Result<Page<Usuario>, DomainError> {
+        let usuarios = self.get_usuarios().await?;
+        Ok(Page::from_all(filtro.apply(usuarios), &page))
+    }
 
-        for usuario in usuarios {
-            if cpf.0 == usuario.cpf().0 {
-                return Ok(usuario);
-            }
+    async fn get_usuario_by_cpf(&self, cpf: Cpf) -> Result<Usuario, DomainError> {
+        match self.find_by_cpf(&cpf).await? {
+            Some((_, usuario)) => Ok(usuario),
+            None => Err(DomainError::NotFound),
         }
-
-        Err(DomainError::NotFound)
-
     }
 
     async fn get_usuario_by_id(&self, id: usize) -> Result<Usuario, DomainError> {
-        let usuarios_result = self.get_usuarios().await;
-
-        let usuarios = match usuarios_result {
-            Ok(usuarios) => usuarios,
-            Err(err) => {
-                println!("Error retrieving Usuarios");
-                return Err(err);
-            }
-        };
-
-        for usuario in usuarios {
-
-            if id == *usuario.id() {
-                return Ok(usuario);
-            }
+        match self.find_by_id(id).await? {
+            Some((_, usuario)) => Ok(usuario),
+            None => Err(DomainError::NotFound),
         }
+    }
 
-        Err(DomainError::NotFound)
+    async fn get_usuario_by_email(&self, email: Email) -> Result<Usuario, DomainError> {
+        self.find_by_email(&email).await?.ok_or(DomainError::NotFound)
     }
 
-    async fn create_usuario(&mut self, usuario: Usuario) -> Result<Usuario, DomainError> {
-        let cpf_string = &usuario.cpf().0;
-        // Initialize an empty vector to hold successfully built attributes
-        let mut attributes = Vec::new();
-    
-        let id = cpf_string.replace(".", "").replace("-", "");
-        let string_id: &str = &id;
+    async fn create_usuario(&self, usuario: Usuario) -> Result<Usuario, DomainError> {
+        // O username não carrega o CPF, então a unicidade é conferida antes
+        if self.find_by_cpf(usuario.cpf()).await?.is_some() {
+            return Err(DomainError::AlreadyExists("cpf".to_string()));
+        }
+        let cpf_string = &usuario.cpf().formatted();
         let tipo = &usuario.tipo().to_string();
         let status = &usuario.status().to_string();
-        // List of attribute specifications
-        let attribute_specs = vec![
-            ("custom:id", string_id),
-            ("custom:nome", usuario.nome()),
-            ("custom:email", usuario.email()),
-            ("custom:cpf", cpf_string),
-            ("custom:senha", usuario.senha()),
-            ("custom:tipo", tipo),
-            ("custom:status", status),
-            ("custom:data_criacao", usuario.data_criacao()),
-            ("custom:data_atualizacao", usuario.data_atualizacao()),
-        ];
-    
-        // Iterate over attribute specifications
-        for (name, value) in attribute_specs {
-            // Attempt to build an attribute
-            match AttributeType::builder()
-                .name(name)
-                .value(value)
-                .build()
-            {
-                Ok(attr) => {
-                    // Successfully built the attribute, add it to the vector
-                    attributes.push(attr);
+        let data_criacao = format_timestamp(usuario.data_criacao());
+        let data_atualizacao = format_timestamp(usuario.data_atualizacao());
+        let troca_senha = usuario.troca_senha_obrigatoria().to_string();
+        let email_busca = email_attribute(usuario.email());
+
+        // Um username repetido só acontece se outra instância gerou o mesmo id;
+        // nesse caso gera outro
+        for _ in 0..3 {
+            let id = next_id();
+            let string_id = id.to_string();
+            let username = username_from_id(id);
+            // List of attribute specifications
+            let attribute_specs = vec![
+                ("custom:id", string_id.as_str()),
+                ("custom:nome", usuario.nome()),
+                ("custom:email", &usuario.email().0),
+                ("email", &email_busca),
+                ("email_verified", "true"),
+                ("custom:cpf", cpf_string),
+                ("preferred_username", cpf_string),
+                ("custom:senha", usuario.senha()),
+                ("custom:tipo", tipo),
+                ("custom:status", status),
+                ("custom:data_criacao", &data_criacao),
+                ("custom:data_atualizacao", &data_atualizacao),
+                ("custom:troca_senha", &troca_senha),
+            ];
+
+            // Initialize an empty vector to hold successfully built attributes
+            let mut attributes = Vec::new();
+
+            // Iterate over attribute specifications
+            for (name, value) in attribute_specs {
+                // Attempt to build an attribute
+                match AttributeType::builder()
+                    .name(name)
+                    .value(value)
+                    .build()
+                {
+                    Ok(attr) => {
+                        // Successfully built the attribute, add it to the vector
+                        attributes.push(attr);
+                    },
+                    Err(err) => {
+                        println!("Failed to build attribute {}: {}", name, err);
+                    }
+                }
+            }
+
+            let response = self.client
+                .admin_create_user()
+                .user_pool_id(&self.user_pool_id)
+                .username(&username)
+                .temporary_password(cpf_string)
+                .set_user_attributes(Some(attributes))
+                .send()
+                .await;
+
+            match response {
+                Ok(_) => {
+                    println!("Successfully created user: {}", id);
+                    let mut criado = Usuario::new(
+                        id,
+                        usuario.nome().clone(),
+                        usuario.email().clone(),
+                        usuario.cpf().clone(),
+                        usuario.senha().clone(),
+                        usuario.tipo().clone(),
+                        usuario.status().clone(),
+                        *usuario.data_criacao(),
+                        *usuario.data_atualizacao(),
+                    );
+                    criado.set_troca_senha_obrigatoria(usuario.troca_senha_obrigatoria());
+                    return Ok(criado);
+                },
+                Err(SdkError::ServiceError(err)) if err.err().is_username_exists_exception() => {
+                    println!("Username {} already taken, generating another id", username);
                 },
                 Err(err) => {
-                    println!("Failed to build attribute {}: {}", name, err);
+                    println!("SDK ERROR: {}", err);
+                    println!("Failed to create user: {}", username);
+                    return Err(DomainError::Internal("Usuario".to_string()));
                 }
             }
         }
-    
-        let response = self.client
-            .admin_create_user()
-            .user_pool_id(&self.user_pool_id)
-            .username(cpf_string)
-            .temporary_password(cpf_string)
-            .set_user_attributes(Some(attributes))
-            .send()
-            .await;
-    
-        match response {
-            Ok(resp) => {
-                println!("Successfully created user: {}", usuario.id());
-                Ok(usuario)
-            },
-            Err(SdkError::ServiceError(err)) => {
-                println!("Service error: {:?}", err);
-                println!("Service error details: {:?}", err);
-                Err(DomainError::Invalid("Usuario".to_string()))
-            },
-            Err(SdkError::TimeoutError(source)) => {
-                println!("Timeout error: {:?}", source);
-                Err(DomainError::Invalid("Usuario".to_string()))
-            },
-            Err(SdkError::DispatchFailure (source)) => {
-                println!("Dispatch failure: {:?}", source);
-                Err(DomainError::Invalid("Usuario".to_string()))
-            },
-            Err(SdkError::ResponseError (source)) => {
-                println!("Response error: {:?}", source);
-                Err(DomainError::Invalid("Usuario".to_string()))
-            },
-            Err(err) => {
-                println!("Other SDK error: {:?}", err);
-                Err(DomainError::Invalid("Usuario".to_string()))
+        Err(DomainError::Internal("Usuario".to_string()))
+    }
+
+    async fn update_usuario(&self, dados_usuario_atualizado: Usuario) -> Result<Usuario, DomainError> {
+        let username = match self.find_by_id(*dados_usuario_atualizado.id()).await? {
+            Some((username, _)) => username,
+            None => return Err(DomainError::NotFound),
+        };
+        if let Some((_, existente)) = self.find_by_cpf(dados_usuario_atualizado.cpf()).await? {
+            if existente.id() != dados_usuario_atualizado.id() {
+                return Err(DomainError::AlreadyExists("cpf".to_string()));
             }
         }
-    }
 
-    async fn update_usuario(&mut self, dados_usuario_atualizado: Usuario) -> Result<Usuario, DomainError> {
-        let cpf_string = dados_usuario_atualizado.cpf().0.clone();
-        let id = cpf_string.replace(".", "").replace("-", "");
-        let string_id: &str = &id;
+        let cpf_string = dados_usuario_atualizado.cpf().formatted();
         let tipo = dados_usuario_atualizado.tipo().to_string().clone();
         let status = dados_usuario_atualizado.status().to_string().clone();
+        let data_criacao = format_timestamp(dados_usuario_atualizado.data_criacao());
+        let data_atualizacao = format_timestamp(dados_usuario_atualizado.data_atualizacao());
+        let troca_senha = dados_usuario_atualizado.troca_senha_obrigatoria().to_string();
+        let email_busca = email_attribute(dados_usuario_atualizado.email());
 
         // List of attribute specifications
-        let attribute_specs = vec![
-            ("custom:id", string_id),
+        let attribute_specs: Vec<(&str, &str)> = vec![
             ("custom:nome", dados_usuario_atualizado.nome()),
-            ("custom:email", dados_usuario_atualizado.email()),
+            ("custom:email", &dados_usuario_atualizado.email().0),
+            ("email", email_busca.as_str()),
+            ("email_verified", "true"),
             ("custom:cpf", cpf_string.as_str()),
+            ("preferred_username", cpf_string.as_str()),
             ("custom:senha", dados_usuario_atualizado.senha()),
             ("custom:tipo", tipo.as_str()),
             ("custom:status", status.as_str()),
-            ("custom:data_criacao", dados_usuario_atualizado.data_criacao()),
-            ("custom:data_atualizacao", dados_usuario_atualizado.data_atualizacao()),
+            ("custom:data_criacao", data_criacao.as_str()),
+            ("custom:data_atualizacao", data_atualizacao.as_str()),
+            ("custom:troca_senha", troca_senha.as_str()),
         ];
 
         // Initialize an empty vector to hold successfully built attributes
@@ -355,7 +460,7 @@ impl UsuarioGateway for AwsCognitoUsuarioRepository {
         let response = self.client
             .admin_update_user_attributes()
             .user_pool_id(&self.user_pool_id)
-            .username(cpf_string.as_str())
+            .username(username.as_str())
             .set_user_attributes(Some(attributes))
             .send()
             .await;
@@ -367,18 +472,21 @@ impl UsuarioGateway for AwsCognitoUsuarioRepository {
             },
             Err(err) => {
                 println!("SDK ERROR: {}", err.to_string());
-                println!("Failed to update user: {}", cpf_string);
-                Err(DomainError::Invalid("Usuario".to_string()))
+                println!("Failed to update user: {}", username);
+                Err(DomainError::Internal("Usuario".to_string()))
             }
         }
     }
 
-    async fn delete_usuario(&mut self, cpf: Cpf) -> Result<(), DomainError> {
-        let cpf_string = cpf.0;
+    async fn delete_usuario(&self, cpf: Cpf) -> Result<(), DomainError> {
+        let username = match self.find_by_cpf(&cpf).await? {
+            Some((username, _)) => username,
+            None => return Err(DomainError::NotFound),
+        };
         let response = self.client
             .admin_delete_user()
             .user_pool_id(&self.user_pool_id)
-            .username(cpf_string.clone())
+            .username(username.clone())
             .send()
             .await;
 
@@ -387,10 +495,205 @@ impl UsuarioGateway for AwsCognitoUsuarioRepository {
                 Ok(())
             },
             Err(err) => {
-                println!("Failed to delete user: {}", cpf_string);
+                println!("Failed to delete user: {}", username);
                 Err(DomainError::NotFound)
             }
         }
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateways::cognito_stand_in::{cpf_valido, CognitoStandIn, USER_POOL_ID};
+    use chrono::Utc;
+    use tokio;
+
+    fn add_usuario(stand_in: &CognitoStandIn, cpf: &str) {
+        let id = Cpf::new(cpf.to_string()).unwrap().digits().to_string();
+        stand_in.add_user(
+            cpf,
+            vec![
+                ("custom:id", &id),
+                ("custom:nome", "Fulano da Silva"),
+                ("custom:email", "fulano@exemplo.com"),
+                ("custom:cpf", cpf),
+                ("custom:senha", "hash"),
+                ("custom:tipo", "Cozinha"),
+                ("custom:status", "Ativo"),
+                ("custom:data_criacao", "2024-01-01 10:00:00.000+0000"),
+                ("custom:data_atualizacao", "2024-01-01 10:00:00.000+0000"),
+            ],
+        );
+    }
+
+    async fn repository() -> (CognitoStandIn, AwsCognitoUsuarioRepository) {
+        let stand_in = CognitoStandIn::start().await;
+        for base in 1..=5 {
+            add_usuario(&stand_in, &cpf_valido(base * 1000));
+        }
+        let repository = AwsCognitoUsuarioRepository::from_client(stand_in.client(), USER_POOL_ID.to_string());
+        (stand_in, repository)
+    }
+
+    #[tokio::test]
+    async fn test_get_legacy_usuario_by_cpf() {
+        let (stand_in, repository) = repository().await;
+        let cpf = Cpf::new(cpf_valido(3000)).unwrap();
+
+        let usuario = repository.get_usuario_by_cpf(cpf.clone()).await.unwrap();
+        assert_eq!(usuario.cpf(), &cpf);
+        assert_eq!(format_timestamp(usuario.data_criacao()), "2024-01-01T10:00:00.000Z");
+        assert_eq!(stand_in.take_operations(), vec!["ListUsers", "AdminGetUser"]);
+
+        let result = repository.get_usuario_by_cpf(Cpf::new(cpf_valido(42)).unwrap()).await;
+        assert!(
+            matches!(result, Err(DomainError::NotFound)),
+            "Esperado Err(DomainError::NotFound), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_legacy_usuario_with_corrected_cpf_is_not_found_by_old_cpf() {
+        let (stand_in, repository) = repository().await;
+        let cpf = cpf_valido(123456789);
+        let novo_cpf = cpf_valido(987654321);
+        let id = Cpf::new(cpf.clone()).unwrap().digits().to_string();
+        stand_in.add_user(
+            &cpf,
+            vec![
+                ("custom:id", &id),
+                ("custom:nome", "Fulano da Silva"),
+                ("custom:email", "fulano@exemplo.com"),
+                ("custom:cpf", &novo_cpf),
+                ("preferred_username", &novo_cpf),
+                ("custom:senha", "hash"),
+                ("custom:tipo", "Cozinha"),
+                ("custom:status", "Ativo"),
+                ("custom:data_criacao", "2024-01-01 10:00:00.000+0000"),
+                ("custom:data_atualizacao", "2024-01-01 10:00:00.000+0000"),
+            ],
+        );
+
+        let result = repository.get_usuario_by_cpf(Cpf::new(cpf.clone()).unwrap()).await;
+        assert!(
+            matches!(result, Err(DomainError::NotFound)),
+            "Esperado Err(DomainError::NotFound), obtido {:?}",
+            result
+        );
+        let result = repository.delete_usuario(Cpf::new(cpf).unwrap()).await;
+        assert!(matches!(result, Err(DomainError::NotFound)));
+        let by_cpf = repository.get_usuario_by_cpf(Cpf::new(novo_cpf).unwrap()).await.unwrap();
+        assert_eq!(by_cpf.id().to_string(), id);
+    }
+
+    #[tokio::test]
+    async fn test_get_usuario_by_id_uses_single_lookup() {
+        let (stand_in, repository) = repository().await;
+        let cpf = cpf_valido(4000);
+        let id = Cpf::new(cpf.clone()).unwrap().digits().parse::<usize>().unwrap();
+
+        let usuario = repository.get_usuario_by_id(id).await.unwrap();
+        assert_eq!(usuario.cpf().formatted(), cpf);
+        assert_eq!(stand_in.take_operations(), vec!["AdminGetUser"]);
+
+        let result = repository.get_usuario_by_id(id + 1).await;
+        assert!(matches!(result, Err(DomainError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_get_usuario_by_email_uses_filter() {
+        let (stand_in, repository) = repository().await;
+        let _now = Utc::now();
+        let usuario = Usuario::new(
+            0,
+            "Ciclano".to_string(),
+            Email::new("Ciclano@Exemplo.com".to_string()).unwrap(),
+            Cpf::new(cpf_valido(777888999)).unwrap(),
+            "hash".to_string(),
+            Tipo::Cozinha,
+            Status::Ativo,
+            _now,
+            _now,
+        );
+        let criado = repository.create_usuario(usuario).await.unwrap();
+        stand_in.take_operations();
+
+        let by_email = repository
+            .get_usuario_by_email(Email::new("ciclano@exemplo.com".to_string()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(by_email.id(), criado.id());
+        assert_eq!(by_email.email().0, "Ciclano@exemplo.com");
+        assert_eq!(stand_in.take_operations(), vec!["ListUsers"]);
+
+        let result = repository.get_usuario_by_email(Email::new("outro@exemplo.com".to_string()).unwrap()).await;
+        assert!(matches!(result, Err(DomainError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_get_usuarios_follows_pagination_token() {
+        let (stand_in, repository) = repository().await;
+        for base in 100..230 {
+            add_usuario(&stand_in, &cpf_valido(base));
+        }
+
+        let usuarios = repository.get_usuarios().await.unwrap();
+        assert_eq!(usuarios.len(), 135);
+        assert_eq!(stand_in.take_operations(), vec!["ListUsers", "ListUsers", "ListUsers"]);
+    }
+
+    #[tokio::test]
+    async fn test_created_usuario_keeps_id_when_cpf_changes() {
+        let (stand_in, repository) = repository().await;
+        let _now = Utc::now();
+        let usuario = Usuario::new(
+            0,
+            "Ciclano".to_string(),
+            Email::new("ciclano@exemplo.com".to_string()).unwrap(),
+            Cpf::new(cpf_valido(777888999)).unwrap(),
+            "hash".to_string(),
+            Tipo::Cozinha,
+            Status::Ativo,
+            _now,
+            _now,
+        );
+        let criado = repository.create_usuario(usuario.clone()).await.unwrap();
+        assert_ne!(criado.id(), &0);
+        assert!(stand_in.usernames().contains(&format!("usuario-{}", criado.id())));
+
+        let result = repository.create_usuario(usuario).await;
+        assert!(
+            matches!(result, Err(DomainError::AlreadyExists(_))),
+            "Esperado Err(DomainError::AlreadyExists), obtido {:?}",
+            result
+        );
+
+        let novo_cpf = Cpf::new(cpf_valido(777888990)).unwrap();
+        let mut corrigido = criado.clone();
+        corrigido.set_cpf(novo_cpf.clone());
+        repository.update_usuario(corrigido).await.unwrap();
+        stand_in.take_operations();
+
+        let by_id = repository.get_usuario_by_id(*criado.id()).await.unwrap();
+        assert_eq!(by_id.cpf(), &novo_cpf);
+        assert_eq!(stand_in.take_operations(), vec!["AdminGetUser"]);
+        let by_cpf = repository.get_usuario_by_cpf(novo_cpf.clone()).await.unwrap();
+        assert_eq!(by_cpf.id(), criado.id());
+        assert_eq!(stand_in.take_operations(), vec!["ListUsers"]);
+
+        let mut conflito = criado.clone();
+        conflito.set_cpf(Cpf::new(cpf_valido(3000)).unwrap());
+        let result = repository.update_usuario(conflito).await;
+        assert!(
+            matches!(result, Err(DomainError::AlreadyExists(_))),
+            "Esperado Err(DomainError::AlreadyExists), obtido {:?}",
+            result
+        );
+
+        repository.delete_usuario(novo_cpf).await.unwrap();
+        assert!(!stand_in.usernames().contains(&format!("usuario-{}", criado.id())));
+    }
+}
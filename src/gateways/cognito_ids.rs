@@ -0,0 +1,65 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use chrono::Utc;
+
+use crate::entities::cpf::Cpf;
+use crate::entities::email::Email;
+
+// Registros antigos do pool usam o CPF sem pontuação como id e o CPF formatado
+// como username; os ids gerados abaixo ficam sempre acima desse limite
+pub const CPF_ID_LIMIT: usize = 100_000_000_000;
+
+static LAST_ID: AtomicUsize = AtomicUsize::new(0);
+
+// O Cognito não tem sequência, então o id é o instante da criação em
+// microssegundos, forçado a crescer dentro do processo. Fica abaixo de 2^53 e
+// não perde precisão em clientes JavaScript. O username é derivado do id, então
+// o próprio pool recusa um id repetido entre instâncias.
+pub fn next_id() -> usize {
+    let now = Utc::now().timestamp_micros() as usize;
+    let mut last = LAST_ID.load(Ordering::SeqCst);
+    loop {
+        let id = now.max(last + 1);
+        match LAST_ID.compare_exchange(last, id, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => return id,
+            Err(current) => last = current,
+        }
+    }
+}
+
+// Username dos registros antigos, que têm o id derivado do CPF
+pub fn legacy_username_from_id(id: usize) -> Option<String> {
+    if id >= CPF_ID_LIMIT {
+        return None;
+    }
+    let digits = format!("{:011}", id);
+    Cpf::new(digits).ok().map(|cpf| cpf.formatted())
+}
+
+// Valor do atributo padrão `email`, usado só para busca: o filtro do
+// ListUsers compara o texto exato, então o e-mail vai todo em minúsculas. A
+// forma original continua em `custom:email`
+pub fn email_attribute(email: &Email) -> String {
+    email.0.to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_generate_increasing_ids_above_cpf_range() {
+        let first = next_id();
+        let second = next_id();
+        assert!(first >= CPF_ID_LIMIT);
+        assert!(second > first);
+        assert!(second < 1 << 53);
+    }
+
+    #[test]
+    fn should_map_legacy_ids_to_cpf_username() {
+        assert_eq!(legacy_username_from_id(12345678909), Some("123.456.789-09".to_string()));
+        assert_eq!(legacy_username_from_id(12345678900), None);
+        assert_eq!(legacy_username_from_id(next_id()), None);
+    }
+}
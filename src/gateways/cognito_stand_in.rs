@@ -0,0 +1,260 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use aws_sdk_cognitoidentityprovider::config::{BehaviorVersion, Credentials, Region};
+use aws_sdk_cognitoidentityprovider::{Client, Config};
+use regex::Regex;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+pub const USER_POOL_ID: &str = "local_pool";
+
+const PAGE_SIZE: usize = 60;
+
+type Users = BTreeMap<String, Vec<(String, String)>>;
+
+#[derive(Default)]
+struct State {
+    users: Users,
+    operations: Vec<String>,
+}
+
+// Servidor HTTP local que responde ao protocolo JSON do Cognito para as
+// operações usadas pelos gateways, guardando os usuários em memória. Também
+// registra as operações recebidas para que os testes confiram quantas chamadas
+// remotas cada busca fez.
+pub struct CognitoStandIn {
+    endpoint: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl CognitoStandIn {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, server_state.clone()));
+            }
+        });
+
+        CognitoStandIn { endpoint, state }
+    }
+
+    pub fn client(&self) -> Client {
+        let config = Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .credentials_provider(Credentials::new("test", "test", None, None, "stand-in"))
+            .endpoint_url(&self.endpoint)
+            .build();
+        Client::from_conf(config)
+    }
+
+    pub fn add_user(&self, username: &str, attributes: Vec<(&str, &str)>) {
+        let attributes = attributes
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        self.state.lock().unwrap().users.insert(username.to_string(), attributes);
+    }
+
+    pub fn usernames(&self) -> Vec<String> {
+        self.state.lock().unwrap().users.keys().cloned().collect()
+    }
+
+    // Devolve e zera as operações recebidas até aqui
+    pub fn take_operations(&self) -> Vec<String> {
+        std::mem::take(&mut self.state.lock().unwrap().operations)
+    }
+}
+
+// CPF formatado e com dígitos verificadores válidos a partir de uma base de 9 dígitos
+pub fn cpf_valido(base: u32) -> String {
+    let mut digits: Vec<u32> = format!("{:09}", base).chars().map(|d| d.to_digit(10).unwrap()).collect();
+    for size in [9, 10] {
+        let sum: u32 = (0..size).map(|i| digits[i] * (size as u32 + 1 - i as u32)).sum();
+        let dv = sum % 11;
+        digits.push(if dv < 2 { 0 } else { 11 - dv });
+    }
+    let digits: String = digits.iter().map(|d| d.to_string()).collect();
+    format!("{}.{}.{}-{}", &digits[0..3], &digits[3..6], &digits[6..9], &digits[9..])
+}
+
+async fn serve(stream: TcpStream, state: Arc<Mutex<State>>) {
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut operation = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            match reader.read_line(&mut line).await {
+                Ok(0) | Err(_) => return,
+                Ok(_) => {}
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                match name.trim().to_lowercase().as_str() {
+                    "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                    "x-amz-target" => {
+                        operation = value.trim().rsplit('.').next().unwrap_or_default().to_string()
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        if reader.read_exact(&mut body).await.is_err() {
+            return;
+        }
+        let input: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+        let (status, output) = {
+            let mut state = state.lock().unwrap();
+            state.operations.push(operation.clone());
+            handle(&operation, &input, &mut state.users)
+        };
+
+        let output = output.to_string();
+        let mut response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/x-amz-json-1.1\r\nContent-Length: {}\r\n",
+            status,
+            output.len()
+        );
+        if let Some(error_type) = status.strip_prefix("400 ").and(output_error_type(&output)) {
+            response.push_str(&format!("x-amzn-ErrorType: {}\r\n", error_type));
+        }
+        response.push_str("\r\n");
+        response.push_str(&output);
+        if reader.get_mut().write_all(response.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+}
+
+fn output_error_type(output: &str) -> Option<String> {
+    let output: Value = serde_json::from_str(output).ok()?;
+    output["__type"].as_str().map(str::to_string)
+}
+
+fn error(error_type: &str, message: &str) -> (&'static str, Value) {
+    ("400 Bad Request", json!({ "__type": error_type, "message": message }))
+}
+
+fn attributes_to_json(attributes: &[(String, String)]) -> Value {
+    Value::Array(
+        attributes
+            .iter()
+            .map(|(name, value)| json!({ "Name": name, "Value": value }))
+            .collect(),
+    )
+}
+
+fn attributes_from_json(input: &Value) -> Vec<(String, String)> {
+    input
+        .as_array()
+        .map(|attributes| {
+            attributes
+                .iter()
+                .map(|attribute| {
+                    (
+                        attribute["Name"].as_str().unwrap_or_default().to_string(),
+                        attribute["Value"].as_str().unwrap_or_default().to_string(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Aceita as formas `atributo = "valor"` e `atributo ^= "valor"` do ListUsers
+fn matches_filter(filter: &str, username: &str, attributes: &[(String, String)]) -> bool {
+    let regex = Regex::new(r#"^\s*([\w:]+)\s*(\^?=)\s*"(.*)"\s*$"#).unwrap();
+    let captures = match regex.captures(filter) {
+        Some(captures) => captures,
+        None => return false,
+    };
+    let value = if &captures[1] == "username" {
+        Some(username)
+    } else {
+        attributes
+            .iter()
+            .find(|(name, _)| name == &captures[1])
+            .map(|(_, value)| value.as_str())
+    };
+    match value {
+        Some(value) if &captures[2] == "=" => value == &captures[3],
+        Some(value) => value.starts_with(&captures[3]),
+        None => false,
+    }
+}
+
+fn handle(operation: &str, input: &Value, users: &mut Users) -> (&'static str, Value) {
+    let username = input["Username"].as_str().unwrap_or_default().to_string();
+    match operation {
+        "ListUsers" => {
+            let filter = input["Filter"].as_str().unwrap_or_default();
+            let limit = input["Limit"].as_u64().map_or(PAGE_SIZE, |limit| limit as usize);
+            let start = input["PaginationToken"]
+                .as_str()
+                .and_then(|token| token.parse::<usize>().ok())
+                .unwrap_or(0);
+            let matching: Vec<Value> = users
+                .iter()
+                .filter(|(username, attributes)| filter.is_empty() || matches_filter(filter, username, attributes))
+                .map(|(username, attributes)| {
+                    json!({ "Username": username, "Attributes": attributes_to_json(attributes), "Enabled": true })
+                })
+                .collect();
+            let page: Vec<Value> = matching.iter().skip(start).take(limit).cloned().collect();
+            let mut output = json!({ "Users": page });
+            if start + limit < matching.len() {
+                output["PaginationToken"] = json!((start + limit).to_string());
+            }
+            ("200 OK", output)
+        }
+        "AdminGetUser" => match users.get(&username) {
+            Some(attributes) => (
+                "200 OK",
+                json!({ "Username": username, "UserAttributes": attributes_to_json(attributes), "Enabled": true }),
+            ),
+            None => error("UserNotFoundException", "User does not exist."),
+        },
+        "AdminCreateUser" => {
+            if users.contains_key(&username) {
+                return error("UsernameExistsException", "User account already exists");
+            }
+            let attributes = attributes_from_json(&input["UserAttributes"]);
+            let output = json!({
+                "User": { "Username": username, "Attributes": attributes_to_json(&attributes), "Enabled": true }
+            });
+            users.insert(username, attributes);
+            ("200 OK", output)
+        }
+        "AdminUpdateUserAttributes" => match users.get_mut(&username) {
+            Some(attributes) => {
+                for (name, value) in attributes_from_json(&input["UserAttributes"]) {
+                    match attributes.iter_mut().find(|(existing, _)| *existing == name) {
+                        Some(attribute) => attribute.1 = value,
+                        None => attributes.push((name, value)),
+                    }
+                }
+                ("200 OK", json!({}))
+            }
+            None => error("UserNotFoundException", "User does not exist."),
+        },
+        "AdminDeleteUser" => match users.remove(&username) {
+            Some(_) => ("200 OK", json!({})),
+            None => error("UserNotFoundException", "User does not exist."),
+        },
+        _ => error("InvalidParameterException", "Unsupported operation"),
+    }
+}
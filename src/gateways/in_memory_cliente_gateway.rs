@@ -0,0 +1,302 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    base::domain_error::DomainError,
+    base::pagination::{Page, PageRequest},
+    entities::cliente::Cliente,
+    entities::cpf::Cpf,
+    entities::email::Email,
+    traits::cliente_gateway::{ClienteFiltro, ClienteGateway},
+};
+
+pub struct InMemoryClienteRepository {
+    clientes: RwLock<Vec<Cliente>>,
+    next_id: AtomicUsize,
+}
+
+impl InMemoryClienteRepository {
+    pub fn new() -> Self {
+        InMemoryClienteRepository {
+            clientes: RwLock::new(Vec::new()),
+            next_id: AtomicUsize::new(1),
+        }
+    }
+}
+
+impl Default for InMemoryClienteRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ClienteGateway for InMemoryClienteRepository {
+    async fn get_clientes(&self) -> Result<Vec<Cliente>, DomainError> {
+        Ok(self.clientes.read().unwrap().clone())
+    }
+
+    async fn list_clientes(&self, filtro: ClienteFiltro, page: PageRequest) -> Result<Page<Cliente>, DomainError> {
+        Ok(Page::from_all(filtro.apply(self.clientes.read().unwrap().clone()), &page))
+    }
+
+    async fn get_cliente_by_cpf(&self, cpf: Cpf) -> Result<Cliente, DomainError> {
+        self.clientes
+            .read()
+            .unwrap()
+            .iter()
+            .find(|cliente| cliente.cpf().as_ref() == Some(&cpf))
+            .cloned()
+            .ok_or(DomainError::NotFound)
+    }
+
+    async fn get_cliente_by_email(&self, email: Email) -> Result<Cliente, DomainError> {
+        self.clientes
+            .read()
+            .unwrap()
+            .iter()
+            .find(|cliente| cliente.email().as_ref().is_some_and(|cliente_email| cliente_email.eq_ignore_case(&email)))
+            .cloned()
+            .ok_or(DomainError::NotFound)
+    }
+
+    async fn get_cliente_by_id(&self, id: usize) -> Result<Cliente, DomainError> {
+        self.clientes
+            .read()
+            .unwrap()
+            .iter()
+            .find(|cliente| *cliente.id() == id)
+            .cloned()
+            .ok_or(DomainError::NotFound)
+    }
+
+    async fn create_cliente(&self, cliente: Cliente) -> Result<Cliente, DomainError> {
+        let mut clientes = self.clientes.write().unwrap();
+        if cliente.cpf().is_some() && clientes.iter().any(|c| c.cpf() == cliente.cpf()) {
+            return Err(DomainError::AlreadyExists("cpf".to_string()));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let cliente = match (cliente.cpf(), cliente.email()) {
+            (Some(cpf), Some(email)) => Cliente::new(
+                id,
+                cliente.nome().clone(),
+                email.clone(),
+                cpf.clone(),
+                *cliente.data_criacao(),
+                *cliente.data_atualizacao(),
+            ),
+            _ => Cliente::anonimo(
+                id,
+                *cliente.data_criacao(),
+                *cliente.data_atualizacao(),
+            ),
+        };
+        clientes.push(cliente.clone());
+        Ok(cliente)
+    }
+
+    async fn update_cliente(&self, dados_cliente_atualizado: Cliente) -> Result<Cliente, DomainError> {
+        let mut clientes = self.clientes.write().unwrap();
+        if dados_cliente_atualizado.cpf().is_some()
+            && clientes.iter().any(|c| {
+                c.cpf() == dados_cliente_atualizado.cpf() && c.id() != dados_cliente_atualizado.id()
+            })
+        {
+            return Err(DomainError::AlreadyExists("cpf".to_string()));
+        }
+        match clientes
+            .iter_mut()
+            .find(|cliente| cliente.id() == dados_cliente_atualizado.id())
+        {
+            Some(cliente) => {
+                *cliente = dados_cliente_atualizado.clone();
+                Ok(dados_cliente_atualizado)
+            }
+            None => Err(DomainError::NotFound),
+        }
+    }
+
+    async fn delete_cliente(&self, cpf: Cpf) -> Result<(), DomainError> {
+        let mut clientes = self.clientes.write().unwrap();
+        match clientes.iter().position(|cliente| cliente.cpf().as_ref() == Some(&cpf)) {
+            Some(index) => {
+                clientes.remove(index);
+                Ok(())
+            }
+            None => Err(DomainError::NotFound),
+        }
+    }
+
+    async fn delete_clientes_anonimos(&self, criado_ate: DateTime<Utc>) -> Result<usize, DomainError> {
+        let mut clientes = self.clientes.write().unwrap();
+        let total = clientes.len();
+        clientes.retain(|cliente| !cliente.is_anonimo() || *cliente.data_criacao() >= criado_ate);
+        Ok(total - clientes.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::pagination::{CampoOrdenacao, Ordem};
+    use chrono::Duration;
+    use tokio;
+
+    fn create_cliente(cpf: &str) -> Cliente {
+        let _now = Utc::now();
+        Cliente::new(
+            0,
+            "Fulano da Silva".to_string(),
+            Email::new("fulano.silva@exemplo.com".to_string()).unwrap(),
+            Cpf::new(cpf.to_string()).unwrap(),
+            _now,
+            _now,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_cliente() {
+        let repository = InMemoryClienteRepository::new();
+        let cliente = repository.create_cliente(create_cliente("123.456.789-09")).await.unwrap();
+        assert_eq!(cliente.id(), &1);
+
+        let by_cpf = repository
+            .get_cliente_by_cpf(Cpf::new("123.456.789-09".to_string()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(by_cpf.id(), &1);
+
+        let by_id = repository.get_cliente_by_id(1).await.unwrap();
+        assert_eq!(by_id.cpf(), cliente.cpf());
+    }
+
+    #[tokio::test]
+    async fn test_get_cliente_by_email_ignores_case() {
+        let repository = InMemoryClienteRepository::new();
+        let _ = repository.create_cliente(create_cliente("123.456.789-09")).await;
+        let cliente = repository
+            .get_cliente_by_email(Email::new("Fulano.Silva@EXEMPLO.com".to_string()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(cliente.id(), &1);
+        let result = repository.get_cliente_by_email(Email::new("outro@exemplo.com".to_string()).unwrap()).await;
+        assert!(matches!(result, Err(DomainError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_create_cliente_duplicate_cpf() {
+        let repository = InMemoryClienteRepository::new();
+        let _ = repository.create_cliente(create_cliente("123.456.789-09")).await;
+        let result = repository.create_cliente(create_cliente("123.456.789-09")).await;
+        assert!(
+            matches!(result, Err(DomainError::AlreadyExists(_))),
+            "Esperado Err(DomainError::AlreadyExists), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_promote_anonimo_keeps_id() {
+        let repository = InMemoryClienteRepository::new();
+        let _now = Utc::now();
+        let _ = repository.create_cliente(create_cliente("123.456.789-09")).await;
+        let anonimo = repository
+            .create_cliente(Cliente::anonimo(0, _now, _now))
+            .await
+            .unwrap();
+        let outro_anonimo = repository
+            .create_cliente(Cliente::anonimo(0, _now, _now))
+            .await
+            .unwrap();
+        assert_eq!(anonimo.id(), &2);
+        assert_eq!(outro_anonimo.id(), &3);
+
+        let mut promovido = anonimo.clone();
+        promovido
+            .identifica("Ciclano".to_string(), Email::new("ciclano@exemplo.com".to_string()).unwrap(), Cpf::new("123.456.789-09".to_string()).unwrap())
+            .unwrap();
+        let result = repository.update_cliente(promovido).await;
+        assert!(
+            matches!(result, Err(DomainError::AlreadyExists(_))),
+            "Esperado Err(DomainError::AlreadyExists), obtido {:?}",
+            result
+        );
+
+        let mut promovido = anonimo.clone();
+        let cpf = Cpf::new("529.982.247-25".to_string()).unwrap();
+        promovido
+            .identifica("Ciclano".to_string(), Email::new("ciclano@exemplo.com".to_string()).unwrap(), cpf.clone())
+            .unwrap();
+        repository.update_cliente(promovido).await.unwrap();
+        let by_cpf = repository.get_cliente_by_cpf(cpf).await.unwrap();
+        assert_eq!(by_cpf.id(), &2);
+    }
+
+    #[tokio::test]
+    async fn test_list_clientes_paginates_filters_and_sorts() {
+        let repository = InMemoryClienteRepository::new();
+        for (nome, cpf) in [
+            ("Beatriz", "123.456.789-09"),
+            ("ana", "529.982.247-25"),
+            ("Bruno", "111.444.777-35"),
+        ] {
+            let mut cliente = create_cliente(cpf);
+            cliente.set_nome(nome.to_string()).unwrap();
+            repository.create_cliente(cliente).await.unwrap();
+        }
+
+        let filtro = ClienteFiltro {
+            ordenar_por: CampoOrdenacao::Nome,
+            ..Default::default()
+        };
+        let page = PageRequest::new(Some(2), None).unwrap();
+        let first = repository.list_clientes(filtro.clone(), page).await.unwrap();
+        let nomes: Vec<&String> = first.items.iter().map(|cliente| cliente.nome()).collect();
+        assert_eq!(nomes, vec!["ana", "Beatriz"]);
+
+        let page = PageRequest::new(Some(2), first.next_cursor).unwrap();
+        let second = repository.list_clientes(filtro, page).await.unwrap();
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].nome(), "Bruno");
+        assert!(second.next_cursor.is_none());
+
+        let filtro = ClienteFiltro {
+            nome_prefixo: Some("b".to_string()),
+            ordem: Ordem::Desc,
+            ..Default::default()
+        };
+        let result = repository.list_clientes(filtro, PageRequest::default()).await.unwrap();
+        let ids: Vec<usize> = result.items.iter().map(|cliente| *cliente.id()).collect();
+        assert_eq!(ids, vec![3, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_cliente() {
+        let repository = InMemoryClienteRepository::new();
+        let _ = repository.create_cliente(create_cliente("123.456.789-09")).await;
+        let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
+        assert!(repository.delete_cliente(cpf.clone()).await.is_ok());
+
+        let result = repository.get_cliente_by_cpf(cpf.clone()).await;
+        assert!(matches!(result, Err(DomainError::NotFound)));
+        let result = repository.delete_cliente(cpf).await;
+        assert!(matches!(result, Err(DomainError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_delete_clientes_anonimos() {
+        let repository = InMemoryClienteRepository::new();
+        let _now = Utc::now();
+        let antigo = _now - Duration::days(2);
+        let _ = repository.create_cliente(create_cliente("123.456.789-09")).await;
+        let _ = repository.create_cliente(Cliente::anonimo(0, antigo, antigo)).await;
+        let recente = repository.create_cliente(Cliente::anonimo(0, _now, _now)).await.unwrap();
+
+        let removidos = repository.delete_clientes_anonimos(_now - Duration::days(1)).await.unwrap();
+        assert_eq!(removidos, 1);
+        let ids: Vec<usize> = repository.get_clientes().await.unwrap().iter().map(|cliente| *cliente.id()).collect();
+        assert_eq!(ids, vec![1, *recente.id()]);
+    }
+}
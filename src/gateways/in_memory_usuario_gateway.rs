@@ -0,0 +1,236 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+use crate::base::domain_error::DomainError;
+use crate::base::pagination::{Page, PageRequest};
+use crate::traits::usuario_gateway::{UsuarioFiltro, UsuarioGateway};
+
+use crate::entities::{
+    cpf::Cpf,
+    email::Email,
+    usuario::Usuario,
+};
+
+pub struct InMemoryUsuarioRepository {
+    usuarios: RwLock<Vec<Usuario>>,
+    next_id: AtomicUsize,
+}
+
+impl InMemoryUsuarioRepository {
+    pub fn new() -> Self {
+        InMemoryUsuarioRepository {
+            usuarios: RwLock::new(Vec::new()),
+            next_id: AtomicUsize::new(1),
+        }
+    }
+}
+
+impl Default for InMemoryUsuarioRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl UsuarioGateway for InMemoryUsuarioRepository {
+    async fn get_usuarios(&self) -> Result<Vec<Usuario>, DomainError> {
+        Ok(self.usuarios.read().unwrap().clone())
+    }
+
+    async fn list_usuarios(&self, filtro: UsuarioFiltro, page: PageRequest) -> Result<Page<Usuario>, DomainError> {
+        Ok(Page::from_all(filtro.apply(self.usuarios.read().unwrap().clone()), &page))
+    }
+
+    async fn get_usuario_by_id(&self, id: usize) -> Result<Usuario, DomainError> {
+        self.usuarios
+            .read()
+            .unwrap()
+            .iter()
+            .find(|usuario| *usuario.id() == id)
+            .cloned()
+            .ok_or(DomainError::NotFound)
+    }
+
+    async fn get_usuario_by_cpf(&self, cpf: Cpf) -> Result<Usuario, DomainError> {
+        self.usuarios
+            .read()
+            .unwrap()
+            .iter()
+            .find(|usuario| *usuario.cpf() == cpf)
+            .cloned()
+            .ok_or(DomainError::NotFound)
+    }
+
+    async fn get_usuario_by_email(&self, email: Email) -> Result<Usuario, DomainError> {
+        self.usuarios
+            .read()
+            .unwrap()
+            .iter()
+            .find(|usuario| usuario.email().eq_ignore_case(&email))
+            .cloned()
+            .ok_or(DomainError::NotFound)
+    }
+
+    async fn create_usuario(&self, usuario: Usuario) -> Result<Usuario, DomainError> {
+        let mut usuarios = self.usuarios.write().unwrap();
+        if usuarios.iter().any(|u| u.cpf() == usuario.cpf()) {
+            return Err(DomainError::AlreadyExists("cpf".to_string()));
+        }
+        let mut novo_usuario = Usuario::new(
+            self.next_id.fetch_add(1, Ordering::SeqCst),
+            usuario.nome().clone(),
+            usuario.email().clone(),
+            usuario.cpf().clone(),
+            usuario.senha().clone(),
+            usuario.tipo().clone(),
+            usuario.status().clone(),
+            *usuario.data_criacao(),
+            *usuario.data_atualizacao(),
+        );
+        novo_usuario.set_troca_senha_obrigatoria(usuario.troca_senha_obrigatoria());
+        usuarios.push(novo_usuario.clone());
+        Ok(novo_usuario)
+    }
+
+    async fn update_usuario(&self, dados_usuario_atualizado: Usuario) -> Result<Usuario, DomainError> {
+        let mut usuarios = self.usuarios.write().unwrap();
+        if usuarios.iter().any(|u| {
+            u.cpf() == dados_usuario_atualizado.cpf() && u.id() != dados_usuario_atualizado.id()
+        }) {
+            return Err(DomainError::AlreadyExists("cpf".to_string()));
+        }
+        match usuarios
+            .iter_mut()
+            .find(|usuario| usuario.id() == dados_usuario_atualizado.id())
+        {
+            Some(usuario) => {
+                *usuario = dados_usuario_atualizado.clone();
+                Ok(dados_usuario_atualizado)
+            }
+            None => Err(DomainError::NotFound),
+        }
+    }
+
+    async fn delete_usuario(&self, cpf: Cpf) -> Result<(), DomainError> {
+        let mut usuarios = self.usuarios.write().unwrap();
+        match usuarios.iter().position(|usuario| *usuario.cpf() == cpf) {
+            Some(index) => {
+                usuarios.remove(index);
+                Ok(())
+            }
+            None => Err(DomainError::NotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::usuario::{Status, Tipo};
+    use chrono::Utc;
+    use tokio;
+
+    fn create_usuario(id: usize, cpf: &str) -> Usuario {
+        let _now = Utc::now();
+        Usuario::new(
+            id,
+            "Fulano da Silva".to_string(),
+            Email::new("fulano.silva@exemplo.com".to_string()).unwrap(),
+            Cpf::new(cpf.to_string()).unwrap(),
+            "senha_segura".to_string(),
+            Tipo::Cozinha,
+            Status::Ativo,
+            _now,
+            _now,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_new_starts_empty() {
+        let repository = InMemoryUsuarioRepository::new();
+        let usuarios = repository.get_usuarios().await.unwrap();
+        assert!(usuarios.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_usuario() {
+        let repository = InMemoryUsuarioRepository::new();
+        let usuario = repository.create_usuario(create_usuario(0, "123.456.789-09")).await.unwrap();
+        assert_eq!(usuario.id(), &1);
+
+        let by_cpf = repository
+            .get_usuario_by_cpf(Cpf::new("123.456.789-09".to_string()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(by_cpf.id(), &1);
+
+        let by_id = repository.get_usuario_by_id(1).await.unwrap();
+        assert_eq!(by_id.cpf(), usuario.cpf());
+    }
+
+    #[tokio::test]
+    async fn test_create_usuario_duplicate_cpf() {
+        let repository = InMemoryUsuarioRepository::new();
+        let _ = repository.create_usuario(create_usuario(0, "123.456.789-09")).await;
+        let result = repository.create_usuario(create_usuario(0, "123.456.789-09")).await;
+        assert!(
+            matches!(result, Err(DomainError::AlreadyExists(_))),
+            "Esperado Err(DomainError::AlreadyExists), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_usuario_not_found() {
+        let repository = InMemoryUsuarioRepository::new();
+        let result = repository.update_usuario(create_usuario(42, "123.456.789-09")).await;
+        assert!(
+            matches!(result, Err(DomainError::NotFound)),
+            "Esperado Err(DomainError::NotFound), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_usuarios_filters_by_tipo() {
+        let repository = InMemoryUsuarioRepository::new();
+        let _ = repository.create_usuario(create_usuario(0, "123.456.789-09")).await;
+        let _ = repository.create_usuario(create_usuario(0, "529.982.247-25")).await;
+
+        let filtro = UsuarioFiltro {
+            tipo: Some(Tipo::Cozinha),
+            ..Default::default()
+        };
+        let page = PageRequest::new(Some(1), None).unwrap();
+        let result = repository.list_usuarios(filtro.clone(), page).await.unwrap();
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].id(), &1);
+        assert!(result.next_cursor.is_some());
+
+        let page = PageRequest::new(Some(1), result.next_cursor).unwrap();
+        let result = repository.list_usuarios(filtro, page).await.unwrap();
+        assert_eq!(result.items[0].id(), &2);
+        assert!(result.next_cursor.is_none());
+
+        let filtro = UsuarioFiltro {
+            tipo: Some(Tipo::Admin),
+            status: Some(Status::Inativo),
+            ..Default::default()
+        };
+        let result = repository.list_usuarios(filtro, PageRequest::default()).await.unwrap();
+        assert!(result.items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_usuario() {
+        let repository = InMemoryUsuarioRepository::new();
+        let _ = repository.create_usuario(create_usuario(0, "123.456.789-09")).await;
+        let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
+        assert!(repository.delete_usuario(cpf.clone()).await.is_ok());
+
+        let result = repository.get_usuario_by_cpf(cpf.clone()).await;
+        assert!(matches!(result, Err(DomainError::NotFound)));
+        let result = repository.delete_usuario(cpf).await;
+        assert!(matches!(result, Err(DomainError::NotFound)));
+    }
+}
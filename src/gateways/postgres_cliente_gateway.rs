@@ -0,0 +1,252 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use tokio_postgres::{Client, Row};
+
+use crate::{
+    base::domain_error::DomainError,
+    base::pagination::{Page, PageRequest},
+    entities::cliente::Cliente,
+    entities::cpf::Cpf,
+    entities::email::Email,
+    gateways::postgres_errors::write_error,
+    gateways::postgres_list_query::{prefix_pattern, ListQuery},
+    traits::cliente_gateway::{ClienteFiltro, ClienteGateway},
+};
+
+// As colunas TIMESTAMP guardam o horário em UTC, sem fuso
+fn timestamp_from_column(timestamp: Option<NaiveDateTime>) -> DateTime<Utc> {
+    timestamp.map(|value| value.and_utc()).unwrap_or_default()
+}
+
+// A coluna mantém o CPF com pontuação, como nos registros já existentes
+fn cpf_to_column(cpf: &Option<Cpf>) -> Option<String> {
+    cpf.as_ref().map(|cpf| cpf.formatted())
+}
+
+// Clientes anônimos não têm e-mail, mas a coluna é NOT NULL
+fn email_to_column(email: &Option<Email>) -> String {
+    email.as_ref().map(|email| email.0.clone()).unwrap_or_default()
+}
+
+fn row_to_cliente(row: &Row) -> Result<Cliente, DomainError> {
+    let id: i32 = row.get("id");
+    let cpf: Option<String> = row.get("cpf");
+    let cpf = match cpf {
+        Some(cpf) => Cpf::new(cpf)?,
+        None => {
+            return Ok(Cliente::anonimo(
+                id as usize,
+                timestamp_from_column(row.get("data_criacao")),
+                timestamp_from_column(row.get("data_atualizacao")),
+            ))
+        }
+    };
+    Ok(Cliente::new(
+        id as usize,
+        row.get("nome"),
+        Email::new(row.get("email"))?,
+        cpf,
+        timestamp_from_column(row.get("data_criacao")),
+        timestamp_from_column(row.get("data_atualizacao")),
+    ))
+}
+
+pub struct PostgresClienteRepository {
+    client: Client,
+}
+
+impl PostgresClienteRepository {
+    pub fn new(client: Client) -> Self {
+        PostgresClienteRepository { client }
+    }
+}
+
+#[async_trait]
+impl ClienteGateway for PostgresClienteRepository {
+    async fn get_clientes(&self) -> Result<Vec<Cliente>, DomainError> {
+        let rows = self
+            .client
+            .query("SELECT * FROM cliente ORDER BY id", &[])
+            .await
+            .map_err(|err| {
+                println!("Error during postgres request: {}", err);
+                DomainError::Internal("Cliente".to_string())
+            })?;
+
+        let mut clientes: Vec<Cliente> = Vec::new();
+        for row in rows {
+            match row_to_cliente(&row) {
+                Ok(cliente) => clientes.push(cliente),
+                Err(_) => println!("Invalid CPF for cliente: {}", row.get::<_, String>("nome")),
+            }
+        }
+        Ok(clientes)
+    }
+
+    async fn list_clientes(&self, filtro: ClienteFiltro, page: PageRequest) -> Result<Page<Cliente>, DomainError> {
+        let mut query = ListQuery::new();
+        if let Some(nome_prefixo) = &filtro.nome_prefixo {
+            query.filter("nome ILIKE {}", prefix_pattern(nome_prefixo));
+        }
+        if let Some(email) = filtro.email {
+            query.filter("lower(email) = lower({})", email.0);
+        }
+        if let Some(criado_de) = filtro.criado_de {
+            query.filter("data_criacao >= {}", criado_de.naive_utc());
+        }
+        if let Some(criado_ate) = filtro.criado_ate {
+            query.filter("data_criacao <= {}", criado_ate.naive_utc());
+        }
+        let sql = query.build("cliente", filtro.ordenar_por, filtro.ordem, &page);
+        let rows = self
+            .client
+            .query(&sql, &query.params())
+            .await
+            .map_err(|err| {
+                println!("Error during postgres request: {}", err);
+                DomainError::Internal("Cliente".to_string())
+            })?;
+
+        let mut clientes: Vec<Cliente> = Vec::new();
+        for row in rows {
+            match row_to_cliente(&row) {
+                Ok(cliente) => clientes.push(cliente),
+                Err(_) => println!("Invalid CPF for cliente: {}", row.get::<_, String>("nome")),
+            }
+        }
+        Ok(Page::from_fetched(clientes, &page))
+    }
+
+    async fn get_cliente_by_cpf(&self, cpf: Cpf) -> Result<Cliente, DomainError> {
+        let row = self
+            .client
+            .query_opt("SELECT * FROM cliente WHERE cpf = $1", &[&cpf.formatted()])
+            .await
+            .map_err(|err| {
+                println!("Error during postgres request: {}", err);
+                DomainError::Internal("Cliente".to_string())
+            })?;
+
+        match row {
+            Some(row) => row_to_cliente(&row),
+            None => Err(DomainError::NotFound),
+        }
+    }
+
+    async fn get_cliente_by_email(&self, email: Email) -> Result<Cliente, DomainError> {
+        let row = self
+            .client
+            .query_opt("SELECT * FROM cliente WHERE lower(email) = lower($1) LIMIT 1", &[&email.0])
+            .await
+            .map_err(|err| {
+                println!("Error during postgres request: {}", err);
+                DomainError::Internal("Cliente".to_string())
+            })?;
+
+        match row {
+            Some(row) => row_to_cliente(&row),
+            None => Err(DomainError::NotFound),
+        }
+    }
+
+    async fn get_cliente_by_id(&self, id: usize) -> Result<Cliente, DomainError> {
+        let id = id as i32;
+        let row = self
+            .client
+            .query_opt("SELECT * FROM cliente WHERE id = $1", &[&id])
+            .await
+            .map_err(|err| {
+                println!("Error during postgres request: {}", err);
+                DomainError::Internal("Cliente".to_string())
+            })?;
+
+        match row {
+            Some(row) => row_to_cliente(&row),
+            None => Err(DomainError::NotFound),
+        }
+    }
+
+    async fn create_cliente(&self, cliente: Cliente) -> Result<Cliente, DomainError> {
+        let row = self
+            .client
+            .query_one(
+                "INSERT INTO cliente (nome, email, cpf, data_criacao, data_atualizacao) \
+                 VALUES ($1, $2, $3, $4, $5) RETURNING *",
+                &[
+                    cliente.nome(),
+                    &email_to_column(cliente.email()),
+                    &cpf_to_column(cliente.cpf()),
+                    &cliente.data_criacao().naive_utc(),
+                    &cliente.data_atualizacao().naive_utc(),
+                ],
+            )
+            .await
+            .map_err(|err| {
+                println!("Failed to create cliente {:?}: {}", cliente.cpf(), err);
+                write_error(&err, "Cliente")
+            })?;
+
+        row_to_cliente(&row)
+    }
+
+    async fn update_cliente(&self, dados_cliente_atualizado: Cliente) -> Result<Cliente, DomainError> {
+        let id = *dados_cliente_atualizado.id() as i32;
+        let row = self
+            .client
+            .query_opt(
+                "UPDATE cliente SET nome = $2, email = $3, cpf = $4, data_criacao = $5, data_atualizacao = $6 \
+                 WHERE id = $1 RETURNING *",
+                &[
+                    &id,
+                    dados_cliente_atualizado.nome(),
+                    &email_to_column(dados_cliente_atualizado.email()),
+                    &cpf_to_column(dados_cliente_atualizado.cpf()),
+                    &dados_cliente_atualizado.data_criacao().naive_utc(),
+                    &dados_cliente_atualizado.data_atualizacao().naive_utc(),
+                ],
+            )
+            .await
+            .map_err(|err| {
+                println!("Failed to update cliente {}: {}", id, err);
+                write_error(&err, "Cliente")
+            })?;
+
+        match row {
+            Some(row) => row_to_cliente(&row),
+            None => Err(DomainError::NotFound),
+        }
+    }
+
+    async fn delete_cliente(&self, cpf: Cpf) -> Result<(), DomainError> {
+        let deleted = self
+            .client
+            .execute("DELETE FROM cliente WHERE cpf = $1", &[&cpf.formatted()])
+            .await
+            .map_err(|err| {
+                println!("Failed to delete cliente {}: {}", cpf.formatted(), err);
+                DomainError::Internal("Cliente".to_string())
+            })?;
+
+        if deleted == 0 {
+            return Err(DomainError::NotFound);
+        }
+        Ok(())
+    }
+
+    // Clientes com pedido ficam, já que pedido.cliente_id referencia cliente
+    async fn delete_clientes_anonimos(&self, criado_ate: DateTime<Utc>) -> Result<usize, DomainError> {
+        let deleted = self
+            .client
+            .execute(
+                "DELETE FROM cliente WHERE cpf IS NULL AND data_criacao < $1 \
+                 AND NOT EXISTS (SELECT 1 FROM pedido WHERE pedido.cliente_id = cliente.id)",
+                &[&criado_ate.naive_utc()],
+            )
+            .await
+            .map_err(|err| {
+                println!("Failed to delete clientes anonimos: {}", err);
+                DomainError::Internal("Cliente".to_string())
+            })?;
+
+        Ok(deleted as usize)
+    }
+}
@@ -0,0 +1,34 @@
+use tokio_postgres::error::SqlState;
+
+use crate::base::domain_error::DomainError;
+
+// Os índices únicos (migração 0005) se chamam <tabela>_<campo>_key
+fn campo_unico(constraint: Option<&str>) -> &'static str {
+    match constraint {
+        Some(constraint) if constraint.ends_with("_email_key") => "email",
+        _ => "cpf",
+    }
+}
+
+// Converte o erro de INSERT/UPDATE; violação de unicidade vira AlreadyExists
+// como na checagem feita pelo caso de uso
+pub fn write_error(err: &tokio_postgres::Error, entidade: &str) -> DomainError {
+    if err.code() == Some(&SqlState::UNIQUE_VIOLATION) {
+        let constraint = err.as_db_error().and_then(|db_error| db_error.constraint());
+        return DomainError::AlreadyExists(campo_unico(constraint).to_string());
+    }
+    DomainError::Internal(entidade.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_map_constraint_to_campo() {
+        assert_eq!(campo_unico(Some("usuario_email_key")), "email");
+        assert_eq!(campo_unico(Some("cliente_email_key")), "email");
+        assert_eq!(campo_unico(Some("usuario_cpf_key")), "cpf");
+        assert_eq!(campo_unico(None), "cpf");
+    }
+}
@@ -0,0 +1,103 @@
+use tokio_postgres::types::ToSql;
+
+use crate::base::pagination::{CampoOrdenacao, Ordem, PageRequest};
+
+// Monta o SELECT das listagens paginadas. Os valores dos filtros vão sempre
+// como parâmetros; só nomes de colunas fixos entram no texto da consulta.
+pub struct ListQuery {
+    conditions: Vec<String>,
+    params: Vec<Box<dyn ToSql + Sync + Send>>,
+}
+
+impl ListQuery {
+    pub fn new() -> Self {
+        ListQuery {
+            conditions: Vec::new(),
+            params: Vec::new(),
+        }
+    }
+
+    // `condition` usa `{}` no lugar do parâmetro, ex.: "tipo = {}"
+    pub fn filter<T: ToSql + Sync + Send + 'static>(&mut self, condition: &str, value: T) {
+        self.params.push(Box::new(value));
+        self.conditions
+            .push(condition.replace("{}", &format!("${}", self.params.len())));
+    }
+
+    pub fn build(&mut self, table: &str, ordenar_por: CampoOrdenacao, ordem: Ordem, page: &PageRequest) -> String {
+        let where_clause = if self.conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", self.conditions.join(" AND "))
+        };
+        let column = match ordenar_por {
+            CampoOrdenacao::Id => "id",
+            CampoOrdenacao::Nome => "lower(nome)",
+            CampoOrdenacao::DataCriacao => "data_criacao",
+        };
+        let direction = match ordem {
+            Ordem::Asc => "ASC",
+            Ordem::Desc => "DESC",
+        };
+        // Busca um registro a mais para saber se existe próxima página
+        self.params.push(Box::new((page.limit + 1) as i64));
+        let limit = self.params.len();
+        self.params.push(Box::new(page.offset as i64));
+        let offset = self.params.len();
+        format!(
+            "SELECT * FROM {}{} ORDER BY {} {}, id {} LIMIT ${} OFFSET ${}",
+            table, where_clause, column, direction, direction, limit, offset
+        )
+    }
+
+    pub fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.params
+            .iter()
+            .map(|param| param.as_ref() as &(dyn ToSql + Sync))
+            .collect()
+    }
+}
+
+impl Default for ListQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn prefix_pattern(prefixo: &str) -> String {
+    let escaped = prefixo
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("{}%", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_build_query_with_filters() {
+        let mut query = ListQuery::new();
+        query.filter("nome ILIKE {}", prefix_pattern("Ful"));
+        query.filter("tipo = {}", "Admin".to_string());
+        let sql = query.build("usuario", CampoOrdenacao::Nome, Ordem::Desc, &PageRequest::default());
+        assert_eq!(
+            sql,
+            "SELECT * FROM usuario WHERE nome ILIKE $1 AND tipo = $2 ORDER BY lower(nome) DESC, id DESC LIMIT $3 OFFSET $4"
+        );
+        assert_eq!(query.params().len(), 4);
+    }
+
+    #[test]
+    fn should_build_query_without_filters() {
+        let mut query = ListQuery::new();
+        let sql = query.build("cliente", CampoOrdenacao::Id, Ordem::Asc, &PageRequest::default());
+        assert_eq!(sql, "SELECT * FROM cliente ORDER BY id ASC, id ASC LIMIT $1 OFFSET $2");
+    }
+
+    #[test]
+    fn should_escape_like_wildcards() {
+        assert_eq!(prefix_pattern("50%_a"), "50\\%\\_a%");
+    }
+}
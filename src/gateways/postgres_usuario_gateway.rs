@@ -0,0 +1,251 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use tokio_postgres::{Client, Row};
+
+use crate::base::domain_error::DomainError;
+use crate::base::pagination::{Page, PageRequest};
+use crate::gateways::postgres_errors::write_error;
+use crate::gateways::postgres_list_query::{prefix_pattern, ListQuery};
+use crate::traits::usuario_gateway::{UsuarioFiltro, UsuarioGateway};
+
+use crate::entities::{
+    cpf::Cpf,
+    email::Email,
+    usuario::{Status, Tipo, Usuario},
+};
+
+// As colunas TIMESTAMP guardam o horário em UTC, sem fuso
+fn timestamp_from_column(timestamp: Option<NaiveDateTime>) -> DateTime<Utc> {
+    timestamp.map(|value| value.and_utc()).unwrap_or_default()
+}
+
+// Uma coluna ausente (migração não aplicada) vira erro em vez de panic
+fn column<'a, T: tokio_postgres::types::FromSql<'a>>(row: &'a Row, name: &str) -> Result<T, DomainError> {
+    row.try_get(name).map_err(|err| {
+        println!("Failed to read usuario column {}: {}", name, err);
+        DomainError::Internal("Usuario".to_string())
+    })
+}
+
+fn row_to_usuario(row: &Row) -> Result<Usuario, DomainError> {
+    let id: i32 = column(row, "id")?;
+    let cpf: String = column(row, "cpf")?;
+    let cpf = Cpf::new(cpf)?;
+    let tipo: String = column(row, "tipo")?;
+    let tipo = Tipo::from_str(&tipo)
+        .map_err(|_| DomainError::Internal("Tipo do Usuário é inválido".to_string()))?;
+    let status: String = column(row, "status")?;
+    let status = Status::from_str(&status)
+        .map_err(|_| DomainError::Internal("Status do Usuário é inválido".to_string()))?;
+    let mut usuario = Usuario::new(
+        id as usize,
+        column(row, "nome")?,
+        Email::new(column(row, "email")?)?,
+        cpf,
+        column(row, "senha")?,
+        tipo,
+        status,
+        timestamp_from_column(column(row, "data_criacao")?),
+        timestamp_from_column(column(row, "data_atualizacao")?),
+    );
+    usuario.set_troca_senha_obrigatoria(column(row, "troca_senha_obrigatoria")?);
+    Ok(usuario)
+}
+
+pub struct PostgresUsuarioRepository {
+    client: Client,
+}
+
+impl PostgresUsuarioRepository {
+    pub fn new(client: Client) -> Self {
+        PostgresUsuarioRepository { client }
+    }
+}
+
+#[async_trait]
+impl UsuarioGateway for PostgresUsuarioRepository {
+    async fn get_usuarios(&self) -> Result<Vec<Usuario>, DomainError> {
+        let rows = self
+            .client
+            .query("SELECT * FROM usuario ORDER BY id", &[])
+            .await
+            .map_err(|err| {
+                println!("Error during postgres request: {}", err);
+                DomainError::Internal("Usuario".to_string())
+            })?;
+
+        let mut usuarios: Vec<Usuario> = Vec::new();
+        for row in rows {
+            match row_to_usuario(&row) {
+                Ok(usuario) => usuarios.push(usuario),
+                Err(err) => println!("Skipping invalid usuario {}: {:?}", row.get::<_, i32>("id"), err),
+            }
+        }
+        Ok(usuarios)
+    }
+
+    async fn list_usuarios(&self, filtro: UsuarioFiltro, page: PageRequest) -> Result<Page<Usuario>, DomainError> {
+        let mut query = ListQuery::new();
+        if let Some(nome_prefixo) = &filtro.nome_prefixo {
+            query.filter("nome ILIKE {}", prefix_pattern(nome_prefixo));
+        }
+        if let Some(email) = filtro.email {
+            query.filter("lower(email) = lower({})", email.0);
+        }
+        if let Some(tipo) = filtro.tipo {
+            query.filter("tipo = {}", tipo.to_string());
+        }
+        if let Some(status) = filtro.status {
+            query.filter("status = {}", status.to_string());
+        }
+        if let Some(criado_de) = filtro.criado_de {
+            query.filter("data_criacao >= {}", criado_de.naive_utc());
+        }
+        if let Some(criado_ate) = filtro.criado_ate {
+            query.filter("data_criacao <= {}", criado_ate.naive_utc());
+        }
+        let sql = query.build("usuario", filtro.ordenar_por, filtro.ordem, &page);
+        let rows = self
+            .client
+            .query(&sql, &query.params())
+            .await
+            .map_err(|err| {
+                println!("Error during postgres request: {}", err);
+                DomainError::Internal("Usuario".to_string())
+            })?;
+
+        let mut usuarios: Vec<Usuario> = Vec::new();
+        for row in rows {
+            match row_to_usuario(&row) {
+                Ok(usuario) => usuarios.push(usuario),
+                Err(err) => println!("Skipping invalid usuario {}: {:?}", row.get::<_, i32>("id"), err),
+            }
+        }
+        Ok(Page::from_fetched(usuarios, &page))
+    }
+
+    async fn get_usuario_by_id(&self, id: usize) -> Result<Usuario, DomainError> {
+        let id = id as i32;
+        let row = self
+            .client
+            .query_opt("SELECT * FROM usuario WHERE id = $1", &[&id])
+            .await
+            .map_err(|err| {
+                println!("Error during postgres request: {}", err);
+                DomainError::Internal("Usuario".to_string())
+            })?;
+
+        match row {
+            Some(row) => row_to_usuario(&row),
+            None => Err(DomainError::NotFound),
+        }
+    }
+
+    async fn get_usuario_by_cpf(&self, cpf: Cpf) -> Result<Usuario, DomainError> {
+        let row = self
+            .client
+            .query_opt("SELECT * FROM usuario WHERE cpf = $1", &[&cpf.formatted()])
+            .await
+            .map_err(|err| {
+                println!("Error during postgres request: {}", err);
+                DomainError::Internal("Usuario".to_string())
+            })?;
+
+        match row {
+            Some(row) => row_to_usuario(&row),
+            None => Err(DomainError::NotFound),
+        }
+    }
+
+    async fn get_usuario_by_email(&self, email: Email) -> Result<Usuario, DomainError> {
+        let row = self
+            .client
+            .query_opt("SELECT * FROM usuario WHERE lower(email) = lower($1) LIMIT 1", &[&email.0])
+            .await
+            .map_err(|err| {
+                println!("Error during postgres request: {}", err);
+                DomainError::Internal("Usuario".to_string())
+            })?;
+
+        match row {
+            Some(row) => row_to_usuario(&row),
+            None => Err(DomainError::NotFound),
+        }
+    }
+
+    async fn create_usuario(&self, usuario: Usuario) -> Result<Usuario, DomainError> {
+        let row = self
+            .client
+            .query_one(
+                "INSERT INTO usuario (nome, email, cpf, senha, tipo, status, data_criacao, data_atualizacao, \
+                 troca_senha_obrigatoria) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING *",
+                &[
+                    usuario.nome(),
+                    &usuario.email().0,
+                    &usuario.cpf().formatted(),
+                    usuario.senha(),
+                    &usuario.tipo().to_string(),
+                    &usuario.status().to_string(),
+                    &usuario.data_criacao().naive_utc(),
+                    &usuario.data_atualizacao().naive_utc(),
+                    &usuario.troca_senha_obrigatoria(),
+                ],
+            )
+            .await
+            .map_err(|err| {
+                println!("Failed to create usuario {}: {}", usuario.cpf().formatted(), err);
+                write_error(&err, "Usuario")
+            })?;
+
+        row_to_usuario(&row)
+    }
+
+    async fn update_usuario(&self, dados_usuario_atualizado: Usuario) -> Result<Usuario, DomainError> {
+        let id = *dados_usuario_atualizado.id() as i32;
+        let row = self
+            .client
+            .query_opt(
+                "UPDATE usuario SET nome = $2, email = $3, cpf = $4, senha = $5, tipo = $6, status = $7, \
+                 data_criacao = $8, data_atualizacao = $9, troca_senha_obrigatoria = $10 WHERE id = $1 RETURNING *",
+                &[
+                    &id,
+                    dados_usuario_atualizado.nome(),
+                    &dados_usuario_atualizado.email().0,
+                    &dados_usuario_atualizado.cpf().formatted(),
+                    dados_usuario_atualizado.senha(),
+                    &dados_usuario_atualizado.tipo().to_string(),
+                    &dados_usuario_atualizado.status().to_string(),
+                    &dados_usuario_atualizado.data_criacao().naive_utc(),
+                    &dados_usuario_atualizado.data_atualizacao().naive_utc(),
+                    &dados_usuario_atualizado.troca_senha_obrigatoria(),
+                ],
+            )
+            .await
+            .map_err(|err| {
+                println!("Failed to update usuario {}: {}", id, err);
+                write_error(&err, "Usuario")
+            })?;
+
+        match row {
+            Some(row) => row_to_usuario(&row),
+            None => Err(DomainError::NotFound),
+        }
+    }
+
+    async fn delete_usuario(&self, cpf: Cpf) -> Result<(), DomainError> {
+        let deleted = self
+            .client
+            .execute("DELETE FROM usuario WHERE cpf = $1", &[&cpf.formatted()])
+            .await
+            .map_err(|err| {
+                println!("Failed to delete usuario {}: {}", cpf.formatted(), err);
+                DomainError::Internal("Usuario".to_string())
+            })?;
+
+        if deleted == 0 {
+            return Err(DomainError::NotFound);
+        }
+        Ok(())
+    }
+}
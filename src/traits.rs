@@ -1,3 +1,8 @@
 pub mod authentication_adapter;
+pub mod login_attempt_store;
+pub mod notifier;
+pub mod password_hasher;
+pub mod token_revocation_store;
+pub mod verification_code_store;
 pub mod usuario_gateway;
 pub mod cliente_gateway;
@@ -1,13 +1,60 @@
 use mockall::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     base::domain_error::DomainError,
+    entities::cliente::Cliente,
     entities::usuario::{Tipo, Usuario},
 };
 
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct AuthenticationTokens {
+    pub token: String,
+    pub token_expiration: usize,
+    pub refresh_token: String,
+    pub refresh_token_expiration: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct ClienteAuthenticationToken {
+    pub token: String,
+    pub token_expiration: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Jwk {
+    pub kty: String,
+    pub kid: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub alg: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
 #[automock]
 #[async_trait]
 pub trait AuthenticationAdapter{
-    async fn get_token(&self, user: Usuario) -> Result<String, DomainError>;
+    async fn get_token(&self, user: Usuario) -> Result<AuthenticationTokens, DomainError>;
     async fn validate_token(&self, token: String, role: Option<Tipo>) -> Result<String, DomainError>;
+    async fn validate_refresh_token(&self, refresh_token: String) -> Result<String, DomainError>;
+    async fn rotate_refresh_token(&self, refresh_token: String, user: Usuario) -> Result<AuthenticationTokens, DomainError>;
+    async fn revoke_token(&self, token: String) -> Result<(), DomainError>;
+    async fn get_cliente_token(&self, cliente: Cliente) -> Result<ClienteAuthenticationToken, DomainError>;
+    async fn validate_cliente_token(&self, token: String) -> Result<String, DomainError>;
+    async fn get_jwks(&self) -> Result<JwkSet, DomainError>;
 }
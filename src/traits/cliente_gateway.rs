@@ -1,21 +1,78 @@
+use chrono::{DateTime, Utc};
 use mockall::*;
 
 use crate::base::domain_error::DomainError;
+use crate::base::pagination::{timestamp_in_range, CampoOrdenacao, Ordem, Page, PageRequest};
 use crate::entities::{
     cliente::Cliente,
-    cpf::Cpf
+    cpf::Cpf,
+    email::Email,
 };
 
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClienteFiltro {
+    pub nome_prefixo: Option<String>,
+    pub email: Option<Email>,
+    pub criado_de: Option<DateTime<Utc>>,
+    pub criado_ate: Option<DateTime<Utc>>,
+    pub ordenar_por: CampoOrdenacao,
+    pub ordem: Ordem,
+}
+
+impl ClienteFiltro {
+    pub fn matches(&self, cliente: &Cliente) -> bool {
+        if let Some(nome_prefixo) = &self.nome_prefixo {
+            if !cliente.nome().to_lowercase().starts_with(&nome_prefixo.to_lowercase()) {
+                return false;
+            }
+        }
+        if let Some(email) = &self.email {
+            if !cliente.email().as_ref().is_some_and(|cliente_email| cliente_email.eq_ignore_case(email)) {
+                return false;
+            }
+        }
+        timestamp_in_range(cliente.data_criacao(), &self.criado_de, &self.criado_ate)
+    }
+
+    // Filtra e ordena uma listagem completa, para os backends sem consulta própria
+    pub fn apply(&self, clientes: Vec<Cliente>) -> Vec<Cliente> {
+        let mut clientes: Vec<Cliente> = clientes.into_iter().filter(|cliente| self.matches(cliente)).collect();
+        clientes.sort_by(|a, b| {
+            let ordering = match self.ordenar_por {
+                CampoOrdenacao::Id => a.id().cmp(b.id()),
+                CampoOrdenacao::Nome => a.nome().to_lowercase().cmp(&b.nome().to_lowercase()),
+                CampoOrdenacao::DataCriacao => a.data_criacao().cmp(b.data_criacao()),
+            }
+            .then(a.id().cmp(b.id()));
+            match self.ordem {
+                Ordem::Asc => ordering,
+                Ordem::Desc => ordering.reverse(),
+            }
+        });
+        clientes
+    }
+}
+
 #[automock]
 #[async_trait]
 pub trait ClienteGateway {
     async fn get_clientes(&self) -> Result<Vec<Cliente>, DomainError>;
 
+    async fn list_clientes(&self, filtro: ClienteFiltro, page: PageRequest) -> Result<Page<Cliente>, DomainError>;
+
     async fn get_cliente_by_cpf(&self, cpf: Cpf) -> Result<Cliente, DomainError>;
 
+    async fn get_cliente_by_email(&self, email: Email) -> Result<Cliente, DomainError>;
+
     async fn get_cliente_by_id(&self, id: usize) -> Result<Cliente, DomainError>;
 
-    async fn create_cliente(&mut self, cliente: Cliente) -> Result<Cliente, DomainError>;
+    async fn create_cliente(&self, cliente: Cliente) -> Result<Cliente, DomainError>;
+
+    async fn update_cliente(&self, cliente: Cliente) -> Result<Cliente, DomainError>;
+
+    async fn delete_cliente(&self, cpf: Cpf) -> Result<(), DomainError>;
 
-    async fn delete_cliente(&mut self, cpf: Cpf) -> Result<(), DomainError>;
+    // Remove os clientes anônimos criados antes de `criado_ate` que nunca se
+    // identificaram; devolve quantos foram removidos
+    async fn delete_clientes_anonimos(&self, criado_ate: DateTime<Utc>) -> Result<usize, DomainError>;
 }
@@ -0,0 +1,42 @@
+use mockall::*;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::base::domain_error::DomainError;
+
+// Falhas de login são contadas por conta (CPF) e por IP de origem; logins
+// anônimos, que criam um cliente cada, são contados à parte por IP
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, JsonSchema)]
+#[serde(tag = "tipo", content = "valor", rename_all = "lowercase")]
+pub enum LoginAttemptKey {
+    Conta(String),
+    Ip(String),
+    Anonimo(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, JsonSchema)]
+pub struct Lockout {
+    pub chave: LoginAttemptKey,
+    pub bloqueios: u32,
+    pub bloqueado_ate: i64,
+}
+
+#[automock]
+#[async_trait]
+pub trait LoginAttemptStore {
+    // Reserva uma tentativa em andamento. Falha com TooManyAttempts enquanto a
+    // chave estiver bloqueada ou se as tentativas pendentes já somam o limite.
+    // Toda reserva termina em register_failure, register_success ou release
+    async fn reserve(&self, key: LoginAttemptKey) -> Result<(), DomainError>;
+
+    async fn register_failure(&self, key: LoginAttemptKey) -> Result<(), DomainError>;
+
+    // Devolve a reserva sem contar falha
+    async fn release(&self, key: LoginAttemptKey) -> Result<(), DomainError>;
+
+    async fn register_success(&self, key: LoginAttemptKey) -> Result<(), DomainError>;
+
+    async fn list_lockouts(&self) -> Result<Vec<Lockout>, DomainError>;
+
+    async fn clear(&self, key: LoginAttemptKey) -> Result<(), DomainError>;
+}
@@ -0,0 +1,9 @@
+use mockall::*;
+
+use crate::base::domain_error::DomainError;
+
+#[automock]
+#[async_trait]
+pub trait Notifier {
+    async fn notify(&self, destinatario: String, assunto: String, mensagem: String) -> Result<(), DomainError>;
+}
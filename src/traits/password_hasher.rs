@@ -0,0 +1,10 @@
+use mockall::*;
+
+use crate::base::domain_error::DomainError;
+
+#[automock]
+pub trait PasswordHasher {
+    fn hash(&self, senha: &str) -> Result<String, DomainError>;
+    fn verify(&self, senha: &str, hash: &str) -> bool;
+    fn needs_rehash(&self, hash: &str) -> bool;
+}
@@ -0,0 +1,11 @@
+use mockall::*;
+
+use crate::base::domain_error::DomainError;
+
+#[automock]
+#[async_trait]
+pub trait TokenRevocationStore {
+    async fn revoke(&self, jti: String, exp: usize) -> Result<(), DomainError>;
+
+    async fn is_revoked(&self, jti: String) -> Result<bool, DomainError>;
+}
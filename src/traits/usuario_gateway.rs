@@ -1,9 +1,12 @@
+use chrono::{DateTime, Utc};
 use mockall::*;
 
 use crate::base::domain_error::DomainError;
+use crate::base::pagination::{timestamp_in_range, CampoOrdenacao, Ordem, Page, PageRequest};
 use crate::entities::{
     cpf::Cpf,
-    usuario::{Status, Usuario},
+    email::Email,
+    usuario::{Status, Tipo, Usuario},
 };
 use std::fmt;
 use std::str::FromStr;
@@ -33,21 +36,77 @@ impl fmt::Display for Status {
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UsuarioFiltro {
+    pub nome_prefixo: Option<String>,
+    pub email: Option<Email>,
+    pub tipo: Option<Tipo>,
+    pub status: Option<Status>,
+    pub criado_de: Option<DateTime<Utc>>,
+    pub criado_ate: Option<DateTime<Utc>>,
+    pub ordenar_por: CampoOrdenacao,
+    pub ordem: Ordem,
+}
+
+impl UsuarioFiltro {
+    pub fn matches(&self, usuario: &Usuario) -> bool {
+        if let Some(nome_prefixo) = &self.nome_prefixo {
+            if !usuario.nome().to_lowercase().starts_with(&nome_prefixo.to_lowercase()) {
+                return false;
+            }
+        }
+        if let Some(email) = &self.email {
+            if !usuario.email().eq_ignore_case(email) {
+                return false;
+            }
+        }
+        if self.tipo.as_ref().is_some_and(|tipo| tipo != usuario.tipo()) {
+            return false;
+        }
+        if self.status.as_ref().is_some_and(|status| status != usuario.status()) {
+            return false;
+        }
+        timestamp_in_range(usuario.data_criacao(), &self.criado_de, &self.criado_ate)
+    }
+
+    // Filtra e ordena uma listagem completa, para os backends sem consulta própria
+    pub fn apply(&self, usuarios: Vec<Usuario>) -> Vec<Usuario> {
+        let mut usuarios: Vec<Usuario> = usuarios.into_iter().filter(|usuario| self.matches(usuario)).collect();
+        usuarios.sort_by(|a, b| {
+            let ordering = match self.ordenar_por {
+                CampoOrdenacao::Id => a.id().cmp(b.id()),
+                CampoOrdenacao::Nome => a.nome().to_lowercase().cmp(&b.nome().to_lowercase()),
+                CampoOrdenacao::DataCriacao => a.data_criacao().cmp(b.data_criacao()),
+            }
+            .then(a.id().cmp(b.id()));
+            match self.ordem {
+                Ordem::Asc => ordering,
+                Ordem::Desc => ordering.reverse(),
+            }
+        });
+        usuarios
+    }
+}
+
 #[automock]
 #[async_trait]
 pub trait UsuarioGateway {
     async fn get_usuarios(&self) -> Result<Vec<Usuario>, DomainError>;
 
+    async fn list_usuarios(&self, filtro: UsuarioFiltro, page: PageRequest) -> Result<Page<Usuario>, DomainError>;
+
     async fn get_usuario_by_id(&self, id: usize) -> Result<Usuario, DomainError>;
 
     async fn get_usuario_by_cpf(&self, cpf: Cpf) -> Result<Usuario, DomainError>;
 
-    async fn create_usuario(&mut self, user: Usuario) -> Result<Usuario, DomainError>;
+    async fn get_usuario_by_email(&self, email: Email) -> Result<Usuario, DomainError>;
+
+    async fn create_usuario(&self, user: Usuario) -> Result<Usuario, DomainError>;
 
     async fn update_usuario(
-        &mut self,
+        &self,
         dados_usuario_atualizado: Usuario,
     ) -> Result<Usuario, DomainError>;
 
-    async fn delete_usuario(&mut self, cpf: Cpf) -> Result<(), DomainError>;
+    async fn delete_usuario(&self, cpf: Cpf) -> Result<(), DomainError>;
 }
@@ -0,0 +1,11 @@
+use mockall::*;
+
+use crate::base::domain_error::DomainError;
+
+#[automock]
+#[async_trait]
+pub trait VerificationCodeStore {
+    async fn issue(&self, key: String) -> Result<String, DomainError>;
+
+    async fn verify(&self, key: String, code: String) -> Result<(), DomainError>;
+}
@@ -1,15 +1,17 @@
-use chrono::Utc;
-use tokio::sync::Mutex;
+use chrono::{Duration, Utc};
 use schemars::JsonSchema;
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::base::assertion_concern::{assert_field_not_empty, Violations};
 use crate::base::domain_error::DomainError;
+use crate::base::pagination::{parse_campo_filtro, parse_data_filtro, Page, PageRequest};
 use crate::entities::{
     cliente::Cliente,
     cpf::Cpf,
+    email::Email,
 };
-use crate::traits::cliente_gateway::ClienteGateway;
+use crate::traits::cliente_gateway::{ClienteFiltro, ClienteGateway};
 
 #[derive(Clone, Debug, Deserialize, JsonSchema)]
 pub struct CreateClienteInput {
@@ -18,41 +20,112 @@ pub struct CreateClienteInput {
     cpf: String,
 }
 
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
+pub struct UpdateClienteInput {
+    nome: Option<String>,
+    email: Option<String>,
+    cpf: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, FromForm, JsonSchema)]
+pub struct ListaClientesInput {
+    limit: Option<usize>,
+    cursor: Option<String>,
+    nome: Option<String>,
+    email: Option<String>,
+    criado_de: Option<String>,
+    criado_ate: Option<String>,
+    ordenar_por: Option<String>,
+    ordem: Option<String>,
+}
+
+// CPF e e-mail identificam o cliente; o e-mail é comparado sem diferenciar
+// maiúsculas e minúsculas. Numa atualização, `proprio_id` é o registro que
+// está sendo alterado e pode manter os próprios valores
+async fn check_cliente_unico(
+    cliente_repository: &(dyn ClienteGateway + Sync + Send),
+    cpf: Option<&Cpf>,
+    email: Option<&Email>,
+    proprio_id: Option<usize>,
+) -> Result<(), DomainError> {
+    if let Some(cpf) = cpf {
+        match cliente_repository.get_cliente_by_cpf(cpf.clone()).await {
+            Ok(existente) if Some(*existente.id()) != proprio_id => {
+                return Err(DomainError::AlreadyExists("cpf".to_string()))
+            }
+            Ok(_) | Err(DomainError::NotFound) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    if let Some(email) = email {
+        match cliente_repository.get_cliente_by_email(email.clone()).await {
+            Ok(existente) if Some(*existente.id()) != proprio_id => {
+                return Err(DomainError::AlreadyExists("email".to_string()))
+            }
+            Ok(_) | Err(DomainError::NotFound) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+// Valida todos os campos de uma vez, para que a resposta traga a lista
+// completa de violações
+fn valida_dados_cliente(dados_cliente: CreateClienteInput) -> Result<(String, Email, Cpf), DomainError> {
+    let mut violations = Violations::new();
+    violations.check("nome", assert_field_not_empty("nome", &dados_cliente.nome));
+    let email = violations.check("email", Email::new(dados_cliente.email));
+    let cpf = violations.check("cpf", Cpf::new(dados_cliente.cpf));
+    violations.into_result()?;
+    Ok((dados_cliente.nome, email.unwrap(), cpf.unwrap()))
+}
+
 #[derive(Clone)]
 pub struct ClienteUseCase {
-    cliente_repository: Arc<Mutex<dyn ClienteGateway + Sync + Send>>,
+    cliente_repository: Arc<dyn ClienteGateway + Sync + Send>,
 }
 
 impl ClienteUseCase {
-    pub fn new(cliente_repository: Arc<Mutex<dyn ClienteGateway + Sync + Send>>) -> Self {
+    pub fn new(cliente_repository: Arc<dyn ClienteGateway + Sync + Send>) -> Self {
         ClienteUseCase { cliente_repository }
     }
 
-    pub async fn get_clientes(&self) -> Result<Vec<Cliente>, DomainError> {
-        let cliente_repository = self.cliente_repository.lock().await;
-        cliente_repository.get_clientes().await
+    pub async fn lista_clientes(&self, input: ListaClientesInput) -> Result<Page<Cliente>, DomainError> {
+        let page = PageRequest::new(input.limit, input.cursor)?;
+        let filtro = ClienteFiltro {
+            nome_prefixo: input.nome,
+            email: input.email.map(Email::new).transpose()?,
+            criado_de: parse_data_filtro(input.criado_de, "criado_de")?,
+            criado_ate: parse_data_filtro(input.criado_ate, "criado_ate")?,
+            ordenar_por: parse_campo_filtro(input.ordenar_por, "ordenar_por")?.unwrap_or_default(),
+            ordem: parse_campo_filtro(input.ordem, "ordem")?.unwrap_or_default(),
+        };
+        self.cliente_repository.list_clientes(filtro, page).await
     }
 
     pub async fn get_cliente_by_cpf(&self, cpf: Cpf) -> Result<Cliente, DomainError> {
-        let cliente_repository = self.cliente_repository.lock().await;
-        cliente_repository.get_cliente_by_cpf(cpf).await
+        self.cliente_repository.get_cliente_by_cpf(cpf).await
+    }
+
+    pub async fn get_cliente_by_id(&self, id: usize) -> Result<Cliente, DomainError> {
+        self.cliente_repository.get_cliente_by_id(id).await
     }
 
     pub async fn create_cliente(
         &self,
         cliente: CreateClienteInput,
     ) -> Result<Cliente, DomainError> {
-        let mut cliente_repository = self.cliente_repository.lock().await;
         let _id = 0;
-        let cpf = Cpf::new(cliente.cpf.clone())?;
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-        let cliente = cliente_repository
+        let (nome, email, cpf) = valida_dados_cliente(cliente)?;
+        check_cliente_unico(&*self.cliente_repository, Some(&cpf), Some(&email), None).await?;
+        let _now = Utc::now();
+        let cliente = self.cliente_repository
             .create_cliente(Cliente::new(
                 _id,
-                cliente.nome,
-                cliente.email,
+                nome,
+                email,
                 cpf,
-                _now.clone(),
+                _now,
                 _now,
             ))
             .await?;
@@ -60,48 +133,178 @@ impl ClienteUseCase {
         Ok(cliente.clone())
     }
 
+    pub async fn update_cliente(
+        &self,
+        cpf: Cpf,
+        dados_cliente: CreateClienteInput,
+    ) -> Result<Cliente, DomainError> {
+        self.patch_cliente(
+            cpf,
+            UpdateClienteInput {
+                nome: Some(dados_cliente.nome),
+                email: Some(dados_cliente.email),
+                cpf: Some(dados_cliente.cpf),
+            },
+        )
+        .await
+    }
+
+    // Atualização parcial: só os campos informados são alterados; id e
+    // data_criacao são sempre preservados
+    pub async fn patch_cliente(
+        &self,
+        cpf: Cpf,
+        dados_cliente: UpdateClienteInput,
+    ) -> Result<Cliente, DomainError> {
+        let mut violations = Violations::new();
+        if let Some(nome) = &dados_cliente.nome {
+            violations.check("nome", assert_field_not_empty("nome", nome));
+        }
+        let novo_email = dados_cliente.email.and_then(|email| violations.check("email", Email::new(email)));
+        let novo_cpf = dados_cliente.cpf.and_then(|cpf| violations.check("cpf", Cpf::new(cpf)));
+        violations.into_result()?;
+        let mut cliente = self.cliente_repository.get_cliente_by_cpf(cpf).await?;
+        check_cliente_unico(&*self.cliente_repository, novo_cpf.as_ref(), novo_email.as_ref(), Some(*cliente.id())).await?;
+        if let Some(nome) = dados_cliente.nome {
+            cliente.set_nome(nome)?;
+        }
+        if let Some(email) = novo_email {
+            cliente.set_email(email);
+        }
+        if let Some(cpf) = novo_cpf {
+            cliente.set_cpf(cpf);
+        }
+        let _now = Utc::now();
+        cliente.set_data_atualizacao(_now);
+        self.cliente_repository.update_cliente(cliente).await
+    }
+
+    pub async fn create_cliente_anonimo(&self) -> Result<Cliente, DomainError> {
+        let _now = Utc::now();
+        self.cliente_repository
+            .create_cliente(Cliente::anonimo(0, _now, _now))
+            .await
+    }
+
+    // Remove os clientes anônimos com mais de `ttl` segundos que não se
+    // identificaram
+    pub async fn purge_clientes_anonimos(&self, ttl: i64) -> Result<usize, DomainError> {
+        self.cliente_repository
+            .delete_clientes_anonimos(Utc::now() - Duration::seconds(ttl))
+            .await
+    }
+
+    pub async fn identifica_cliente_anonimo(
+        &self,
+        id: usize,
+        dados_cliente: CreateClienteInput,
+    ) -> Result<Cliente, DomainError> {
+        let (nome, email, cpf) = valida_dados_cliente(dados_cliente)?;
+        let mut cliente = self.cliente_repository.get_cliente_by_id(id).await?;
+        if !cliente.is_anonimo() {
+            return Err(DomainError::Conflict("Cliente já identificado".to_string()));
+        }
+        check_cliente_unico(&*self.cliente_repository, Some(&cpf), Some(&email), None).await?;
+        cliente.identifica(nome, email, cpf)?;
+        let _now = Utc::now();
+        cliente.set_data_atualizacao(_now);
+        self.cliente_repository.update_cliente(cliente).await
+    }
+
     pub async fn delete_cliente(&self, cpf: Cpf) -> Result<(), DomainError> {
-        let mut cliente_repository = self.cliente_repository.lock().await;
-        cliente_repository.delete_cliente(cpf).await?;
+        self.cliente_repository.delete_cliente(cpf).await?;
         Ok(())
     }
 }
 
-unsafe impl Send for ClienteUseCase {}
-unsafe impl Sync for ClienteUseCase {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::base::pagination::CampoOrdenacao;
     use mockall::predicate::*;
     use crate::entities::cliente::Cliente;
     use crate::traits::cliente_gateway::MockClienteGateway;
-    use tokio::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
+    use std::time::{Duration, Instant};
     use tokio;
+    use chrono::{DateTime, TimeZone, Utc};
 
-    #[tokio::test]
-    async fn test_get_clientes() {
+    fn data_fixa() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2021, 10, 10, 10, 0, 0).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_reads_are_not_serialized() {
         let mut mock = MockClienteGateway::new();
+        let chegadas = Arc::new(AtomicUsize::new(0));
 
-        let returned_cliente = Cliente::new(
-            1,
-            "nome".to_string(),
-            "email".to_string(),
-            Cpf::new("000.000.000-00".to_string()).unwrap(),
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
-        );
+        // Cada leitura só termina depois que a outra começou; se as chamadas
+        // ao gateway fossem serializadas, a primeira desistiria com erro
+        for id in [1, 2] {
+            let chegadas = chegadas.clone();
+            mock.expect_get_cliente_by_id()
+                .with(eq(id))
+                .times(1)
+                .returning(move |id| {
+                    chegadas.fetch_add(1, Ordering::SeqCst);
+                    let inicio = Instant::now();
+                    while chegadas.load(Ordering::SeqCst) < 2 {
+                        if inicio.elapsed() > Duration::from_secs(2) {
+                            return Err(DomainError::Invalid("leitura serializada".to_string()));
+                        }
+                        std::thread::sleep(Duration::from_millis(5));
+                    }
+                    Ok(Cliente::anonimo(id, data_fixa(), data_fixa()))
+                });
+        }
 
-        let expected_cliente = returned_cliente.clone();
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let primeira = tokio::spawn({
+            let use_case = use_case.clone();
+            async move { use_case.get_cliente_by_id(1).await }
+        });
+        let segunda = tokio::spawn({
+            let use_case = use_case.clone();
+            async move { use_case.get_cliente_by_id(2).await }
+        });
+
+        assert_eq!(primeira.await.unwrap().unwrap().id(), &1);
+        assert_eq!(segunda.await.unwrap().unwrap().id(), &2);
+    }
+
+    #[tokio::test]
+    async fn test_lista_clientes() {
+        let mut mock = MockClienteGateway::new();
 
-        mock.expect_get_clientes()
+        mock.expect_list_clientes()
             .times(1)
-            .returning(move || Ok(vec![returned_cliente.clone()]));
+            .withf(|filtro, page| {
+                filtro.nome_prefixo == Some("Ful".to_string())
+                    && filtro.ordenar_por == CampoOrdenacao::Nome
+                    && page == &PageRequest::default()
+            })
+            .returning(|_, _| Ok(Page { items: vec![], next_cursor: None }));
 
-        let use_case = ClienteUseCase::new(Arc::new(Mutex::new(mock)));
-        let result = use_case.get_clientes().await;
-        assert_eq!(result.unwrap()[0].id(), expected_cliente.id());
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let input = ListaClientesInput {
+            nome: Some("Ful".to_string()),
+            ordenar_por: Some("nome".to_string()),
+            ..Default::default()
+        };
+        let result = use_case.lista_clientes(input).await;
+        assert!(result.is_ok());
+
+        let input = ListaClientesInput {
+            criado_ate: Some("ontem".to_string()),
+            ..Default::default()
+        };
+        let result = use_case.lista_clientes(input).await;
+        assert!(
+            matches!(result, Err(DomainError::Invalid(ref campo)) if campo == "criado_ate"),
+            "Esperado Err(DomainError::Invalid(\"criado_ate\")), obtido {:?}",
+            result
+        );
     }
 
     #[tokio::test]
@@ -111,21 +314,21 @@ mod tests {
         let returned_cliente = Cliente::new(
             1,
             "nome".to_string(),
-            "email".to_string(),
-            Cpf::new("000.000.000-00".to_string()).unwrap(),
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Email::new("fulano@email.com".to_string()).unwrap(),
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
+            data_fixa(),
+            data_fixa(),
         );
 
         let expected_cliente = returned_cliente.clone();
 
         mock.expect_get_cliente_by_cpf()
             .times(1)
-            .with(eq(Cpf::new("000.000.000-00".to_string()).unwrap()))
+            .with(eq(Cpf::new("123.456.789-09".to_string()).unwrap()))
             .returning(move |_| Ok(returned_cliente.clone()));
 
-        let use_case = ClienteUseCase::new(Arc::new(Mutex::new(mock)));
-        let result = use_case.get_cliente_by_cpf(Cpf::new("000.000.000-00".to_string()).unwrap()).await;
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.get_cliente_by_cpf(Cpf::new("123.456.789-09".to_string()).unwrap()).await;
         assert_eq!(result.unwrap().id(), expected_cliente.id());
     }
 
@@ -136,39 +339,378 @@ mod tests {
         let returned_cliente = Cliente::new(
             1,
             "nome".to_string(),
-            "email".to_string(),
-            Cpf::new("000.000.000-00".to_string()).unwrap(),
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Email::new("fulano@email.com".to_string()).unwrap(),
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
+            data_fixa(),
+            data_fixa(),
         );
 
         let expected_cliente = returned_cliente.clone();
 
+        mock.expect_get_cliente_by_cpf()
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_get_cliente_by_email()
+            .returning(|_| Err(DomainError::NotFound));
         mock.expect_create_cliente()
             .times(1)
             .returning(move |_| Ok(returned_cliente.clone()));
 
-        let use_case = ClienteUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = ClienteUseCase::new(Arc::new(mock));
         let result = use_case.create_cliente(CreateClienteInput {
             nome: "nome".to_string(),
-            email: "email".to_string(),
-            cpf: "000.000.000-00".to_string(),
+            email: "fulano@email.com".to_string(),
+            cpf: "123.456.789-09".to_string(),
         }).await;
 
         assert_eq!(result.unwrap().id(), expected_cliente.id());
     }
 
+    #[tokio::test]
+    async fn test_create_cliente_invalid_email() {
+        let mut mock = MockClienteGateway::new();
+        mock.expect_create_cliente().never();
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.create_cliente(CreateClienteInput {
+            nome: "nome".to_string(),
+            email: "email".to_string(),
+            cpf: "123.456.789-09".to_string(),
+        }).await;
+
+        assert!(
+            matches!(&result, Err(DomainError::Validation(errors)) if errors.len() == 1 && errors[0].field == "email"),
+            "Esperado Err(DomainError::Validation) para email, obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_cliente_reports_all_violations() {
+        let mut mock = MockClienteGateway::new();
+        mock.expect_create_cliente().never();
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.create_cliente(CreateClienteInput {
+            nome: " ".to_string(),
+            email: "email".to_string(),
+            cpf: "123.456.789-00".to_string(),
+        }).await;
+
+        let errors = match result {
+            Err(DomainError::Validation(errors)) => errors,
+            other => panic!("Esperado Err(DomainError::Validation), obtido {:?}", other),
+        };
+        let campos: Vec<(&str, &str)> = errors
+            .iter()
+            .map(|error| (error.field.as_str(), error.code.as_str()))
+            .collect();
+        assert_eq!(
+            campos,
+            vec![("nome", "required"), ("email", "invalid_format"), ("cpf", "invalid_check_digits")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_cliente_duplicate_cpf() {
+        let mut mock = MockClienteGateway::new();
+
+        mock.expect_get_cliente_by_cpf()
+            .returning(|cpf| Ok(Cliente::new(
+                1,
+                "nome".to_string(),
+                Email::new("fulano@email.com".to_string()).unwrap(),
+                cpf,
+                data_fixa(),
+                data_fixa(),
+            )));
+        mock.expect_create_cliente().never();
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.create_cliente(CreateClienteInput {
+            nome: "nome".to_string(),
+            email: "fulano@email.com".to_string(),
+            cpf: "123.456.789-09".to_string(),
+        }).await;
+
+        assert!(
+            matches!(&result, Err(DomainError::AlreadyExists(field)) if field == "cpf"),
+            "Esperado Err(DomainError::AlreadyExists(\"cpf\")), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_cliente_duplicate_email() {
+        let mut mock = MockClienteGateway::new();
+
+        mock.expect_get_cliente_by_cpf()
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_get_cliente_by_email()
+            .with(eq(Email::new("Fulano@email.com".to_string()).unwrap()))
+            .returning(|_| Ok(Cliente::new(
+                1,
+                "nome".to_string(),
+                Email::new("fulano@email.com".to_string()).unwrap(),
+                Cpf::new("529.982.247-25".to_string()).unwrap(),
+                data_fixa(),
+                data_fixa(),
+            )));
+        mock.expect_create_cliente().never();
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.create_cliente(CreateClienteInput {
+            nome: "nome".to_string(),
+            email: "Fulano@Email.com".to_string(),
+            cpf: "123.456.789-09".to_string(),
+        }).await;
+
+        assert!(
+            matches!(&result, Err(DomainError::AlreadyExists(field)) if field == "email"),
+            "Esperado Err(DomainError::AlreadyExists(\"email\")), obtido {:?}",
+            result
+        );
+    }
+
     #[tokio::test]
     async fn test_delete_cliente() {
         let mut mock = MockClienteGateway::new();
 
         mock.expect_delete_cliente()
             .times(1)
-            .with(eq(Cpf::new("000.000.000-00".to_string()).unwrap()))
+            .with(eq(Cpf::new("123.456.789-09".to_string()).unwrap()))
             .returning(move |_| Ok(()));
 
-        let use_case = ClienteUseCase::new(Arc::new(Mutex::new(mock)));
-        let result = use_case.delete_cliente(Cpf::new("000.000.000-00".to_string()).unwrap()).await;
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.delete_cliente(Cpf::new("123.456.789-09".to_string()).unwrap()).await;
         assert_eq!(result.unwrap(), ());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_purge_clientes_anonimos() {
+        let mut mock = MockClienteGateway::new();
+
+        let limite = Utc::now() - chrono::Duration::seconds(3600);
+        mock.expect_delete_clientes_anonimos()
+            .times(1)
+            .withf(move |criado_ate| (*criado_ate - limite).num_seconds().abs() <= 1)
+            .returning(|_| Ok(3));
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        assert_eq!(use_case.purge_clientes_anonimos(3600).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_identifica_cliente_anonimo() {
+        let mut mock = MockClienteGateway::new();
+
+        mock.expect_get_cliente_by_id()
+            .times(1)
+            .with(eq(5))
+            .returning(|id| Ok(Cliente::anonimo(id, data_fixa(), data_fixa())));
+        mock.expect_get_cliente_by_cpf()
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_get_cliente_by_email()
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_update_cliente()
+            .times(1)
+            .returning(Ok);
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.identifica_cliente_anonimo(5, CreateClienteInput {
+            nome: "nome".to_string(),
+            email: "fulano@email.com".to_string(),
+            cpf: "123.456.789-09".to_string(),
+        }).await.unwrap();
+
+        assert_eq!(result.id(), &5);
+        assert!(!result.is_anonimo());
+        assert_eq!(result.data_criacao(), &data_fixa());
+    }
+
+    #[tokio::test]
+    async fn test_identifica_cliente_already_identified() {
+        let mut mock = MockClienteGateway::new();
+
+        mock.expect_get_cliente_by_id()
+            .returning(|id| Ok(Cliente::new(
+                id,
+                "nome".to_string(),
+                Email::new("fulano@email.com".to_string()).unwrap(),
+                Cpf::new("123.456.789-09".to_string()).unwrap(),
+                data_fixa(),
+                data_fixa(),
+            )));
+        mock.expect_update_cliente().never();
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.identifica_cliente_anonimo(5, CreateClienteInput {
+            nome: "nome".to_string(),
+            email: "fulano@email.com".to_string(),
+            cpf: "123.456.789-09".to_string(),
+        }).await;
+
+        assert!(
+            matches!(result, Err(DomainError::Conflict(_))),
+            "Esperado Err(DomainError::Conflict), obtido {:?}",
+            result
+        );
+    }
+
+    fn cliente_existente(cpf: Cpf) -> Cliente {
+        Cliente::new(
+            1,
+            "nome".to_string(),
+            Email::new("fulano@email.com".to_string()).unwrap(),
+            cpf,
+            data_fixa(),
+            data_fixa(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_update_cliente() {
+        let mut mock = MockClienteGateway::new();
+
+        mock.expect_get_cliente_by_cpf()
+            .times(1)
+            .with(eq(Cpf::new("123.456.789-09".to_string()).unwrap()))
+            .returning(|cpf| Ok(cliente_existente(cpf)));
+        mock.expect_get_cliente_by_cpf()
+            .with(eq(Cpf::new("529.982.247-25".to_string()).unwrap()))
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_get_cliente_by_email()
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_update_cliente()
+            .times(1)
+            .returning(Ok);
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.update_cliente(
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
+            CreateClienteInput {
+                nome: "novo nome".to_string(),
+                email: "novo@email.com".to_string(),
+                cpf: "529.982.247-25".to_string(),
+            },
+        ).await.unwrap();
+
+        assert_eq!(result.id(), &1);
+        assert_eq!(result.nome(), "novo nome");
+        assert_eq!(result.cpf(), &Some(Cpf::new("529.982.247-25".to_string()).unwrap()));
+        assert_eq!(result.data_criacao(), &data_fixa());
+        assert_ne!(result.data_atualizacao(), &data_fixa());
+    }
+
+    #[tokio::test]
+    async fn test_patch_cliente_keeps_missing_fields() {
+        let mut mock = MockClienteGateway::new();
+
+        mock.expect_get_cliente_by_cpf()
+            .times(1)
+            .returning(|cpf| Ok(cliente_existente(cpf)));
+        mock.expect_get_cliente_by_email()
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_update_cliente()
+            .times(1)
+            .returning(Ok);
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.patch_cliente(
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
+            UpdateClienteInput {
+                nome: None,
+                email: Some("novo@email.com".to_string()),
+                cpf: None,
+            },
+        ).await.unwrap();
+
+        assert_eq!(result.nome(), "nome");
+        assert_eq!(result.email(), &Some(Email::new("novo@email.com".to_string()).unwrap()));
+        assert_eq!(result.cpf(), &Some(Cpf::new("123.456.789-09".to_string()).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_patch_cliente_keeps_own_cpf() {
+        let mut mock = MockClienteGateway::new();
+
+        mock.expect_get_cliente_by_cpf()
+            .times(2)
+            .returning(|cpf| Ok(cliente_existente(cpf)));
+        mock.expect_update_cliente()
+            .times(1)
+            .returning(Ok);
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.patch_cliente(
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
+            UpdateClienteInput {
+                nome: None,
+                email: None,
+                cpf: Some("123.456.789-09".to_string()),
+            },
+        ).await;
+
+        assert!(result.is_ok(), "Esperado Ok, obtido {:?}", result);
+    }
+
+    #[tokio::test]
+    async fn test_patch_cliente_duplicate_email() {
+        let mut mock = MockClienteGateway::new();
+
+        mock.expect_get_cliente_by_cpf()
+            .returning(|cpf| Ok(cliente_existente(cpf)));
+        mock.expect_get_cliente_by_email()
+            .returning(|email| {
+                Ok(Cliente::new(
+                    2,
+                    "outro".to_string(),
+                    email,
+                    Cpf::new("529.982.247-25".to_string()).unwrap(),
+                    data_fixa(),
+                    data_fixa(),
+                ))
+            });
+        mock.expect_update_cliente().never();
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.patch_cliente(
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
+            UpdateClienteInput {
+                nome: None,
+                email: Some("outro@email.com".to_string()),
+                cpf: None,
+            },
+        ).await;
+
+        assert!(
+            matches!(&result, Err(DomainError::AlreadyExists(field)) if field == "email"),
+            "Esperado Err(DomainError::AlreadyExists(\"email\")), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_patch_cliente_not_found() {
+        let mut mock = MockClienteGateway::new();
+
+        mock.expect_get_cliente_by_cpf()
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_update_cliente().never();
+
+        let use_case = ClienteUseCase::new(Arc::new(mock));
+        let result = use_case.patch_cliente(
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
+            UpdateClienteInput {
+                nome: Some("nome".to_string()),
+                email: None,
+                cpf: None,
+            },
+        ).await;
+
+        assert!(
+            matches!(result, Err(DomainError::NotFound)),
+            "Esperado Err(DomainError::NotFound), obtido {:?}",
+            result
+        );
+    }
+}
@@ -4,12 +4,15 @@ use chrono::Utc;
 
 use schemars::JsonSchema;
 use serde::Deserialize;
-use tokio::sync::Mutex;
 
-use crate::base::domain_error::DomainError;
+use crate::base::assertion_concern::{assert_field_not_empty, Violations};
+use crate::base::domain_error::{DomainError, FieldError};
+use crate::base::pagination::{parse_campo_filtro, parse_data_filtro, Page, PageRequest};
 use crate::entities::cpf::Cpf;
+use crate::entities::email::Email;
 use crate::entities::usuario::{Status, Tipo, Usuario};
-use crate::traits::usuario_gateway::UsuarioGateway;
+use crate::traits::password_hasher::PasswordHasher;
+use crate::traits::usuario_gateway::{UsuarioFiltro, UsuarioGateway};
 
 #[derive(Clone, Debug, Deserialize, JsonSchema)]
 pub struct CreateUsuarioInput {
@@ -21,57 +24,216 @@ pub struct CreateUsuarioInput {
     status: String,
 }
 
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
+pub struct UpdateUsuarioInput {
+    nome: Option<String>,
+    email: Option<String>,
+    senha: Option<String>,
+    cpf: Option<String>,
+    tipo: Option<String>,
+    status: Option<String>,
+}
+
+// Hash argon2id com os parâmetros padrão, usado quando o CPF não existe
+const HASH_FICTICIO: &str =
+    "$argon2id$v=19$m=19456,t=2,p=1$NXYdlpsxC/M+mSuBSarygw$xgTo5aGbUx2q4HBpbCZdh453tcD0L8wISqzd9ECupZU";
+
+fn parse_tipo(tipo: &str) -> Result<Tipo, DomainError> {
+    tipo.parse().map_err(|_| {
+        DomainError::Validation(vec![FieldError::new("tipo", "invalid_value", "tipo deve ser Admin ou Cozinha")])
+    })
+}
+
+fn parse_status(status: &str) -> Result<Status, DomainError> {
+    status.parse().map_err(|_| {
+        DomainError::Validation(vec![FieldError::new("status", "invalid_value", "status deve ser Ativo ou Inativo")])
+    })
+}
+
+struct DadosUsuario {
+    nome: String,
+    email: Email,
+    senha: String,
+    cpf: Cpf,
+    tipo: Tipo,
+    status: Status,
+}
+
+// Valida todos os campos de uma vez, para que a resposta traga a lista
+// completa de violações
+fn valida_dados_usuario(dados_usuario: CreateUsuarioInput) -> Result<DadosUsuario, DomainError> {
+    let mut violations = Violations::new();
+    violations.check("nome", assert_field_not_empty("nome", &dados_usuario.nome));
+    violations.check("senha", assert_field_not_empty("senha", &dados_usuario.senha));
+    let email = violations.check("email", Email::new(dados_usuario.email));
+    let cpf = violations.check("cpf", Cpf::new(dados_usuario.cpf));
+    let tipo = violations.check("tipo", parse_tipo(&dados_usuario.tipo));
+    let status = violations.check("status", parse_status(&dados_usuario.status));
+    violations.into_result()?;
+    Ok(DadosUsuario {
+        nome: dados_usuario.nome,
+        email: email.unwrap(),
+        senha: dados_usuario.senha,
+        cpf: cpf.unwrap(),
+        tipo: tipo.unwrap(),
+        status: status.unwrap(),
+    })
+}
+
+// CPF e e-mail identificam o usuário; o e-mail é comparado sem diferenciar
+// maiúsculas e minúsculas. Numa atualização, `proprio_id` é o registro que
+// está sendo alterado e pode manter os próprios valores
+async fn check_usuario_unico(
+    usuario_repository: &(dyn UsuarioGateway + Sync + Send),
+    cpf: Option<&Cpf>,
+    email: Option<&Email>,
+    proprio_id: Option<usize>,
+) -> Result<(), DomainError> {
+    if let Some(cpf) = cpf {
+        match usuario_repository.get_usuario_by_cpf(cpf.clone()).await {
+            Ok(existente) if Some(*existente.id()) != proprio_id => {
+                return Err(DomainError::AlreadyExists("cpf".to_string()))
+            }
+            Ok(_) | Err(DomainError::NotFound) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    if let Some(email) = email {
+        match usuario_repository.get_usuario_by_email(email.clone()).await {
+            Ok(existente) if Some(*existente.id()) != proprio_id => {
+                return Err(DomainError::AlreadyExists("email".to_string()))
+            }
+            Ok(_) | Err(DomainError::NotFound) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, FromForm, JsonSchema)]
+pub struct ListaUsuariosInput {
+    limit: Option<usize>,
+    cursor: Option<String>,
+    nome: Option<String>,
+    email: Option<String>,
+    tipo: Option<String>,
+    status: Option<String>,
+    criado_de: Option<String>,
+    criado_ate: Option<String>,
+    ordenar_por: Option<String>,
+    ordem: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
+pub struct AlteraSenhaInput {
+    senha_atual: String,
+    nova_senha: String,
+}
+
+// Credenciais do primeiro administrador, vindas da configuração
+#[derive(Clone, Debug)]
+pub struct BootstrapAdminInput {
+    pub cpf: String,
+    pub email: String,
+    pub senha: String,
+}
+
 #[derive(Clone)]
 pub struct UsuarioUseCase {
-    usuario_repository: Arc<Mutex<dyn UsuarioGateway + Sync + Send>>,
+    usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+    password_hasher: Arc<dyn PasswordHasher + Sync + Send>,
 }
 
 impl UsuarioUseCase {
-    pub fn new(usuario_repository: Arc<Mutex<dyn UsuarioGateway + Sync + Send>>) -> Self {
-        UsuarioUseCase { usuario_repository }
+    pub fn new(
+        usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+        password_hasher: Arc<dyn PasswordHasher + Sync + Send>,
+    ) -> Self {
+        UsuarioUseCase { usuario_repository, password_hasher }
     }
 
-    pub async fn get_usuarios(&self) -> Result<Vec<Usuario>, DomainError> {
-        let usuario_repository = self.usuario_repository.lock().await;
-        usuario_repository.get_usuarios().await
+    pub async fn lista_usuarios(&self, input: ListaUsuariosInput) -> Result<Page<Usuario>, DomainError> {
+        let page = PageRequest::new(input.limit, input.cursor)?;
+        let filtro = UsuarioFiltro {
+            nome_prefixo: input.nome,
+            email: input.email.map(Email::new).transpose()?,
+            tipo: parse_campo_filtro(input.tipo, "tipo")?,
+            status: parse_campo_filtro(input.status, "status")?,
+            criado_de: parse_data_filtro(input.criado_de, "criado_de")?,
+            criado_ate: parse_data_filtro(input.criado_ate, "criado_ate")?,
+            ordenar_por: parse_campo_filtro(input.ordenar_por, "ordenar_por")?.unwrap_or_default(),
+            ordem: parse_campo_filtro(input.ordem, "ordem")?.unwrap_or_default(),
+        };
+        self.usuario_repository.list_usuarios(filtro, page).await
     }
 
     pub async fn get_usuario_by_id(&self, id: usize) -> Result<Usuario, DomainError> {
-        let usuario_repository = self.usuario_repository.lock().await;
-        usuario_repository.get_usuario_by_id(id).await
+        self.usuario_repository.get_usuario_by_id(id).await
     }
 
     pub async fn get_usuario_by_cpf(&self, cpf: Cpf) -> Result<Usuario, DomainError> {
-        let usuario_repository = self.usuario_repository.lock().await;
-        usuario_repository.get_usuario_by_cpf(cpf).await
+        self.usuario_repository.get_usuario_by_cpf(cpf).await
+    }
+
+    pub async fn get_usuario_by_email(&self, email: Email) -> Result<Usuario, DomainError> {
+        self.usuario_repository.get_usuario_by_email(email).await
     }
 
     pub async fn create_usuario(
         &self,
         usuario: CreateUsuarioInput,
     ) -> Result<Usuario, DomainError> {
-        let mut usuario_repository = self.usuario_repository.lock().await;
+        let usuario = valida_dados_usuario(usuario)?;
+        self.cria_usuario(usuario, false).await
+    }
+
+    async fn cria_usuario(&self, usuario: DadosUsuario, troca_senha_obrigatoria: bool) -> Result<Usuario, DomainError> {
         let _id = 0;
-        let valid_cpf = Cpf::new(usuario.cpf.clone())?;
-        let valid_tipo: Tipo = usuario.tipo.parse().unwrap();
-        let valid_status: Status = usuario.status.parse().unwrap();
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-
-        let usuario = usuario_repository
-            .create_usuario(Usuario::new(
-                _id,
-                usuario.nome,
-                usuario.email,
-                valid_cpf,
-                usuario.senha,
-                valid_tipo,
-                valid_status,
-                _now.clone(),
-                _now,
-            ))
-            .await?;
+        check_usuario_unico(&*self.usuario_repository, Some(&usuario.cpf), Some(&usuario.email), None).await?;
+        let senha_hash = self.password_hasher.hash(&usuario.senha)?;
+        let _now = Utc::now();
+
+        let mut usuario = Usuario::new(
+            _id,
+            usuario.nome,
+            usuario.email,
+            usuario.cpf,
+            senha_hash,
+            usuario.tipo,
+            usuario.status,
+            _now,
+            _now,
+        );
+        usuario.set_troca_senha_obrigatoria(troca_senha_obrigatoria);
+        self.usuario_repository.create_usuario(usuario).await
+    }
+
+    // Cria o administrador inicial só quando ainda não existe nenhum ativo;
+    // um admin inativo não consegue entrar para recuperar o acesso. A senha
+    // vem da configuração, então o primeiro login exige a troca
+    pub async fn existe_admin(&self) -> Result<bool, DomainError> {
+        let filtro = UsuarioFiltro {
+            tipo: Some(Tipo::Admin),
+            status: Some(Status::Ativo),
+            ..Default::default()
+        };
+        let admins = self.usuario_repository.list_usuarios(filtro, PageRequest { limit: 1, offset: 0 }).await?;
+        Ok(!admins.items.is_empty())
+    }
 
-        Ok(usuario.clone())
+    pub async fn bootstrap_admin(&self, admin: BootstrapAdminInput) -> Result<Option<Usuario>, DomainError> {
+        if self.existe_admin().await? {
+            return Ok(None);
+        }
+        let usuario = valida_dados_usuario(CreateUsuarioInput {
+            nome: "Administrador".to_string(),
+            email: admin.email,
+            senha: admin.senha,
+            cpf: admin.cpf,
+            tipo: Tipo::Admin.to_string(),
+            status: "Ativo".to_string(),
+        })?;
+        self.cria_usuario(usuario, true).await.map(Some)
     }
 
     pub async fn update_usuario(
@@ -79,71 +241,205 @@ impl UsuarioUseCase {
         id: usize,
         usuario: CreateUsuarioInput,
     ) -> Result<Usuario, DomainError> {
-        let mut usuario_repository = self.usuario_repository.lock().await;
-
-        let valid_cpf = Cpf::new(usuario.cpf.clone())?;
-        let valid_tipo: Tipo = usuario.tipo.parse().unwrap();
-        let valid_status: Status = usuario.status.parse().unwrap();
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-
-        let usuario = usuario_repository
-            .update_usuario(Usuario::new(
-                id,
-                usuario.nome,
-                usuario.email,
-                valid_cpf,
-                usuario.senha,
-                valid_tipo,
-                valid_status,
-                _now.clone(),
-                _now,
-            ))
-            .await?;
+        self.patch_usuario(
+            id,
+            UpdateUsuarioInput {
+                nome: Some(usuario.nome),
+                email: Some(usuario.email),
+                senha: Some(usuario.senha),
+                cpf: Some(usuario.cpf),
+                tipo: Some(usuario.tipo),
+                status: Some(usuario.status),
+            },
+        )
+        .await
+    }
 
-        Ok(usuario.clone())
+    // Atualização parcial: só os campos informados são alterados; id e
+    // data_criacao são sempre preservados e a senha só é recalculada quando
+    // informada
+    pub async fn patch_usuario(
+        &self,
+        id: usize,
+        dados_usuario: UpdateUsuarioInput,
+    ) -> Result<Usuario, DomainError> {
+        let mut violations = Violations::new();
+        if let Some(nome) = &dados_usuario.nome {
+            violations.check("nome", assert_field_not_empty("nome", nome));
+        }
+        if let Some(senha) = &dados_usuario.senha {
+            violations.check("senha", assert_field_not_empty("senha", senha));
+        }
+        let novo_email = dados_usuario.email.and_then(|email| violations.check("email", Email::new(email)));
+        let novo_cpf = dados_usuario.cpf.and_then(|cpf| violations.check("cpf", Cpf::new(cpf)));
+        let novo_tipo = dados_usuario.tipo.and_then(|tipo| violations.check("tipo", parse_tipo(&tipo)));
+        let novo_status = dados_usuario.status.and_then(|status| violations.check("status", parse_status(&status)));
+        violations.into_result()?;
+        let mut usuario = self.usuario_repository.get_usuario_by_id(id).await?;
+        check_usuario_unico(&*self.usuario_repository, novo_cpf.as_ref(), novo_email.as_ref(), Some(id)).await?;
+        if let Some(nome) = dados_usuario.nome {
+            usuario.set_nome(nome)?;
+        }
+        if let Some(email) = novo_email {
+            usuario.set_email(email);
+        }
+        if let Some(cpf) = novo_cpf {
+            usuario.set_cpf(cpf);
+        }
+        if let Some(tipo) = novo_tipo {
+            usuario.set_tipo(tipo);
+        }
+        if let Some(status) = novo_status {
+            usuario.set_status(status);
+        }
+        if let Some(senha) = dados_usuario.senha {
+            usuario.set_senha(self.password_hasher.hash(&senha)?)?;
+        }
+        let _now = Utc::now();
+        usuario.set_data_atualizacao(_now);
+        self.usuario_repository.update_usuario(usuario).await
+    }
+
+    pub fn validate_senha(&self, usuario: &Usuario, senha: &str) -> bool {
+        self.password_hasher.verify(senha, usuario.senha())
+    }
+
+    // Gasta o mesmo tempo de validate_senha quando o usuário não existe, para
+    // que o tempo de resposta não revele quais CPFs estão cadastrados
+    pub fn simula_validacao_senha(&self, senha: &str) {
+        self.password_hasher.verify(senha, HASH_FICTICIO);
+    }
+
+    pub async fn upgrade_senha(&self, mut usuario: Usuario, senha: &str) -> Result<Usuario, DomainError> {
+        if !self.password_hasher.needs_rehash(usuario.senha()) {
+            return Ok(usuario);
+        }
+        usuario.set_senha(self.password_hasher.hash(senha)?)?;
+        self.usuario_repository.update_usuario(usuario).await
+    }
+
+    // Troca a senha a pedido do próprio usuário e libera o login quando a
+    // troca era obrigatória
+    pub async fn troca_senha(&self, mut usuario: Usuario, nova_senha: String) -> Result<Usuario, DomainError> {
+        assert_field_not_empty("nova_senha", &nova_senha)?;
+        if self.password_hasher.verify(&nova_senha, usuario.senha()) {
+            return Err(DomainError::Validation(vec![FieldError::new(
+                "nova_senha",
+                "same_as_current",
+                "nova_senha deve ser diferente da senha atual",
+            )]));
+        }
+        usuario.set_senha(self.password_hasher.hash(&nova_senha)?)?;
+        usuario.set_troca_senha_obrigatoria(false);
+        usuario.set_data_atualizacao(Utc::now());
+        self.usuario_repository.update_usuario(usuario).await
+    }
+
+    // Troca feita pelo próprio usuário logado, que confirma a senha atual.
+    // Sem limite de tentativas, a rota vira um teste de senhas para quem tem
+    // o token, então a conferência é separada da troca para o controller
+    // contar as falhas
+    pub fn confere_senha_atual(&self, usuario: &Usuario, input: &AlteraSenhaInput) -> Result<(), DomainError> {
+        if !self.validate_senha(usuario, &input.senha_atual) {
+            return Err(DomainError::Validation(vec![FieldError::new(
+                "senha_atual",
+                "invalid",
+                "senha_atual não confere",
+            )]));
+        }
+        Ok(())
+    }
+
+    // Espera a senha atual já conferida por confere_senha_atual
+    pub async fn altera_senha(&self, usuario: Usuario, input: AlteraSenhaInput) -> Result<Usuario, DomainError> {
+        self.troca_senha(usuario, input.nova_senha).await
     }
 
     pub async fn delete_usuario(&self, cpf: Cpf) -> Result<(), DomainError> {
-        let mut usuario_repository = self.usuario_repository.lock().await;
-        usuario_repository.delete_usuario(cpf).await?;
+        self.usuario_repository.delete_usuario(cpf).await?;
         Ok(())
     }
 }
 
-unsafe impl Send for UsuarioUseCase {}
-unsafe impl Sync for UsuarioUseCase {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::base::pagination::{CampoOrdenacao, Ordem};
+    use crate::traits::password_hasher::MockPasswordHasher;
     use crate::traits::usuario_gateway::MockUsuarioGateway;
+    use mockall::predicate::*;
     use tokio;
+    use chrono::{DateTime, TimeZone, Utc};
 
-    #[tokio::test]
-    async fn test_get_usuarios() {
-        let mut mock = MockUsuarioGateway::new();
+    fn data_fixa() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2021, 10, 10, 10, 0, 0).unwrap()
+    }
 
-        let returned_usuario = Usuario::new(
-            1,
-            "nome".to_string(),
-            "email".to_string(),
-            Cpf::new("000.000.000-00".to_string()).unwrap(),
-            "senha".to_string(),
-            Tipo::Cozinha,
-            Status::Ativo,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
-        );
+    fn password_hasher() -> Arc<MockPasswordHasher> {
+        let mut hasher = MockPasswordHasher::new();
+        hasher.expect_hash().returning(|senha| Ok(format!("hash:{}", senha)));
+        hasher.expect_verify().returning(|senha, hash| format!("hash:{}", senha) == hash);
+        hasher.expect_needs_rehash().returning(|hash| !hash.starts_with("hash:"));
+        Arc::new(hasher)
+    }
 
-        let expected_usuario = returned_usuario.clone();
+    #[tokio::test]
+    async fn test_lista_usuarios_parses_filters() {
+        let mut mock = MockUsuarioGateway::new();
 
-        mock.expect_get_usuarios()
+        mock.expect_list_usuarios()
             .times(1)
-            .returning(move || Ok(vec![returned_usuario.clone()]));
+            .withf(|filtro, page| {
+                filtro.tipo == Some(Tipo::Admin)
+                    && filtro.status.is_none()
+                    && filtro.email == Some(Email::new("admin@exemplo.com".to_string()).unwrap())
+                    && filtro.ordenar_por == CampoOrdenacao::DataCriacao
+                    && filtro.ordem == Ordem::Desc
+                    && filtro.criado_de.is_some()
+                    && page.limit == 10
+            })
+            .returning(|_, _| Ok(Page { items: vec![], next_cursor: None }));
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let input = ListaUsuariosInput {
+            limit: Some(10),
+            email: Some(" admin@exemplo.com ".to_string()),
+            tipo: Some("Admin".to_string()),
+            criado_de: Some("2024-01-01T00:00:00Z".to_string()),
+            ordenar_por: Some("data_criacao".to_string()),
+            ordem: Some("desc".to_string()),
+            ..Default::default()
+        };
+        let result = use_case.lista_usuarios(input).await;
+        assert!(result.unwrap().items.is_empty());
+    }
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
-        let result = use_case.get_usuarios().await;
-        assert_eq!(result.unwrap()[0].id(), expected_usuario.id());
+    #[tokio::test]
+    async fn test_lista_usuarios_rejects_invalid_filter() {
+        let mock = MockUsuarioGateway::new();
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+
+        let input = ListaUsuariosInput {
+            tipo: Some("Gerente".to_string()),
+            ..Default::default()
+        };
+        let result = use_case.lista_usuarios(input).await;
+        assert!(
+            matches!(result, Err(DomainError::Invalid(ref campo)) if campo == "tipo"),
+            "Esperado Err(DomainError::Invalid(\"tipo\")), obtido {:?}",
+            result
+        );
+
+        let input = ListaUsuariosInput {
+            cursor: Some("nao-e-um-cursor".to_string()),
+            ..Default::default()
+        };
+        let result = use_case.lista_usuarios(input).await;
+        assert!(
+            matches!(result, Err(DomainError::Invalid(ref campo)) if campo == "cursor"),
+            "Esperado Err(DomainError::Invalid(\"cursor\")), obtido {:?}",
+            result
+        );
     }
 
     #[tokio::test]
@@ -153,13 +449,13 @@ mod tests {
         let returned_usuario = Usuario::new(
             1,
             "nome".to_string(),
-            "email".to_string(),
-            Cpf::new("000.000.000-00".to_string()).unwrap(),
+            Email::new("fulano@email.com".to_string()).unwrap(),
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
             "senha".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            data_fixa(),
+            data_fixa(),
         );
 
         let expected_usuario = returned_usuario.clone();
@@ -168,7 +464,7 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(returned_usuario.clone()));
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
         let result = use_case.get_usuario_by_id(1).await;
         assert_eq!(result.unwrap().id(), expected_usuario.id());
     }
@@ -180,13 +476,13 @@ mod tests {
         let returned_usuario = Usuario::new(
             1,
             "nome".to_string(),
-            "email".to_string(),
-            Cpf::new("000.000.000-00".to_string()).unwrap(),
+            Email::new("fulano@email.com".to_string()).unwrap(),
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
             "senha".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            data_fixa(),
+            data_fixa(),
         );
 
         let expected_usuario = returned_usuario.clone();
@@ -195,9 +491,9 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(returned_usuario.clone()));
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
         let result = use_case
-            .get_usuario_by_cpf(Cpf::new("000.000.000-00".to_string()).unwrap())
+            .get_usuario_by_cpf(Cpf::new("123.456.789-09".to_string()).unwrap())
             .await;
         assert_eq!(result.unwrap().id(), expected_usuario.id());
     }
@@ -209,28 +505,33 @@ mod tests {
         let returned_usuario = Usuario::new(
             1,
             "nome".to_string(),
-            "email".to_string(),
-            Cpf::new("000.000.000-00".to_string()).unwrap(),
+            Email::new("fulano@email.com".to_string()).unwrap(),
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
             "senha".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            data_fixa(),
+            data_fixa(),
         );
 
         let expected_usuario = returned_usuario.clone();
 
+        mock.expect_get_usuario_by_cpf()
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_get_usuario_by_email()
+            .returning(|_| Err(DomainError::NotFound));
         mock.expect_create_usuario()
             .times(1)
+            .withf(|usuario| usuario.senha() == "hash:senha")
             .returning(move |_| Ok(returned_usuario.clone()));
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
         let result = use_case
             .create_usuario(CreateUsuarioInput {
                 nome: "nome".to_string(),
-                email: "email".to_string(),
+                email: "fulano@email.com".to_string(),
                 senha: "senha".to_string(),
-                cpf: "000.000.000-00".to_string(),
+                cpf: "123.456.789-09".to_string(),
                 tipo: "Admin".to_string(),
                 status: "Ativo".to_string(),
             })
@@ -238,43 +539,307 @@ mod tests {
         assert_eq!(result.unwrap().id(), expected_usuario.id());
     }
 
+    fn create_usuario_input(cpf: &str, email: &str) -> CreateUsuarioInput {
+        CreateUsuarioInput {
+            nome: "nome".to_string(),
+            email: email.to_string(),
+            senha: "senha".to_string(),
+            cpf: cpf.to_string(),
+            tipo: "Cozinha".to_string(),
+            status: "Ativo".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_usuario_duplicate_cpf() {
+        let mut mock = MockUsuarioGateway::new();
+
+        mock.expect_get_usuario_by_cpf().returning(|cpf| {
+            Ok(Usuario::new(
+                1,
+                "nome".to_string(),
+                Email::new("fulano@email.com".to_string()).unwrap(),
+                cpf,
+                "senha".to_string(),
+                Tipo::Cozinha,
+                Status::Ativo,
+                data_fixa(),
+                data_fixa(),
+            ))
+        });
+        mock.expect_create_usuario().never();
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let result = use_case.create_usuario(create_usuario_input("123.456.789-09", "fulano@email.com")).await;
+        assert!(
+            matches!(&result, Err(DomainError::AlreadyExists(field)) if field == "cpf"),
+            "Esperado Err(DomainError::AlreadyExists(\"cpf\")), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_usuario_duplicate_email() {
+        let mut mock = MockUsuarioGateway::new();
+
+        mock.expect_get_usuario_by_cpf()
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_get_usuario_by_email()
+            .with(eq(Email::new("fulano@email.com".to_string()).unwrap()))
+            .returning(|email| {
+                Ok(Usuario::new(
+                    1,
+                    "nome".to_string(),
+                    Email(email.0.to_uppercase()),
+                    Cpf::new("529.982.247-25".to_string()).unwrap(),
+                    "senha".to_string(),
+                    Tipo::Cozinha,
+                    Status::Ativo,
+                    data_fixa(),
+                    data_fixa(),
+                ))
+            });
+        mock.expect_create_usuario().never();
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let result = use_case.create_usuario(create_usuario_input("123.456.789-09", " fulano@email.com ")).await;
+        assert!(
+            matches!(&result, Err(DomainError::AlreadyExists(field)) if field == "email"),
+            "Esperado Err(DomainError::AlreadyExists(\"email\")), obtido {:?}",
+            result
+        );
+    }
+
     #[tokio::test]
     async fn test_update_usuario() {
         let mut mock = MockUsuarioGateway::new();
 
-        let returned_usuario = Usuario::new(
+        let existing_usuario = Usuario::new(
             1,
             "nome".to_string(),
-            "email".to_string(),
-            Cpf::new("000.000.000-00".to_string()).unwrap(),
+            Email::new("fulano@email.com".to_string()).unwrap(),
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
             "senha".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            data_fixa(),
+            data_fixa(),
         );
 
-        let expected_usuario = returned_usuario.clone();
-
+        let mesmo_usuario = existing_usuario.clone();
+        mock.expect_get_usuario_by_id()
+            .with(eq(1))
+            .times(1)
+            .returning(move |_| Ok(existing_usuario.clone()));
+        mock.expect_get_usuario_by_cpf()
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_get_usuario_by_email()
+            .returning(move |_| Ok(mesmo_usuario.clone()));
         mock.expect_update_usuario()
             .times(1)
-            .returning(move |_| Ok(returned_usuario.clone()));
+            .withf(|usuario| usuario.senha() == "hash:senha")
+            .returning(Ok);
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
         let result = use_case
             .update_usuario(
                 1,
                 CreateUsuarioInput {
                     nome: "nome".to_string(),
-                    email: "email".to_string(),
+                    email: "fulano@email.com".to_string(),
                     senha: "senha".to_string(),
-                    cpf: "000.000.000-00".to_string(),
+                    cpf: "123.456.789-09".to_string(),
                     tipo: "Cozinha".to_string(),
                     status: "Ativo".to_string(),
                 },
             )
+            .await
+            .unwrap();
+        assert_eq!(result.id(), &1);
+        assert_eq!(result.tipo(), &Tipo::Cozinha);
+        assert_eq!(result.data_criacao(), &data_fixa());
+        assert!(result.data_atualizacao() > &data_fixa());
+    }
+
+    fn usuario_existente(id: usize) -> Usuario {
+        Usuario::new(
+            id,
+            "nome".to_string(),
+            Email::new("fulano@email.com".to_string()).unwrap(),
+            Cpf::new("529.982.247-25".to_string()).unwrap(),
+            "hash:senha".to_string(),
+            Tipo::Cozinha,
+            Status::Ativo,
+            data_fixa(),
+            data_fixa(),
+        )
+    }
+
+    fn update_usuario_input() -> UpdateUsuarioInput {
+        UpdateUsuarioInput {
+            nome: None,
+            email: None,
+            senha: None,
+            cpf: None,
+            tipo: None,
+            status: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_patch_usuario_keeps_missing_fields() {
+        let mut mock = MockUsuarioGateway::new();
+        mock.expect_get_usuario_by_id()
+            .times(1)
+            .returning(|id| Ok(usuario_existente(id)));
+        mock.expect_update_usuario()
+            .times(1)
+            .returning(Ok);
+
+        let mut hasher = MockPasswordHasher::new();
+        hasher.expect_hash().never();
+        let use_case = UsuarioUseCase::new(Arc::new(mock), Arc::new(hasher));
+        let result = use_case
+            .patch_usuario(
+                7,
+                UpdateUsuarioInput {
+                    status: Some("Inativo".to_string()),
+                    ..update_usuario_input()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.id(), &7);
+        assert_eq!(result.nome(), "nome");
+        assert_eq!(result.senha(), "hash:senha");
+        assert_eq!(result.status(), &Status::Inativo);
+        assert_eq!(result.data_criacao(), &data_fixa());
+    }
+
+    #[tokio::test]
+    async fn test_patch_usuario_rehashes_senha() {
+        let mut mock = MockUsuarioGateway::new();
+        mock.expect_get_usuario_by_id()
+            .times(1)
+            .returning(|id| Ok(usuario_existente(id)));
+        mock.expect_update_usuario()
+            .times(1)
+            .returning(Ok);
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let result = use_case
+            .patch_usuario(
+                7,
+                UpdateUsuarioInput {
+                    senha: Some("nova_senha".to_string()),
+                    ..update_usuario_input()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.senha(), "hash:nova_senha");
+    }
+
+    #[tokio::test]
+    async fn test_patch_usuario_duplicate_cpf() {
+        let mut mock = MockUsuarioGateway::new();
+        mock.expect_get_usuario_by_id()
+            .returning(|id| Ok(usuario_existente(id)));
+        mock.expect_get_usuario_by_cpf()
+            .with(eq(Cpf::new("123.456.789-09".to_string()).unwrap()))
+            .returning(|_| Ok(usuario_existente(8)));
+        mock.expect_update_usuario().never();
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let result = use_case
+            .patch_usuario(
+                7,
+                UpdateUsuarioInput {
+                    cpf: Some("123.456.789-09".to_string()),
+                    ..update_usuario_input()
+                },
+            )
             .await;
-        assert_eq!(result.unwrap().id(), expected_usuario.id());
+        assert!(
+            matches!(&result, Err(DomainError::AlreadyExists(field)) if field == "cpf"),
+            "Esperado Err(DomainError::AlreadyExists(\"cpf\")), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_patch_usuario_duplicate_email() {
+        let mut mock = MockUsuarioGateway::new();
+        mock.expect_get_usuario_by_id()
+            .returning(|id| Ok(usuario_existente(id)));
+        mock.expect_get_usuario_by_email()
+            .returning(|_| Ok(usuario_existente(8)));
+        mock.expect_update_usuario().never();
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let result = use_case
+            .patch_usuario(
+                7,
+                UpdateUsuarioInput {
+                    email: Some("OUTRO@email.com".to_string()),
+                    ..update_usuario_input()
+                },
+            )
+            .await;
+        assert!(
+            matches!(&result, Err(DomainError::AlreadyExists(field)) if field == "email"),
+            "Esperado Err(DomainError::AlreadyExists(\"email\")), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_patch_usuario_not_found() {
+        let mut mock = MockUsuarioGateway::new();
+        mock.expect_get_usuario_by_id()
+            .times(1)
+            .returning(|_| Err(DomainError::NotFound));
+        mock.expect_update_usuario().never();
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let result = use_case
+            .patch_usuario(
+                42,
+                UpdateUsuarioInput {
+                    nome: Some("outro".to_string()),
+                    ..update_usuario_input()
+                },
+            )
+            .await;
+        assert!(
+            matches!(result, Err(DomainError::NotFound)),
+            "Esperado Err(DomainError::NotFound), obtido {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_patch_usuario_invalid_tipo() {
+        let mut mock = MockUsuarioGateway::new();
+        mock.expect_get_usuario_by_id().never();
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let result = use_case
+            .patch_usuario(
+                1,
+                UpdateUsuarioInput {
+                    tipo: Some("Gerente".to_string()),
+                    ..update_usuario_input()
+                },
+            )
+            .await;
+        assert!(
+            matches!(&result, Err(DomainError::Validation(errors)) if errors[0].field == "tipo"),
+            "Esperado Err(DomainError::Validation) para tipo, obtido {:?}",
+            result
+        );
     }
 
     #[tokio::test]
@@ -285,10 +850,155 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(()));
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
         let result = use_case
-            .delete_usuario(Cpf::new("000.000.000-00".to_string()).unwrap())
+            .delete_usuario(Cpf::new("123.456.789-09".to_string()).unwrap())
             .await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_upgrade_senha_legacy_plaintext() {
+        let mut mock = MockUsuarioGateway::new();
+
+        let usuario = Usuario::new(
+            1,
+            "nome".to_string(),
+            Email::new("fulano@email.com".to_string()).unwrap(),
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
+            "senha".to_string(),
+            Tipo::Admin,
+            Status::Ativo,
+            data_fixa(),
+            data_fixa(),
+        );
+
+        mock.expect_update_usuario()
+            .times(1)
+            .withf(|usuario| usuario.senha() == "hash:senha")
+            .returning(Ok);
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let result = use_case.upgrade_senha(usuario, "senha").await;
+        assert_eq!(result.unwrap().senha(), "hash:senha");
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_senha_already_hashed() {
+        let mut mock = MockUsuarioGateway::new();
+
+        let usuario = Usuario::new(
+            1,
+            "nome".to_string(),
+            Email::new("fulano@email.com".to_string()).unwrap(),
+            Cpf::new("123.456.789-09".to_string()).unwrap(),
+            "hash:senha".to_string(),
+            Tipo::Admin,
+            Status::Ativo,
+            data_fixa(),
+            data_fixa(),
+        );
+
+        mock.expect_update_usuario().times(0);
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let result = use_case.upgrade_senha(usuario, "senha").await;
+        assert_eq!(result.unwrap().senha(), "hash:senha");
+    }
+
+    fn bootstrap_admin_input() -> BootstrapAdminInput {
+        BootstrapAdminInput {
+            cpf: "12345678909".to_string(),
+            email: "admin@exemplo.com".to_string(),
+            senha: "provisoria".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_admin_creates_first_admin() {
+        let mut mock = MockUsuarioGateway::new();
+
+        mock.expect_list_usuarios()
+            .times(1)
+            .withf(|filtro, page| {
+                filtro.tipo == Some(Tipo::Admin) && filtro.status == Some(Status::Ativo) && page.limit == 1
+            })
+            .returning(|_, _| Ok(Page { items: vec![], next_cursor: None }));
+        mock.expect_get_usuario_by_cpf().returning(|_| Err(DomainError::NotFound));
+        mock.expect_get_usuario_by_email().returning(|_| Err(DomainError::NotFound));
+        mock.expect_create_usuario().times(1).returning(Ok);
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let admin = use_case.bootstrap_admin(bootstrap_admin_input()).await.unwrap().unwrap();
+        assert_eq!(admin.tipo(), &Tipo::Admin);
+        assert_eq!(admin.cpf().formatted(), "123.456.789-09");
+        assert_eq!(admin.senha(), "hash:provisoria");
+        assert!(admin.troca_senha_obrigatoria());
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_admin_skips_when_admin_exists() {
+        let mut mock = MockUsuarioGateway::new();
+
+        mock.expect_list_usuarios().times(1).returning(|_, _| {
+            let mut admin = usuario_existente(1);
+            admin.set_tipo(Tipo::Admin);
+            Ok(Page { items: vec![admin], next_cursor: None })
+        });
+        mock.expect_create_usuario().times(0);
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let result = use_case.bootstrap_admin(bootstrap_admin_input()).await;
+        assert!(matches!(result, Ok(None)), "Esperado Ok(None), obtido {:?}", result);
+    }
+
+    #[tokio::test]
+    async fn test_troca_senha_requires_nova_senha() {
+        let mut mock = MockUsuarioGateway::new();
+        mock.expect_update_usuario().times(0);
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let result = use_case.troca_senha(usuario_existente(1), " ".to_string()).await;
+        assert!(
+            matches!(result, Err(DomainError::Validation(ref erros)) if erros[0].field == "nova_senha"),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_confere_senha_atual() {
+        let use_case = UsuarioUseCase::new(Arc::new(MockUsuarioGateway::new()), password_hasher());
+        let input = AlteraSenhaInput {
+            senha_atual: "errada".to_string(),
+            nova_senha: "nova".to_string(),
+        };
+        let result = use_case.confere_senha_atual(&usuario_existente(1), &input);
+        assert!(
+            matches!(result, Err(DomainError::Validation(ref erros)) if erros[0].field == "senha_atual"),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
+            result
+        );
+
+        let input = AlteraSenhaInput {
+            senha_atual: "senha".to_string(),
+            nova_senha: "nova".to_string(),
+        };
+        assert!(use_case.confere_senha_atual(&usuario_existente(1), &input).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_altera_senha() {
+        let mut mock = MockUsuarioGateway::new();
+        mock.expect_update_usuario().times(1).returning(Ok);
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock), password_hasher());
+        let input = AlteraSenhaInput {
+            senha_atual: "senha".to_string(),
+            nova_senha: "nova".to_string(),
+        };
+        let usuario = use_case.altera_senha(usuario_existente(1), input).await.unwrap();
+        assert_eq!(usuario.senha(), "hash:nova");
+        assert_eq!(usuario.data_criacao(), &data_fixa());
+    }
 }